@@ -31,21 +31,47 @@ pub fn run() {
             ytdlp::commands::select_download_directory,
             ytdlp::commands::get_available_browsers,
             ytdlp::commands::get_download_history,
+            ytdlp::commands::get_history_extractors,
+            ytdlp::commands::get_download_statistics,
             ytdlp::commands::check_duplicate,
             ytdlp::commands::delete_history_item,
             ytdlp::commands::get_active_downloads,
             ytdlp::commands::get_download_queue_paginated,
             ytdlp::commands::get_queue_summary,
+            ytdlp::commands::reverify_library,
+            ytdlp::commands::find_duplicate_files,
+            ytdlp::commands::remove_duplicate_files,
+            ytdlp::commands::reconcile_library,
+            ytdlp::commands::move_library,
+            ytdlp::commands::import_library_folder,
             ytdlp::metadata::validate_url,
+            ytdlp::metadata::classify_url,
             ytdlp::metadata::fetch_video_info,
+            ytdlp::metadata::fetch_video_info_batch,
+            ytdlp::metadata::cancel_metadata_batch,
             ytdlp::metadata::fetch_playlist_info,
+            ytdlp::metadata::stream_playlist_info,
+            ytdlp::metadata::cancel_playlist_fetch,
+            ytdlp::metadata::list_channel_tabs,
+            ytdlp::metadata::fetch_channel_tab,
             ytdlp::metadata::fetch_quick_metadata,
+            ytdlp::metadata::clear_metadata_cache,
             ytdlp::download::start_download,
             ytdlp::download::add_to_queue,
+            ytdlp::download::add_to_queue_batch,
             ytdlp::download::cancel_download,
             ytdlp::download::cancel_all_downloads,
             ytdlp::download::pause_download,
             ytdlp::download::resume_download,
+            ytdlp::download::get_download_groups,
+            ytdlp::download::get_download_group,
+            ytdlp::download::cancel_group,
+            ytdlp::download::pause_group,
+            ytdlp::download::resume_group,
+            ytdlp::download::retry_failed_in_group,
+            ytdlp::download::delete_group,
+            ytdlp::download::export_queue,
+            ytdlp::download::import_queue,
             ytdlp::commands::set_minimize_to_tray,
             ytdlp::commands::get_recent_logs,
             ytdlp::commands::get_cached_dep_status,
@@ -56,6 +82,9 @@ pub fn run() {
             ytdlp::commands::update_dependency,
             ytdlp::commands::delete_app_managed_dep,
             ytdlp::commands::reset_all_data,
+            ytdlp::commands::create_backup,
+            ytdlp::commands::restore_backup,
+            ytdlp::commands::run_maintenance,
             modules::log_commands::get_logs,
             modules::log_commands::get_log_stats,
             modules::log_commands::clear_logs,
@@ -64,6 +93,9 @@ pub fn run() {
             ytdlp::types::GlobalDownloadEvent,
             ytdlp::types::DepInstallEvent,
             ytdlp::types::NewLogEvent,
+            ytdlp::types::LibraryReconcileSummary,
+            ytdlp::types::PlaylistEntriesEvent,
+            ytdlp::types::BatchMetadataEvent,
         ]);
 
     #[cfg(debug_assertions)]
@@ -105,7 +137,10 @@ pub fn run() {
             app.manage(log_db.clone());
 
             // Cleanup old logs on startup (30 days, 50k max)
-            if let Err(e) = log_db.cleanup_old_logs(30, 50000) {
+            if let Err(e) = log_db.cleanup_old_logs(
+                ytdlp::maintenance::LOG_MAX_AGE_DAYS,
+                ytdlp::maintenance::LOG_MAX_ENTRIES,
+            ) {
                 eprintln!("Failed to cleanup old logs: {}", e);
             }
 
@@ -132,6 +167,15 @@ pub fn run() {
             ));
             app.manage(download_manager);
 
+            // Flush live download progress to the DB in batches
+            ytdlp::download::spawn_progress_flusher(app.handle().clone());
+
+            // Enforce queue/history retention and log cleanup while the app runs
+            ytdlp::maintenance::spawn_maintenance(app.handle().clone());
+
+            // Find history files that were moved or deleted while the app was closed
+            ytdlp::library::spawn_startup_reconcile(app.handle().clone());
+
             // Setup system tray
             ytdlp::tray::setup_tray(&app.handle().clone()).expect("Failed to setup system tray");
 
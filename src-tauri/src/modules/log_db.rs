@@ -238,6 +238,20 @@ impl LogDatabase {
         Ok(())
     }
 
+    /// Write a consistent snapshot of the log database to `dest`.
+    pub fn backup_to(&self, dest: &Path) -> Result<(), AppError> {
+        let conn = self.conn();
+        crate::ytdlp::db::snapshot_to_file(&conn, dest)
+    }
+
+    /// Replace the live log database contents with a snapshot.
+    pub fn restore_from(&self, src: &Path) -> Result<(), AppError> {
+        let snapshot = crate::ytdlp::db::open_snapshot(src)?;
+        let mut conn = self.conn();
+        crate::ytdlp::db::copy_database(&snapshot, &mut conn)?;
+        Self::create_tables(&conn)
+    }
+
     pub fn cleanup_old_logs(&self, max_age_days: u32, max_entries: u64) -> Result<u64, AppError> {
         let conn = self.conn();
         let mut total_deleted = 0u64;
@@ -1,4 +1,6 @@
-#[derive(Debug, thiserror::Error, specta::Type, serde::Serialize)]
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, thiserror::Error, specta::Type, serde::Serialize)]
 pub enum AppError {
     #[error("File error: {0}")]
     FileError(String),
@@ -22,4 +24,147 @@ pub enum AppError {
     ChecksumError(String),
     #[error("Not implemented: {0}")]
     NotImplemented(String),
+    #[error("Duplicate: {0}")]
+    Duplicate(String),
+    /// A failure the frontend can localize from its code and parameters
+    #[error("{0}")]
+    Coded(ErrorDetail),
+}
+
+impl AppError {
+    pub fn coded(code: ErrorCode) -> Self {
+        AppError::Coded(ErrorDetail::new(code))
+    }
+
+    pub fn detail(&self) -> Option<&ErrorDetail> {
+        match self {
+            AppError::Coded(detail) => Some(detail),
+            _ => None,
+        }
+    }
+}
+
+/// Stable identifier for a user-facing failure. The frontend translates it as
+/// `errors.<code>`, substituting the detail's parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+    PrivateVideo,
+    /// Needs an account: members-only, sign-in walls
+    LoginRequired,
+    VideoUnavailable,
+    LiveNotStarted,
+    UnsupportedUrl,
+    RateLimited,
+    NoFormats,
+    FormatUnavailable,
+    AgeRestricted,
+    GeoBlocked,
+    CookieCopyFailed,
+    NetworkError,
+    /// Params: `status`
+    HttpError,
+    FfmpegMissing,
+    PostprocessFailed,
+    DiskFull,
+    EncodingError,
+    /// Params: `seconds`
+    Timeout,
+    Cancelled,
+    YtdlpNotFound,
+    /// The process died without an exit code (killed or crashed)
+    ProcessCrashed,
+    /// Params: `code`
+    ExitCode,
+    /// Unrecognized yt-dlp failure. Params: `detail` (last stderr line)
+    Ytdlp,
+}
+
+impl ErrorCode {
+    /// English fallback, used for logs and the plain error message column
+    fn template(self) -> &'static str {
+        match self {
+            ErrorCode::PrivateVideo => "This video is private.",
+            ErrorCode::LoginRequired => {
+                "This video requires signing in. Set a cookie browser in Settings."
+            }
+            ErrorCode::VideoUnavailable => "This video is unavailable.",
+            ErrorCode::LiveNotStarted => "This live stream has not started yet.",
+            ErrorCode::UnsupportedUrl => "This URL is not supported.",
+            ErrorCode::RateLimited => "Too many requests. Try again later.",
+            ErrorCode::NoFormats => "No video formats found. It may be a live stream.",
+            ErrorCode::FormatUnavailable => "The requested format is not available for this video.",
+            ErrorCode::AgeRestricted => {
+                "This video is age-restricted. Set a cookie browser in Settings."
+            }
+            ErrorCode::GeoBlocked => "This video is not available in your country.",
+            ErrorCode::CookieCopyFailed => {
+                "Could not read browser cookies. Close the browser completely or use Firefox cookies."
+            }
+            ErrorCode::NetworkError => "Network problem. Check your internet connection.",
+            ErrorCode::HttpError => "The server responded with HTTP error {status}.",
+            ErrorCode::FfmpegMissing => "ffmpeg is required for this download but was not found.",
+            ErrorCode::PostprocessFailed => "Post-processing with ffmpeg failed.",
+            ErrorCode::DiskFull => "Not enough disk space.",
+            ErrorCode::EncodingError => {
+                "Download failed due to a text encoding error. Enable \"Beta: Use Unicode UTF-8 \
+                 for worldwide language support\" in the Windows region settings and restart."
+            }
+            ErrorCode::Timeout => "Timed out after {seconds} seconds.",
+            ErrorCode::Cancelled => "Cancelled.",
+            ErrorCode::YtdlpNotFound => {
+                "yt-dlp not found. Please install via Homebrew or click Install."
+            }
+            ErrorCode::ProcessCrashed => "The yt-dlp process exited unexpectedly.",
+            ErrorCode::ExitCode => "yt-dlp exited with code {code}.",
+            ErrorCode::Ytdlp => "yt-dlp error: {detail}",
+        }
+    }
+}
+
+/// An error code with its message parameters and, separately, the raw stderr that
+/// led to it (already sanitized of local paths).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorDetail {
+    pub code: ErrorCode,
+    pub params: BTreeMap<String, String>,
+    pub stderr: Option<String>,
+}
+
+impl ErrorDetail {
+    pub fn new(code: ErrorCode) -> Self {
+        Self {
+            code,
+            params: BTreeMap::new(),
+            stderr: None,
+        }
+    }
+
+    pub fn with_param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_stderr(mut self, stderr: &str) -> Self {
+        let stderr = stderr.trim();
+        self.stderr = (!stderr.is_empty()).then(|| stderr.to_string());
+        self
+    }
+}
+
+impl std::fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut message = self.code.template().to_string();
+        for (key, value) in &self.params {
+            message = message.replace(&format!("{{{}}}", key), value);
+        }
+        f.write_str(&message)
+    }
+}
+
+impl From<ErrorDetail> for AppError {
+    fn from(detail: ErrorDetail) -> Self {
+        AppError::Coded(detail)
+    }
 }
@@ -0,0 +1,429 @@
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::binary;
+use crate::ytdlp::db::Database;
+use crate::ytdlp::download::{process_next_pending_public, DownloadManager};
+use crate::ytdlp::settings;
+use crate::ytdlp::types::*;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DB_ENTRY: &str = "ytdlp.db";
+const LOGS_ENTRY: &str = "logs.db";
+const SETTINGS_STORE: &str = "settings.json";
+const DEP_CACHE_STORE: &str = "dep-cache.json";
+
+/// How long a restore waits for running downloads to stop after cancelling them
+const STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Scratch directory for snapshots and extracted entries; removed on drop.
+struct WorkDir(PathBuf);
+
+impl WorkDir {
+    fn new(app: &AppHandle, prefix: &str) -> Result<Self, AppError> {
+        let dir = app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| AppError::FileError(format!("Failed to get cache dir: {}", e)))?
+            .join(format!(
+                "{}-{}",
+                prefix,
+                chrono::Utc::now().timestamp_millis()
+            ));
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::FileError(format!("Failed to create work dir: {}", e)))?;
+        Ok(Self(dir))
+    }
+
+    fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn store_snapshot(app: &AppHandle, name: &str) -> Result<serde_json::Value, AppError> {
+    let store = app
+        .store(name)
+        .map_err(|e| AppError::Custom(e.to_string()))?;
+    Ok(serde_json::Value::Object(
+        store.entries().into_iter().collect(),
+    ))
+}
+
+fn restore_store(app: &AppHandle, name: &str, value: &serde_json::Value) -> Result<(), AppError> {
+    let entries = value
+        .as_object()
+        .ok_or_else(|| AppError::Custom(format!("{} in backup is not an object", name)))?;
+    let store = app
+        .store(name)
+        .map_err(|e| AppError::Custom(e.to_string()))?;
+    store.clear();
+    for (key, value) in entries {
+        store.set(key.clone(), value.clone());
+    }
+    store.save().map_err(|e| AppError::Custom(e.to_string()))
+}
+
+fn zip_error(e: impl std::fmt::Display) -> AppError {
+    AppError::FileError(format!("Backup archive error: {}", e))
+}
+
+/// Write the archive next to `dest` first and rename it into place, so a failed
+/// backup never leaves a truncated file behind.
+fn write_archive(
+    dest: &Path,
+    manifest: &BackupManifest,
+    files: &[(String, PathBuf)],
+    json_entries: &[(&str, serde_json::Value)],
+) -> Result<(), AppError> {
+    let tmp = dest.with_extension("zip.tmp");
+    let file = std::fs::File::create(&tmp)
+        .map_err(|e| AppError::FileError(format!("Failed to create backup file: {}", e)))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    let result = (|| {
+        zip.start_file(MANIFEST_ENTRY, options).map_err(zip_error)?;
+        let manifest_json = serde_json::to_vec_pretty(manifest).map_err(zip_error)?;
+        zip.write_all(&manifest_json).map_err(zip_error)?;
+
+        for (name, value) in json_entries {
+            zip.start_file(*name, options).map_err(zip_error)?;
+            let bytes = serde_json::to_vec_pretty(value).map_err(zip_error)?;
+            zip.write_all(&bytes).map_err(zip_error)?;
+        }
+
+        for (name, path) in files {
+            zip.start_file(name.as_str(), options).map_err(zip_error)?;
+            let mut src = std::fs::File::open(path).map_err(zip_error)?;
+            std::io::copy(&mut src, &mut zip).map_err(zip_error)?;
+        }
+
+        zip.finish().map_err(zip_error)?;
+        std::fs::rename(&tmp, dest).map_err(zip_error)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Write a backup archive with a consistent snapshot of `ytdlp.db`, the settings and
+/// dependency-cache stores and, optionally, `logs.db`.
+pub async fn create_backup(
+    app: &AppHandle,
+    dest: &str,
+    include_logs: bool,
+) -> Result<BackupManifest, AppError> {
+    let dest = PathBuf::from(dest.trim());
+    if !dest.is_absolute() {
+        return Err(AppError::FileError(
+            "Backup path must be absolute".to_string(),
+        ));
+    }
+
+    let work = WorkDir::new(app, "backup")?;
+    let db = app.state::<crate::DbState>().inner().clone();
+    let log_db = app.state::<crate::LogDbState>().inner().clone();
+
+    let db_snapshot = work.join(DB_ENTRY);
+    let logs_snapshot = work.join(LOGS_ENTRY);
+    let (db_path, logs_path) = (db_snapshot.clone(), logs_snapshot.clone());
+    tokio::task::spawn_blocking(move || -> Result<(), AppError> {
+        db.backup_to(&db_path)?;
+        if include_logs {
+            log_db.backup_to(&logs_path)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Backup task failed: {}", e)))??;
+
+    let mut files = vec![(DB_ENTRY.to_string(), db_snapshot.clone())];
+    if include_logs {
+        files.push((LOGS_ENTRY.to_string(), logs_snapshot));
+    }
+    let json_entries = vec![
+        (SETTINGS_STORE, store_snapshot(app, SETTINGS_STORE)?),
+        (DEP_CACHE_STORE, store_snapshot(app, DEP_CACHE_STORE)?),
+    ];
+
+    let manifest = BackupManifest {
+        format_version: FORMAT_VERSION,
+        app_version: app.package_info().version.to_string(),
+        schema_version: Database::snapshot_schema_version(&db_snapshot)?,
+        created_at: chrono::Utc::now().timestamp(),
+        files: json_entries
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(files.iter().map(|(name, _)| name.clone()))
+            .collect(),
+    };
+
+    let archive_manifest = manifest.clone();
+    let archive_dest = dest.clone();
+    tokio::task::spawn_blocking(move || {
+        write_archive(&archive_dest, &archive_manifest, &files, &json_entries)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Backup task failed: {}", e)))??;
+
+    logger::info_cat(
+        "backup",
+        &format!(
+            "Backup written to {} (schema v{}, logs: {})",
+            dest.display(),
+            manifest.schema_version,
+            include_logs
+        ),
+    );
+
+    Ok(manifest)
+}
+
+/// Contents of a backup archive after extraction into the work dir.
+struct ExtractedBackup {
+    manifest: BackupManifest,
+    db_path: PathBuf,
+    logs_path: Option<PathBuf>,
+    settings: Option<serde_json::Value>,
+    dep_cache: Option<serde_json::Value>,
+}
+
+fn read_entry(
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, AppError> {
+    match archive.by_name(name) {
+        Ok(mut entry) => {
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf).map_err(zip_error)?;
+            Ok(Some(buf))
+        }
+        Err(zip::result::ZipError::FileNotFound) => Ok(None),
+        Err(e) => Err(zip_error(e)),
+    }
+}
+
+fn extract_entry_to(
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    name: &str,
+    dest: &Path,
+) -> Result<bool, AppError> {
+    match archive.by_name(name) {
+        Ok(mut entry) => {
+            let mut out = std::fs::File::create(dest).map_err(zip_error)?;
+            std::io::copy(&mut entry, &mut out).map_err(zip_error)?;
+            Ok(true)
+        }
+        Err(zip::result::ZipError::FileNotFound) => Ok(false),
+        Err(e) => Err(zip_error(e)),
+    }
+}
+
+/// Extract only the known entries; anything else in the archive is ignored.
+fn extract_archive(src: &Path, work: &Path) -> Result<ExtractedBackup, AppError> {
+    let file = std::fs::File::open(src)
+        .map_err(|e| AppError::FileError(format!("Failed to open backup: {}", e)))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(zip_error)?;
+
+    let manifest: BackupManifest = read_entry(&mut archive, MANIFEST_ENTRY)?
+        .ok_or_else(|| AppError::FileError("Backup has no manifest".to_string()))
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(zip_error))?;
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(AppError::FileError(format!(
+            "Unsupported backup format version {}",
+            manifest.format_version
+        )));
+    }
+
+    let db_path = work.join(DB_ENTRY);
+    if !extract_entry_to(&mut archive, DB_ENTRY, &db_path)? {
+        return Err(AppError::FileError(format!("Backup has no {}", DB_ENTRY)));
+    }
+    let logs_path = work.join(LOGS_ENTRY);
+    let logs_path = extract_entry_to(&mut archive, LOGS_ENTRY, &logs_path)?.then_some(logs_path);
+
+    let json = |archive: &mut zip::ZipArchive<std::fs::File>, name: &str| {
+        read_entry(archive, name)?
+            .map(|bytes| serde_json::from_slice(&bytes).map_err(zip_error))
+            .transpose()
+    };
+    let settings = json(&mut archive, SETTINGS_STORE)?;
+    let dep_cache = json(&mut archive, DEP_CACHE_STORE)?;
+
+    Ok(ExtractedBackup {
+        manifest,
+        db_path,
+        logs_path,
+        settings,
+        dep_cache,
+    })
+}
+
+/// Cancel running downloads and wait for their tasks to finish.
+async fn stop_queue(manager: &DownloadManager) -> Result<(), AppError> {
+    manager.pause_dispatch();
+    manager.cancel_all();
+    let deadline = tokio::time::Instant::now() + STOP_TIMEOUT;
+    while manager.active_count() > 0 {
+        if tokio::time::Instant::now() >= deadline {
+            return Err(AppError::DownloadError(
+                "Downloads did not stop in time; restore aborted".to_string(),
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    Ok(())
+}
+
+/// Restore app state from a backup archive. The snapshot is validated before anything
+/// is touched; then the queue is stopped, the databases are swapped in through the
+/// live connections and dispatching restarts.
+pub async fn restore_backup(app: &AppHandle, src: &str) -> Result<RestoreReport, AppError> {
+    let src = PathBuf::from(src.trim());
+    let work = WorkDir::new(app, "restore")?;
+    let work_dir = work.0.clone();
+    let extracted = tokio::task::spawn_blocking(move || {
+        let extracted = extract_archive(&src, &work_dir)?;
+        let version = Database::snapshot_schema_version(&extracted.db_path)?;
+        if version != extracted.manifest.schema_version {
+            return Err(AppError::DatabaseError(format!(
+                "Snapshot schema v{} does not match the manifest (v{})",
+                version, extracted.manifest.schema_version
+            )));
+        }
+        Ok(extracted)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Restore task failed: {}", e)))??;
+
+    let manager = app.state::<Arc<DownloadManager>>().inner().clone();
+    if let Err(e) = stop_queue(&manager).await {
+        manager.resume_dispatch();
+        return Err(e);
+    }
+
+    let db = app.state::<crate::DbState>().inner().clone();
+    let log_db = app.state::<crate::LogDbState>().inner().clone();
+    let db_path = extracted.db_path.clone();
+    let logs_path = extracted.logs_path.clone();
+    let restored = tokio::task::spawn_blocking(move || {
+        db.restore_from(&db_path)?;
+        let logs = logs_path.map(|path| log_db.restore_from(&path));
+        Ok::<_, AppError>(logs)
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Restore task failed: {}", e)));
+
+    let mut results = Vec::new();
+    match restored {
+        Ok(Ok(logs)) => {
+            results.push(format!("{}: restored", DB_ENTRY));
+            match logs {
+                Some(Ok(())) => results.push(format!("{}: restored", LOGS_ENTRY)),
+                Some(Err(e)) => results.push(format!("{}: restore failed - {}", LOGS_ENTRY, e)),
+                None => {}
+            }
+        }
+        Ok(Err(e)) | Err(e) => {
+            // The live database is untouched unless the page copy itself failed midway
+            manager.resume_dispatch();
+            process_next_pending_public(app.clone());
+            return Err(e);
+        }
+    }
+
+    if let Some(value) = &extracted.settings {
+        match restore_store(app, SETTINGS_STORE, value) {
+            Ok(()) => {
+                if let Ok(settings) = settings::get_settings(app) {
+                    manager.set_max_concurrent(settings.max_concurrent);
+                }
+                results.push(format!("{}: restored", SETTINGS_STORE));
+            }
+            Err(e) => results.push(format!("{}: restore failed - {}", SETTINGS_STORE, e)),
+        }
+    }
+    if let Some(value) = &extracted.dep_cache {
+        match restore_store(app, DEP_CACHE_STORE, value) {
+            Ok(()) => results.push(format!("{}: restored", DEP_CACHE_STORE)),
+            Err(e) => results.push(format!("{}: restore failed - {}", DEP_CACHE_STORE, e)),
+        }
+    }
+    binary::invalidate_dep_cache();
+
+    manager.sync_active_count(0);
+    manager.resume_dispatch();
+    process_next_pending_public(app.clone());
+
+    logger::info_cat(
+        "backup",
+        &format!(
+            "Restored backup created at {} (schema v{}): {:?}",
+            extracted.manifest.created_at, extracted.manifest.schema_version, results
+        ),
+    );
+
+    Ok(RestoreReport {
+        manifest: extracted.manifest,
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_file = dir.path().join("source.db");
+        std::fs::write(&db_file, b"database bytes").unwrap();
+        let manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            app_version: "1.0.0".to_string(),
+            schema_version: 13,
+            created_at: 1_700_000_000,
+            files: vec![SETTINGS_STORE.to_string(), DB_ENTRY.to_string()],
+        };
+        let archive = dir.path().join("backup.zip");
+        write_archive(
+            &archive,
+            &manifest,
+            &[(DB_ENTRY.to_string(), db_file)],
+            &[(SETTINGS_STORE, serde_json::json!({"theme": "dark"}))],
+        )
+        .unwrap();
+        assert!(!archive.with_extension("zip.tmp").exists());
+
+        let work = dir.path().join("work");
+        std::fs::create_dir_all(&work).unwrap();
+        let extracted = extract_archive(&archive, &work).unwrap();
+        assert_eq!(extracted.manifest.schema_version, 13);
+        assert_eq!(
+            std::fs::read(&extracted.db_path).unwrap(),
+            b"database bytes"
+        );
+        assert_eq!(extracted.logs_path, None);
+        assert_eq!(
+            extracted.settings,
+            Some(serde_json::json!({"theme": "dark"}))
+        );
+        assert_eq!(extracted.dep_cache, None);
+    }
+}
@@ -8,5 +8,6 @@ pub use dep_check::{
 };
 pub use path::command_with_path_app;
 pub use resolve::{
-    check_dependencies, resolve_ffmpeg_path_with_app, resolve_ytdlp_path_with_app, update_ytdlp,
+    check_dependencies, resolve_ffmpeg_path_with_app, resolve_ffprobe_path_with_app,
+    resolve_ytdlp_path_with_app, update_ytdlp,
 };
@@ -241,6 +241,41 @@ pub async fn resolve_ffmpeg_path_with_app(app: &AppHandle) -> Option<String> {
     resolve_ffmpeg_path().await
 }
 
+/// Resolve the ffprobe binary (installed alongside ffmpeg): app_data_dir/bin/ first
+/// (if external mode), then system PATH. Returns the binary path itself, not its directory.
+pub async fn resolve_ffprobe_path_with_app(app: &AppHandle) -> Option<String> {
+    let bin_name = if cfg!(target_os = "windows") {
+        "ffprobe.exe"
+    } else {
+        "ffprobe"
+    };
+
+    // 1. Check app-managed binary (only in external mode)
+    if is_external_mode(app) {
+        if let Some(bin_dir) = app_bin_dir(app) {
+            let app_binary = bin_dir.join(bin_name);
+            if app_binary.exists() {
+                return Some(app_binary.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    // 2. Fallback to system PATH
+    let mut cmd = command_with_path("ffprobe");
+    cmd.arg("-version");
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    match tokio::time::timeout(Duration::from_secs(5), cmd.output()).await {
+        Ok(Ok(output)) if output.status.success() => Some("ffprobe".to_string()),
+        _ => None,
+    }
+}
+
 /// Resolve deno binary: app_data_dir/bin/ (if external mode) -> ~/.deno/bin/ -> system PATH.
 pub async fn resolve_deno_path(app: &AppHandle) -> Option<PathBuf> {
     // 1. Check app-managed binary (only in external mode)
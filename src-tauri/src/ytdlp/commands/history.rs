@@ -10,9 +10,35 @@ pub async fn get_download_history(
     page: u32,
     page_size: u32,
     search: Option<String>,
+    filter: Option<HistoryFilter>,
 ) -> Result<HistoryResult, AppError> {
     let db = app.state::<crate::DbState>();
-    db.get_history(page, page_size, search.as_deref())
+    let mut filter = filter.unwrap_or_default();
+    // `search` predates the filter object; the filter's own search wins if both are set
+    if filter.search.is_none() {
+        filter.search = search;
+    }
+    db.get_history(page, page_size, &filter)
+}
+
+/// Sites present in history, for populating the site filter.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_history_extractors(app: AppHandle) -> Result<Vec<String>, AppError> {
+    let db = app.state::<crate::DbState>();
+    db.get_history_extractors()
+}
+
+/// Download and library statistics: bytes per period, outcomes per site, top channels,
+/// throughput and library size. `top_channels` defaults to 10.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_download_statistics(
+    app: AppHandle,
+    top_channels: Option<u32>,
+) -> Result<DownloadStatistics, AppError> {
+    let db = app.state::<crate::DbState>();
+    db.get_download_statistics(top_channels.unwrap_or(10).clamp(1, 100))
 }
 
 #[tauri::command]
@@ -20,10 +46,22 @@ pub async fn get_download_history(
 pub async fn check_duplicate(
     app: AppHandle,
     video_id: String,
+    video_url: Option<String>,
 ) -> Result<DuplicateCheckResult, AppError> {
     let db = app.state::<crate::DbState>();
-    let history_item = db.check_duplicate(&video_id)?;
-    let in_queue = db.check_duplicate_in_queue(&video_id)?;
+    let mut history_item = db.check_duplicate(&video_id)?;
+    let mut in_queue = db.check_duplicate_in_queue(&video_id)?;
+
+    // With the URL, also match the same video downloaded through another URL form
+    if let Some((extractor, id)) = video_url
+        .as_deref()
+        .and_then(crate::ytdlp::metadata::canonical_identity)
+    {
+        if history_item.is_none() {
+            history_item = db.find_canonical_in_history(&extractor, &id)?;
+        }
+        in_queue = in_queue || db.canonical_in_queue(&extractor, &id)?;
+    }
 
     let file_exists = if let Some(ref item) = history_item {
         match tokio::fs::metadata(&item.file_path).await {
@@ -0,0 +1,60 @@
+use crate::modules::types::AppError;
+use crate::ytdlp::library;
+use crate::ytdlp::types::*;
+use tauri::AppHandle;
+
+/// Re-run ffprobe verification for every history item and store the results.
+#[tauri::command]
+#[specta::specta]
+pub async fn reverify_library(app: AppHandle) -> Result<LibraryVerifySummary, AppError> {
+    library::verify_library(&app).await
+}
+
+/// Group history items whose files have identical contents (hashing any files not yet hashed).
+#[tauri::command]
+#[specta::specta]
+pub async fn find_duplicate_files(app: AppHandle) -> Result<Vec<DuplicateFileGroup>, AppError> {
+    library::find_duplicate_files(&app).await
+}
+
+/// Remove duplicate history entries, optionally deleting their files from disk.
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_duplicate_files(
+    app: AppHandle,
+    history_ids: Vec<u64>,
+    delete_files: bool,
+) -> Result<RemoveDuplicatesResult, AppError> {
+    library::remove_duplicate_files(&app, &history_ids, delete_files).await
+}
+
+/// Check every history file on disk, relinking moved files found in the library roots
+/// (including rows already marked missing) and marking the rest missing.
+#[tauri::command]
+#[specta::specta]
+pub async fn reconcile_library(app: AppHandle) -> Result<LibraryReconcileSummary, AppError> {
+    library::reconcile_library(&app, true).await
+}
+
+/// Move all history files under `from_root` to `to_root`, keeping their relative layout.
+#[tauri::command]
+#[specta::specta]
+pub async fn move_library(
+    app: AppHandle,
+    from_root: String,
+    to_root: String,
+) -> Result<MoveLibraryResult, AppError> {
+    library::move_library(&app, &from_root, &to_root).await
+}
+
+/// Scan a folder of earlier yt-dlp downloads and add the identified files to history.
+/// With `dry_run` nothing is written and only the report is returned.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_library_folder(
+    app: AppHandle,
+    folder: String,
+    dry_run: bool,
+) -> Result<LibraryImportReport, AppError> {
+    library::import_library_folder(&app, &folder, dry_run).await
+}
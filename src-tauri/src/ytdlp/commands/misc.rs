@@ -1,7 +1,10 @@
 use crate::modules::logger;
 use crate::modules::types::AppError;
+use crate::ytdlp::backup;
 use crate::ytdlp::binary;
 use crate::ytdlp::download::DownloadManager;
+use crate::ytdlp::maintenance;
+use crate::ytdlp::types::{BackupManifest, RestoreReport, RetentionResult};
 use std::sync::Arc;
 use tauri::AppHandle;
 use tauri::Manager;
@@ -121,3 +124,29 @@ pub async fn reset_all_data(app: AppHandle) -> Result<Vec<String>, AppError> {
 
     Ok(results)
 }
+
+/// Write a backup archive of the app state (database, settings, dependency cache and
+/// optionally logs) to `path`.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_backup(
+    app: AppHandle,
+    path: String,
+    include_logs: bool,
+) -> Result<BackupManifest, AppError> {
+    backup::create_backup(&app, &path, include_logs).await
+}
+
+/// Restore app state from a backup archive, pausing the queue while the databases are swapped.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_backup(app: AppHandle, path: String) -> Result<RestoreReport, AppError> {
+    backup::restore_backup(&app, &path).await
+}
+
+/// Apply the retention settings now instead of waiting for the next periodic run.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_maintenance(app: AppHandle) -> Result<RetentionResult, AppError> {
+    maintenance::run_maintenance(&app)
+}
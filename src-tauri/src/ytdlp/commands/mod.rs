@@ -1,5 +1,6 @@
 pub mod dependency;
 pub mod history;
+pub mod library;
 pub mod misc;
 pub mod queue;
 pub mod settings_cmd;
@@ -7,6 +8,7 @@ pub mod settings_cmd;
 // Re-export all items (includes __cmd__ and __specta__fn__ generated by proc macros)
 pub use dependency::*;
 pub use history::*;
+pub use library::*;
 pub use misc::*;
 pub use queue::*;
 pub use settings_cmd::*;
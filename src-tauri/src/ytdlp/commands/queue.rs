@@ -60,7 +60,10 @@ pub async fn retry_download(app: AppHandle, task_id: u64) -> Result<(), AppError
 #[specta::specta]
 pub async fn get_active_downloads(app: AppHandle) -> Result<Vec<DownloadTaskInfo>, AppError> {
     let db = app.state::<crate::DbState>();
-    db.get_active_downloads()
+    let mut tasks = db.get_active_downloads()?;
+    app.state::<Arc<DownloadManager>>()
+        .merge_live_progress(&mut tasks);
+    Ok(tasks)
 }
 
 #[tauri::command]
@@ -72,12 +75,21 @@ pub async fn get_download_queue_paginated(
     status_filter: Option<String>,
 ) -> Result<QueueResult, AppError> {
     let db = app.state::<crate::DbState>();
-    db.get_download_queue_paginated(page, page_size, status_filter.as_deref())
+    let mut result = db.get_download_queue_paginated(page, page_size, status_filter.as_deref())?;
+    app.state::<Arc<DownloadManager>>()
+        .merge_live_progress(&mut result.items);
+    Ok(result)
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn get_queue_summary(app: AppHandle) -> Result<QueueSummary, AppError> {
+pub async fn get_queue_summary(
+    app: AppHandle,
+    by_group: Option<bool>,
+) -> Result<QueueSummary, AppError> {
     let db = app.state::<crate::DbState>();
-    db.get_queue_summary(5)
+    let mut summary = db.get_queue_summary(5, by_group.unwrap_or(false))?;
+    app.state::<Arc<DownloadManager>>()
+        .merge_live_progress(&mut summary.active_items);
+    Ok(summary)
 }
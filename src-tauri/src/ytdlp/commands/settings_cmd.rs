@@ -59,7 +59,7 @@ pub async fn select_download_directory(app: AppHandle) -> Result<Option<String>,
     let result = tokio::task::spawn_blocking(move || {
         app.dialog()
             .file()
-            .set_title("다운로드 폴더 선택")
+            .set_title("Select Download Folder")
             .blocking_pick_folder()
     })
     .await
@@ -0,0 +1,194 @@
+use super::{Database, SCHEMA_VERSION};
+use crate::modules::types::AppError;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long a copy waits for a busy source or destination before giving up
+const BUSY_RETRIES: u32 = 100;
+const BUSY_PAUSE: Duration = Duration::from_millis(50);
+
+/// Copy every page of `from` into `to` with SQLite's online backup API.
+/// All pages are copied in one step, so the result is a consistent snapshot
+/// even while other connections keep writing.
+pub(crate) fn copy_database(from: &Connection, to: &mut Connection) -> Result<(), AppError> {
+    let backup = Backup::new(from, to).map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let mut retries = 0;
+    loop {
+        match backup
+            .step(-1)
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+        {
+            StepResult::Done => return Ok(()),
+            StepResult::More => {}
+            StepResult::Busy | StepResult::Locked => {
+                retries += 1;
+                if retries > BUSY_RETRIES {
+                    return Err(AppError::DatabaseError(
+                        "Database stayed busy during backup".to_string(),
+                    ));
+                }
+                std::thread::sleep(BUSY_PAUSE);
+            }
+            // StepResult is non-exhaustive
+            _ => {}
+        }
+    }
+}
+
+/// Write a snapshot of `from` to a new single-file database at `dest`.
+pub(crate) fn snapshot_to_file(from: &Connection, dest: &Path) -> Result<(), AppError> {
+    let _ = std::fs::remove_file(dest);
+    let mut dst = Connection::open(dest).map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    copy_database(from, &mut dst)?;
+    // Leave no -wal file next to the snapshot
+    dst.execute_batch("PRAGMA journal_mode=DELETE;")
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    Ok(())
+}
+
+/// Open a snapshot read-only and check it is intact.
+pub(crate) fn open_snapshot(path: &Path) -> Result<Connection, AppError> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| AppError::DatabaseError(format!("Cannot open snapshot: {}", e)))?;
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| AppError::DatabaseError(format!("Snapshot is not a database: {}", e)))?;
+    if check != "ok" {
+        return Err(AppError::DatabaseError(format!(
+            "Snapshot failed integrity check: {}",
+            check
+        )));
+    }
+    Ok(conn)
+}
+
+/// Schema version recorded in an open snapshot, rejecting one newer than this app.
+fn schema_version_of(conn: &Connection) -> Result<u32, AppError> {
+    let version: Option<u32> = conn
+        .query_row("SELECT version FROM _schema_version LIMIT 1", [], |row| {
+            row.get(0)
+        })
+        .optional()
+        .map_err(|_| AppError::DatabaseError("Snapshot has no schema version table".to_string()))?;
+    let version = version
+        .ok_or_else(|| AppError::DatabaseError("Snapshot has no schema version".to_string()))?;
+    if version > SCHEMA_VERSION {
+        return Err(AppError::DatabaseError(format!(
+            "Snapshot schema v{} is newer than this app supports (v{})",
+            version, SCHEMA_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+impl Database {
+    /// Schema version recorded in a snapshot of `ytdlp.db`. Fails if the file is damaged,
+    /// isn't an app database, or was written by a newer version of the app.
+    pub fn snapshot_schema_version(path: &Path) -> Result<u32, AppError> {
+        schema_version_of(&open_snapshot(path)?)
+    }
+
+    /// Write a consistent snapshot of the database to `dest`.
+    pub fn backup_to(&self, dest: &Path) -> Result<(), AppError> {
+        let conn = self.read_conn();
+        snapshot_to_file(&conn, dest)
+    }
+
+    /// Replace the live database contents with a snapshot, then bring it up to the
+    /// current schema. Items that were downloading when the snapshot was taken go back
+    /// to pending. The caller must stop the dispatcher first.
+    pub fn restore_from(&self, src: &Path) -> Result<(), AppError> {
+        let snapshot = open_snapshot(src)?;
+        schema_version_of(&snapshot)?;
+
+        let mut conn = self.conn();
+        copy_database(&snapshot, &mut conn)?;
+        Self::create_tables(&conn)?;
+        Self::run_migrations(&conn)?;
+        conn.execute(
+            "UPDATE downloads SET status = 'pending' WHERE status = 'downloading'",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{download_request, temp_database};
+    use super::*;
+    use crate::ytdlp::types::{DownloadOptions, DownloadStatus};
+
+    #[test]
+    fn test_backup_restore_round_trip() {
+        let (dir, db) = temp_database();
+        let kept = db
+            .insert_download(
+                &download_request("aaaaaaaaaaa"),
+                &DownloadOptions::default(),
+            )
+            .unwrap();
+        let running = db
+            .insert_download(
+                &download_request("bbbbbbbbbbb"),
+                &DownloadOptions::default(),
+            )
+            .unwrap();
+        db.update_download_status(running, &DownloadStatus::Downloading, None)
+            .unwrap();
+
+        let snapshot = dir.path().join("snapshot.db");
+        db.backup_to(&snapshot).unwrap();
+        assert_eq!(
+            Database::snapshot_schema_version(&snapshot).unwrap(),
+            SCHEMA_VERSION
+        );
+
+        let (_other_dir, other) = temp_database();
+        other
+            .insert_download(
+                &download_request("ccccccccccc"),
+                &DownloadOptions::default(),
+            )
+            .unwrap();
+        other.restore_from(&snapshot).unwrap();
+
+        let queue = other.get_download_queue().unwrap();
+        let mut ids: Vec<_> = queue.iter().map(|t| t.video_id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, ["aaaaaaaaaaa", "bbbbbbbbbbb"]);
+        let status = |id| other.get_download(id).unwrap().unwrap().status;
+        assert!(matches!(status(kept), DownloadStatus::Pending));
+        // Nothing is running after a restore, so in-progress items are requeued
+        assert!(matches!(status(running), DownloadStatus::Pending));
+    }
+
+    #[test]
+    fn test_snapshot_newer_schema_rejected() {
+        let (dir, db) = temp_database();
+        let snapshot = dir.path().join("snapshot.db");
+        db.backup_to(&snapshot).unwrap();
+        {
+            let conn = Connection::open(&snapshot).unwrap();
+            conn.execute(
+                "UPDATE _schema_version SET version = ?1",
+                [SCHEMA_VERSION + 1],
+            )
+            .unwrap();
+        }
+
+        assert!(Database::snapshot_schema_version(&snapshot).is_err());
+        assert!(db.restore_from(&snapshot).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_not_a_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("garbage.db");
+        std::fs::write(&path, b"not a sqlite file at all").unwrap();
+        assert!(open_snapshot(&path).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use super::queue::insert_download_rows;
+use super::Database;
+use crate::modules::types::{AppError, ErrorDetail};
+use crate::ytdlp::diagnose;
+use crate::ytdlp::types::*;
+use rusqlite::params;
+
+fn map_group_row(row: &rusqlite::Row) -> rusqlite::Result<DownloadGroup> {
+    let options: Option<String> = row.get(4)?;
+    Ok(DownloadGroup {
+        id: row.get(0)?,
+        source_url: row.get(1)?,
+        group_type: GroupType::parse(&row.get::<_, String>(2)?),
+        title: row.get(3)?,
+        options: options.and_then(|json| serde_json::from_str(&json).ok()),
+        created_at: row.get(5)?,
+        total_count: row.get(6)?,
+        pending_count: row.get(7)?,
+        active_count: row.get(8)?,
+        paused_count: row.get(9)?,
+        completed_count: row.get(10)?,
+        failed_count: row.get(11)?,
+        cancelled_count: row.get(12)?,
+        progress: row.get::<_, f64>(13)? as f32,
+    })
+}
+
+/// Group columns followed by per-status item counts and average progress.
+/// Finished items count as 100% regardless of the last reported progress value.
+const GROUP_SELECT: &str = "SELECT g.id, g.source_url, g.group_type, g.title, g.options, g.created_at,
+        COUNT(d.id),
+        COALESCE(SUM(d.status = 'pending'), 0),
+        COALESCE(SUM(d.status = 'downloading'), 0),
+        COALESCE(SUM(d.status = 'paused'), 0),
+        COALESCE(SUM(d.status IN ('completed', 'needs_attention')), 0),
+        COALESCE(SUM(d.status = 'failed'), 0),
+        COALESCE(SUM(d.status = 'cancelled'), 0),
+        COALESCE(AVG(CASE WHEN d.status IN ('completed', 'needs_attention') THEN 100.0 ELSE d.progress END), 0.0)
+     FROM download_groups g
+     LEFT JOIN downloads d ON d.group_id = g.id";
+
+impl Database {
+    /// Create a download group and insert its items in a single transaction.
+    /// Returns the new group ID and the task IDs in input order.
+    pub fn insert_group_with_downloads(
+        &self,
+        group: &NewDownloadGroup,
+        items: &[(DownloadRequest, DownloadOptions)],
+    ) -> Result<(u64, Vec<u64>), AppError> {
+        let options = group
+            .options
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tx.execute(
+            "INSERT INTO download_groups (source_url, group_type, title, options, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                group.source_url,
+                group.group_type.to_string(),
+                group.title,
+                options,
+                chrono::Utc::now().timestamp(),
+            ],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let group_id = tx.last_insert_rowid() as u64;
+
+        let ids = insert_download_rows(&tx, items, Some(group_id))?;
+
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok((group_id, ids))
+    }
+
+    pub fn get_download_groups(&self) -> Result<Vec<DownloadGroup>, AppError> {
+        let conn = self.read_conn();
+        let mut stmt = conn
+            .prepare(&format!(
+                "{} GROUP BY g.id ORDER BY g.created_at DESC, g.id DESC",
+                GROUP_SELECT
+            ))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let groups = stmt
+            .query_map([], map_group_row)
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(groups)
+    }
+
+    pub fn get_download_group(&self, group_id: u64) -> Result<Option<DownloadGroup>, AppError> {
+        let conn = self.read_conn();
+        let mut stmt = conn
+            .prepare(&format!("{} WHERE g.id = ?1 GROUP BY g.id", GROUP_SELECT))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let result = stmt.query_row([group_id], map_group_row);
+
+        match result {
+            Ok(group) => Ok(Some(group)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(AppError::DatabaseError(e.to_string())),
+        }
+    }
+
+    /// Move every item of a group in one of `from` statuses to `to`, in a single transaction.
+    /// Returns the number of rows changed and the IDs of items that were downloading,
+    /// so the caller can signal their running processes.
+    fn transition_group(
+        &self,
+        group_id: u64,
+        from: &[&str],
+        to: &DownloadStatus,
+    ) -> Result<(u32, Vec<u64>), AppError> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let running: Vec<u64> = if from.contains(&"downloading") {
+            let mut stmt = tx
+                .prepare("SELECT id FROM downloads WHERE group_id = ?1 AND status = 'downloading'")
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let ids = stmt
+                .query_map([group_id], |row| row.get(0))
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .collect::<Result<Vec<u64>, _>>()
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            ids
+        } else {
+            Vec::new()
+        };
+
+        let statuses = from
+            .iter()
+            .map(|s| format!("'{}'", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let changed = tx
+            .execute(
+                &format!(
+                    "UPDATE downloads SET status = ?1 WHERE group_id = ?2 AND status IN ({})",
+                    statuses
+                ),
+                params![to.to_string(), group_id],
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok((changed as u32, running))
+    }
+
+    /// Cancel all unfinished items of a group (pending, downloading or paused).
+    pub fn cancel_group_downloads(&self, group_id: u64) -> Result<(u32, Vec<u64>), AppError> {
+        self.transition_group(
+            group_id,
+            &["pending", "downloading", "paused"],
+            &DownloadStatus::Cancelled,
+        )
+    }
+
+    /// Pause all pending and downloading items of a group.
+    pub fn pause_group_downloads(&self, group_id: u64) -> Result<(u32, Vec<u64>), AppError> {
+        self.transition_group(
+            group_id,
+            &["pending", "downloading"],
+            &DownloadStatus::Paused,
+        )
+    }
+
+    /// Put paused items of a group back into the pending queue.
+    pub fn resume_group_downloads(&self, group_id: u64) -> Result<u32, AppError> {
+        let (changed, _) =
+            self.transition_group(group_id, &["paused"], &DownloadStatus::Pending)?;
+        Ok(changed)
+    }
+
+    /// Reset failed items of a group to pending, clearing their error and progress.
+    /// Items whose recorded error would fail again (see `diagnose::retryable`) stay failed.
+    pub fn retry_failed_in_group(&self, group_id: u64) -> Result<u32, AppError> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let failed: Vec<(u64, Option<String>)> = {
+            let mut stmt = tx
+                .prepare("SELECT id, error_detail FROM downloads WHERE group_id = ?1 AND status = 'failed'")
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let rows = stmt
+                .query_map(params![group_id], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            rows
+        };
+
+        let mut changed = 0;
+        for (id, detail) in failed {
+            let detail = detail.and_then(|json| serde_json::from_str::<ErrorDetail>(&json).ok());
+            if detail.is_some_and(|d| !diagnose::retryable(d.code)) {
+                continue;
+            }
+            changed += tx
+                .execute(
+                    "UPDATE downloads SET status = 'pending', error_message = NULL, error_detail = NULL, progress = 0.0, speed = NULL, eta = NULL
+                     WHERE id = ?1",
+                    params![id],
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        }
+
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(changed as u32)
+    }
+
+    /// Delete a group; its download rows are removed by the foreign key cascade.
+    pub fn delete_group(&self, group_id: u64) -> Result<bool, AppError> {
+        let conn = self.conn();
+        let deleted = conn
+            .execute(
+                "DELETE FROM download_groups WHERE id = ?1",
+                params![group_id],
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(deleted > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{download_request, temp_database};
+    use super::*;
+    use crate::modules::types::ErrorCode;
+
+    #[test]
+    fn test_retry_failed_in_group_skips_permanent_errors() {
+        let (_dir, db) = temp_database();
+        let group = NewDownloadGroup {
+            source_url: "https://www.youtube.com/playlist?list=PL1".to_string(),
+            group_type: GroupType::Playlist,
+            title: "Playlist".to_string(),
+            options: None,
+        };
+        let items: Vec<_> = ["aaaaaaaaaaa", "bbbbbbbbbbb", "ccccccccccc", "ddddddddddd"]
+            .iter()
+            .map(|id| (download_request(id), DownloadOptions::default()))
+            .collect();
+        let (group_id, ids) = db.insert_group_with_downloads(&group, &items).unwrap();
+
+        db.fail_download(ids[0], &ErrorDetail::new(ErrorCode::NetworkError))
+            .unwrap();
+        db.fail_download(ids[1], &ErrorDetail::new(ErrorCode::PrivateVideo))
+            .unwrap();
+        db.update_download_status(ids[2], &DownloadStatus::Failed, Some("old error"))
+            .unwrap();
+
+        assert_eq!(db.retry_failed_in_group(group_id).unwrap(), 2);
+        let status = |id| db.get_download(id).unwrap().unwrap().status;
+        assert!(matches!(status(ids[0]), DownloadStatus::Pending));
+        assert!(matches!(status(ids[1]), DownloadStatus::Failed));
+        assert!(matches!(status(ids[2]), DownloadStatus::Pending));
+        assert!(matches!(status(ids[3]), DownloadStatus::Pending));
+        assert!(db
+            .get_download(ids[0])
+            .unwrap()
+            .unwrap()
+            .error_detail
+            .is_none());
+    }
+}
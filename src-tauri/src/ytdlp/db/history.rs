@@ -1,27 +1,167 @@
 use super::Database;
 use crate::modules::types::AppError;
 use crate::ytdlp::types::*;
-use rusqlite::params;
+use rusqlite::types::Value;
+use rusqlite::{params, OptionalExtension};
+
+fn map_history_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryItem> {
+    Ok(HistoryItem {
+        id: row.get(0)?,
+        video_url: row.get(1)?,
+        video_id: row.get(2)?,
+        title: row.get(3)?,
+        quality_label: row.get(4)?,
+        format: row.get(5)?,
+        file_path: row.get(6)?,
+        file_size: row.get(7)?,
+        downloaded_at: row.get(8)?,
+        duration: row.get(9)?,
+        verify_status: VerifyStatus::parse(&row.get::<_, String>(10)?),
+        verify_issues: row.get(11)?,
+        verified_at: row.get(12)?,
+        content_hash: row.get(13)?,
+        channel: row.get(14)?,
+        extractor: row.get(15)?,
+        channel_url: row.get(16)?,
+        thumbnail: row.get(17)?,
+        upload_date: row.get(18)?,
+        playlist_id: row.get(19)?,
+        playlist_title: row.get(20)?,
+        download_secs: row.get(21)?,
+        canonical_extractor: row.get(22)?,
+        canonical_id: row.get(23)?,
+        expected_streams: match (row.get(24)?, row.get(25)?) {
+            (Some(video), Some(audio)) => Some(ExpectedStreams { video, audio }),
+            _ => None,
+        },
+    })
+}
+
+/// Insert a history row; shared by `insert_history` and `complete_and_record`.
+pub(super) fn insert_history_row(
+    conn: &rusqlite::Connection,
+    item: &HistoryItem,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO history (video_url, video_id, title, quality_label, format, file_path, file_size, downloaded_at, duration, verify_status, verify_issues, verified_at, content_hash,
+                              channel, extractor, channel_url, thumbnail, upload_date, playlist_id, playlist_title, download_secs,
+                              canonical_extractor, canonical_id, expects_video, expects_audio)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)",
+        params![
+            item.video_url,
+            item.video_id,
+            item.title,
+            item.quality_label,
+            item.format,
+            item.file_path,
+            item.file_size,
+            item.downloaded_at,
+            item.duration,
+            item.verify_status.to_string(),
+            item.verify_issues,
+            item.verified_at,
+            item.content_hash,
+            item.channel,
+            item.extractor,
+            item.channel_url,
+            item.thumbnail,
+            item.upload_date,
+            item.playlist_id,
+            item.playlist_title,
+            item.download_secs,
+            item.canonical_extractor,
+            item.canonical_id,
+            item.expected_streams.map(|s| s.video),
+            item.expected_streams.map(|s| s.audio),
+        ],
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    Ok(())
+}
+
+/// Turn free-text input into an FTS5 query: every whitespace-separated term must match
+/// as a prefix. Terms are quoted so FTS syntax characters in titles are taken literally.
+/// Returns None if no searchable term remains.
+pub(super) fn fts_query(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split_whitespace()
+        .map(|t| t.replace('"', "\"\""))
+        .filter(|t| t.chars().any(|c| c.is_alphanumeric()))
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Build the WHERE clause and its positional values for a history filter.
+/// Returns None when the filter can't match any row.
+fn history_where_clause(filter: &HistoryFilter) -> Option<(String, Vec<Value>)> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    let mut push = |condition: &str, value: Value| {
+        values.push(value);
+        conditions.push(condition.replace('?', &format!("?{}", values.len())));
+    };
+
+    if let Some(search) = filter.search.as_deref().filter(|s| !s.trim().is_empty()) {
+        push(
+            "id IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)",
+            Value::Text(fts_query(search)?),
+        );
+    }
+    if let Some(after) = filter.downloaded_after {
+        push("downloaded_at >= ?", Value::Integer(after));
+    }
+    if let Some(before) = filter.downloaded_before {
+        push("downloaded_at <= ?", Value::Integer(before));
+    }
+    if let Some(min) = filter.min_size {
+        push("file_size >= ?", Value::Integer(min as i64));
+    }
+    if let Some(max) = filter.max_size {
+        push("file_size <= ?", Value::Integer(max as i64));
+    }
+    if let Some(format) = &filter.format {
+        push("format = ?", Value::Text(format.clone()));
+    }
+    if let Some(quality) = &filter.quality_label {
+        push("quality_label = ?", Value::Text(quality.clone()));
+    }
+    if let Some(extractor) = &filter.extractor {
+        push("extractor = ?", Value::Text(extractor.to_lowercase()));
+    }
+    if let Some(channel) = &filter.channel {
+        push("channel = ?", Value::Text(channel.clone()));
+    }
+    if let Some(playlist_id) = &filter.playlist_id {
+        push("playlist_id = ?", Value::Text(playlist_id.clone()));
+    }
+    match filter.file_status {
+        Some(HistoryFileFilter::Present) => {
+            conditions.push("verify_status != 'missing'".to_string())
+        }
+        Some(HistoryFileFilter::Missing) => {
+            conditions.push("verify_status = 'missing'".to_string())
+        }
+        None => {}
+    }
+
+    if conditions.is_empty() {
+        Some((String::new(), values))
+    } else {
+        Some((format!("WHERE {}", conditions.join(" AND ")), values))
+    }
+}
+
+const HISTORY_COLUMNS: &str = "id, video_url, video_id, title, quality_label, format, file_path, file_size, downloaded_at, duration, verify_status, verify_issues, verified_at, content_hash, channel, extractor, channel_url, thumbnail, upload_date, playlist_id, playlist_title, download_secs, canonical_extractor, canonical_id, expects_video, expects_audio";
 
 impl Database {
     pub fn insert_history(&self, item: &HistoryItem) -> Result<u64, AppError> {
         let conn = self.conn();
-
-        conn.execute(
-            "INSERT INTO history (video_url, video_id, title, quality_label, format, file_path, file_size, downloaded_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                item.video_url,
-                item.video_id,
-                item.title,
-                item.quality_label,
-                item.format,
-                item.file_path,
-                item.file_size,
-                item.downloaded_at,
-            ],
-        ).map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
+        insert_history_row(&conn, item)?;
         Ok(conn.last_insert_rowid() as u64)
     }
 
@@ -29,74 +169,57 @@ impl Database {
         &self,
         page: u32,
         page_size: u32,
-        search: Option<&str>,
+        filter: &HistoryFilter,
     ) -> Result<HistoryResult, AppError> {
         let page_size = page_size.clamp(1, 100);
-        let conn = self.conn();
-
-        let (where_clause, search_param) = if let Some(s) = search {
-            let escaped = s
-                .replace('\\', "\\\\")
-                .replace('%', "\\%")
-                .replace('_', "\\_");
-            ("WHERE title LIKE ?1 ESCAPE '\\'", format!("%{}%", escaped))
-        } else {
-            ("", String::new())
+        // A search with no searchable terms (only punctuation) can't match anything
+        let Some((where_clause, mut values)) = history_where_clause(filter) else {
+            return Ok(HistoryResult {
+                items: Vec::new(),
+                total_count: 0,
+                page,
+                page_size,
+            });
         };
 
-        let total_count: u64 = if search.is_some() {
-            conn.query_row(
+        let conn = self.read_conn();
+
+        let total_count: u64 = conn
+            .query_row(
                 &format!("SELECT COUNT(*) FROM history {}", where_clause),
-                params![search_param],
+                rusqlite::params_from_iter(values.iter()),
                 |row| row.get(0),
             )
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?
-        } else {
-            conn.query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
-                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let direction = if filter.sort_ascending { "ASC" } else { "DESC" };
+        let order_by = match filter.sort_by.unwrap_or(HistorySort::Date) {
+            HistorySort::Date => format!("downloaded_at {0}, id {0}", direction),
+            HistorySort::Size => format!("file_size {0}, id {0}", direction),
+            HistorySort::Title => format!("title COLLATE NOCASE {0}, id {0}", direction),
         };
 
         let offset = page * page_size;
         let query = format!(
-            "SELECT id, video_url, video_id, title, quality_label, format, file_path, file_size, downloaded_at
-             FROM history
-             {}
-             ORDER BY downloaded_at DESC
-             LIMIT ?{} OFFSET ?{}",
+            "SELECT {} FROM history {} ORDER BY {} LIMIT ?{} OFFSET ?{}",
+            HISTORY_COLUMNS,
             where_clause,
-            if search.is_some() { "2" } else { "1" },
-            if search.is_some() { "3" } else { "2" }
+            order_by,
+            values.len() + 1,
+            values.len() + 2
         );
+        values.push(Value::Integer(page_size as i64));
+        values.push(Value::Integer(offset as i64));
 
         let mut stmt = conn
             .prepare(&query)
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-        fn map_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryItem> {
-            Ok(HistoryItem {
-                id: row.get(0)?,
-                video_url: row.get(1)?,
-                video_id: row.get(2)?,
-                title: row.get(3)?,
-                quality_label: row.get(4)?,
-                format: row.get(5)?,
-                file_path: row.get(6)?,
-                file_size: row.get(7)?,
-                downloaded_at: row.get(8)?,
-            })
-        }
-
-        let items = if search.is_some() {
-            stmt.query_map(params![search_param, page_size, offset], map_row)
-                .map_err(|e| AppError::DatabaseError(e.to_string()))?
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| AppError::DatabaseError(e.to_string()))?
-        } else {
-            stmt.query_map(params![page_size, offset], map_row)
-                .map_err(|e| AppError::DatabaseError(e.to_string()))?
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| AppError::DatabaseError(e.to_string()))?
-        };
+        let items = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), map_history_row)
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
         Ok(HistoryResult {
             items,
@@ -106,11 +229,30 @@ impl Database {
         })
     }
 
+    /// Distinct extractors (sites) present in history, for the site filter.
+    pub fn get_history_extractors(&self) -> Result<Vec<String>, AppError> {
+        let conn = self.read_conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT DISTINCT extractor FROM history WHERE extractor IS NOT NULL ORDER BY extractor",
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let extractors = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(extractors)
+    }
+
+    /// Whether a pending, downloading or paused task has the given video ID.
     pub fn check_duplicate_in_queue(&self, video_id: &str) -> Result<bool, AppError> {
         let conn = self.conn();
         let count: i64 = conn
             .query_row(
-                "SELECT COUNT(*) FROM downloads WHERE video_id = ?1 AND status IN ('pending', 'downloading')",
+                "SELECT COUNT(*) FROM downloads WHERE video_id = ?1 AND status IN ('pending', 'downloading', 'paused')",
                 [video_id],
                 |row| row.get(0),
             )
@@ -118,29 +260,34 @@ impl Database {
         Ok(count > 0)
     }
 
-    pub fn check_duplicate(&self, video_id: &str) -> Result<Option<HistoryItem>, AppError> {
+    /// Video IDs of every pending, downloading or paused task (for bulk duplicate checks).
+    pub fn get_queued_video_ids(&self) -> Result<std::collections::HashSet<String>, AppError> {
         let conn = self.conn();
-        let mut stmt = conn.prepare(
-            "SELECT id, video_url, video_id, title, quality_label, format, file_path, file_size, downloaded_at
-             FROM history
-             WHERE video_id = ?1
-             ORDER BY downloaded_at DESC
-             LIMIT 1"
-        ).map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
-        let result = stmt.query_row([video_id], |row| {
-            Ok(HistoryItem {
-                id: row.get(0)?,
-                video_url: row.get(1)?,
-                video_id: row.get(2)?,
-                title: row.get(3)?,
-                quality_label: row.get(4)?,
-                format: row.get(5)?,
-                file_path: row.get(6)?,
-                file_size: row.get(7)?,
-                downloaded_at: row.get(8)?,
-            })
-        });
+        let mut stmt = conn
+            .prepare(
+                "SELECT DISTINCT video_id FROM downloads WHERE video_id != '' AND status IN ('pending', 'downloading', 'paused')",
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let ids = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<std::collections::HashSet<String>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(ids)
+    }
+
+    pub fn check_duplicate(&self, video_id: &str) -> Result<Option<HistoryItem>, AppError> {
+        let conn = self.read_conn();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history WHERE video_id = ?1 ORDER BY downloaded_at DESC LIMIT 1",
+                HISTORY_COLUMNS
+            ))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let result = stmt.query_row([video_id], map_history_row);
 
         match result {
             Ok(item) => Ok(Some(item)),
@@ -149,6 +296,63 @@ impl Database {
         }
     }
 
+    /// Most recent history entry with the given canonical identity, whichever URL form
+    /// it was downloaded from.
+    pub fn find_canonical_in_history(
+        &self,
+        extractor: &str,
+        video_id: &str,
+    ) -> Result<Option<HistoryItem>, AppError> {
+        let conn = self.read_conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM history WHERE canonical_extractor = ?1 AND canonical_id = ?2
+                 ORDER BY downloaded_at DESC LIMIT 1",
+                HISTORY_COLUMNS
+            ),
+            params![extractor, video_id],
+            map_history_row,
+        )
+        .optional()
+        .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// Whether a pending, downloading or paused task has the given canonical identity.
+    pub fn canonical_in_queue(&self, extractor: &str, video_id: &str) -> Result<bool, AppError> {
+        let conn = self.conn();
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM downloads
+                 WHERE canonical_extractor = ?1 AND canonical_id = ?2
+                   AND status IN ('pending', 'downloading', 'paused')",
+                params![extractor, video_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(count > 0)
+    }
+
+    /// Canonical identities of every pending, downloading or paused task.
+    pub fn get_queued_canonical_keys(
+        &self,
+    ) -> Result<std::collections::HashSet<(String, String)>, AppError> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT DISTINCT canonical_extractor, canonical_id FROM downloads
+                 WHERE canonical_id IS NOT NULL AND status IN ('pending', 'downloading', 'paused')",
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let keys = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<std::collections::HashSet<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(keys)
+    }
+
     pub fn delete_history(&self, id: u64) -> Result<(), AppError> {
         let conn = self.conn();
 
@@ -157,4 +361,323 @@ impl Database {
 
         Ok(())
     }
+
+    /// Load every history row (used by library-wide maintenance such as re-verification).
+    pub fn get_all_history(&self) -> Result<Vec<HistoryItem>, AppError> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history ORDER BY downloaded_at DESC",
+                HISTORY_COLUMNS
+            ))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let items = stmt
+            .query_map([], map_history_row)
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(items)
+    }
+
+    pub fn update_history_verification(
+        &self,
+        id: u64,
+        result: &VerificationResult,
+        verified_at: i64,
+    ) -> Result<(), AppError> {
+        let conn = self.conn();
+        let issues = if result.issues.is_empty() {
+            None
+        } else {
+            Some(result.issues.join("\n"))
+        };
+
+        conn.execute(
+            "UPDATE history SET verify_status = ?1, verify_issues = ?2, verified_at = ?3 WHERE id = ?4",
+            params![result.status.to_string(), issues, verified_at, id],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub fn get_history_item(&self, id: u64) -> Result<Option<HistoryItem>, AppError> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM history WHERE id = ?1", HISTORY_COLUMNS),
+            params![id],
+            map_history_row,
+        )
+        .optional()
+        .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub fn update_history_hash(&self, id: u64, content_hash: &str) -> Result<(), AppError> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE history SET content_hash = ?1 WHERE id = ?2",
+            params![content_hash, id],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// History rows sharing a content hash with another row (other than `exclude_id`).
+    pub fn find_history_by_hash(
+        &self,
+        content_hash: &str,
+        exclude_id: Option<u64>,
+    ) -> Result<Vec<HistoryItem>, AppError> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history WHERE content_hash = ?1 AND id != ?2 ORDER BY downloaded_at ASC",
+                HISTORY_COLUMNS
+            ))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let items = stmt
+            .query_map(
+                params![content_hash, exclude_id.unwrap_or(0)],
+                map_history_row,
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(items)
+    }
+
+    /// All history rows whose content hash occurs more than once,
+    /// ordered by hash and then oldest first.
+    pub fn get_hash_duplicates(&self) -> Result<Vec<HistoryItem>, AppError> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history
+                 WHERE content_hash IN (
+                     SELECT content_hash FROM history
+                     WHERE content_hash IS NOT NULL
+                     GROUP BY content_hash HAVING COUNT(*) > 1
+                 )
+                 ORDER BY content_hash, downloaded_at ASC, id ASC",
+                HISTORY_COLUMNS
+            ))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let items = stmt
+            .query_map([], map_history_row)
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(items)
+    }
+
+    /// Point a history row at a file found elsewhere. The verification result no longer
+    /// applies to the new file, so it is reset to unverified.
+    pub fn relink_history(
+        &self,
+        id: u64,
+        file_path: &str,
+        file_size: Option<u64>,
+    ) -> Result<(), AppError> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE history SET file_path = ?1, file_size = COALESCE(?2, file_size),
+                 verify_status = 'unverified', verify_issues = NULL, verified_at = NULL
+             WHERE id = ?3",
+            params![file_path, file_size, id],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Mark history rows whose file could not be found anywhere.
+    pub fn mark_history_missing(&self, ids: &[u64], verified_at: i64) -> Result<(), AppError> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "UPDATE history SET verify_status = 'missing', verify_issues = 'File not found', verified_at = ?1
+                     WHERE id = ?2",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            for id in ids {
+                stmt.execute(params![verified_at, id])
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Rewrite the file paths of many history rows in a single transaction.
+    pub fn update_history_paths(&self, paths: &[(u64, String)]) -> Result<(), AppError> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        {
+            let mut stmt = tx
+                .prepare("UPDATE history SET file_path = ?1 WHERE id = ?2")
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            for (id, path) in paths {
+                stmt.execute(params![path, id])
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Insert many history rows in a single transaction (library import).
+    pub fn insert_history_batch(&self, items: &[HistoryItem]) -> Result<u64, AppError> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        for item in items {
+            insert_history_row(&tx, item)?;
+        }
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(items.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{download_request, history_item, temp_database};
+    use super::*;
+    use rusqlite::Connection;
+
+    fn search(db: &Database, text: &str) -> Vec<String> {
+        let filter = HistoryFilter {
+            search: Some(text.to_string()),
+            ..Default::default()
+        };
+        db.get_history(0, 100, &filter)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|h| h.video_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_paused_tasks_count_as_queued() {
+        let (_dir, db) = temp_database();
+        let id = db
+            .insert_download(
+                &download_request("aaaaaaaaaaa"),
+                &DownloadOptions::default(),
+            )
+            .unwrap();
+        db.update_download_status(id, &DownloadStatus::Paused, None)
+            .unwrap();
+        assert!(db.check_duplicate_in_queue("aaaaaaaaaaa").unwrap());
+        assert!(db.get_queued_video_ids().unwrap().contains("aaaaaaaaaaa"));
+
+        db.update_download_status(id, &DownloadStatus::Failed, None)
+            .unwrap();
+        assert!(!db.check_duplicate_in_queue("aaaaaaaaaaa").unwrap());
+    }
+
+    #[test]
+    fn test_fts_query_escaping() {
+        assert_eq!(
+            fts_query("hello  world").as_deref(),
+            Some("\"hello\"* \"world\"*")
+        );
+        assert_eq!(
+            fts_query("say \"hi\"").as_deref(),
+            Some("\"say\"* \"\"\"hi\"\"\"*")
+        );
+        // FTS operators and syntax are quoted as plain terms
+        assert_eq!(
+            fts_query("foo* OR NEAR(bar) -baz title:x").as_deref(),
+            Some("\"foo*\"* \"OR\"* \"NEAR(bar)\"* \"-baz\"* \"title:x\"*")
+        );
+        assert_eq!(fts_query("* - \"\" ()"), None);
+        assert_eq!(fts_query("   "), None);
+    }
+
+    #[test]
+    fn test_fts_search_with_syntax_characters() {
+        let (_dir, db) = temp_database();
+        let mut quoted = history_item("aaaaaaaaaaa", 1);
+        quoted.title = "C++ \"Quoted\" Tutorial".to_string();
+        let mut plain = history_item("bbbbbbbbbbb", 2);
+        plain.title = "Cats OR Dogs".to_string();
+        db.insert_history_batch(&[quoted, plain]).unwrap();
+
+        assert_eq!(search(&db, "\"quoted\""), ["aaaaaaaaaaa"]);
+        assert_eq!(search(&db, "tut"), ["aaaaaaaaaaa"]);
+        assert_eq!(search(&db, "OR dog"), ["bbbbbbbbbbb"]);
+        assert!(search(&db, "NEAR( AND *").is_empty());
+        assert!(search(&db, "***").is_empty());
+    }
+
+    #[test]
+    fn test_history_where_clause_numbering() {
+        let filter = HistoryFilter {
+            search: Some("cat".to_string()),
+            min_size: Some(10),
+            extractor: Some("YouTube".to_string()),
+            file_status: Some(HistoryFileFilter::Missing),
+            ..Default::default()
+        };
+        let (clause, values) = history_where_clause(&filter).unwrap();
+        assert_eq!(
+            clause,
+            "WHERE id IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?1) \
+             AND file_size >= ?2 AND extractor = ?3 AND verify_status = 'missing'"
+        );
+        assert_eq!(
+            values,
+            [
+                Value::Text("\"cat\"*".to_string()),
+                Value::Integer(10),
+                Value::Text("youtube".to_string()),
+            ]
+        );
+
+        let (clause, values) = history_where_clause(&HistoryFilter::default()).unwrap();
+        assert!(clause.is_empty() && values.is_empty());
+
+        let unsearchable = HistoryFilter {
+            search: Some("!!".to_string()),
+            ..Default::default()
+        };
+        assert!(history_where_clause(&unsearchable).is_none());
+    }
+
+    #[test]
+    fn test_fts_migration_backfills_existing_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            // A database from before schema versioning: base tables only
+            let conn = Connection::open(dir.path().join("ytdlp.db")).unwrap();
+            Database::create_tables(&conn).unwrap();
+            conn.execute(
+                "INSERT INTO history (video_url, video_id, title, quality_label, format, file_path, downloaded_at)
+                 VALUES ('https://vimeo.com/76979871', '76979871', 'Mountain timelapse', '1080p', 'mp4', '/v/a.mp4', 1)",
+                [],
+            )
+            .unwrap();
+        }
+
+        let db = Database::new(dir.path()).unwrap();
+        assert_eq!(search(&db, "mountain"), ["76979871"]);
+        let item = db.get_all_history().unwrap().remove(0);
+        assert_eq!(item.extractor.as_deref(), Some("vimeo"));
+    }
 }
@@ -0,0 +1,58 @@
+use super::Database;
+use crate::modules::types::AppError;
+use crate::ytdlp::types::VideoInfo;
+use rusqlite::{params, OptionalExtension};
+
+impl Database {
+    /// Cached metadata for `key` if it was fetched at or after `fresh_since`.
+    /// Entries that no longer deserialize (older layouts) are treated as missing.
+    pub fn get_cached_metadata(
+        &self,
+        key: &str,
+        fresh_since: i64,
+    ) -> Result<Option<VideoInfo>, AppError> {
+        let conn = self.read_conn();
+        let info: Option<String> = conn
+            .query_row(
+                "SELECT info FROM metadata_cache WHERE cache_key = ?1 AND fetched_at >= ?2",
+                params![key, fresh_since],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(info.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    pub fn put_cached_metadata(
+        &self,
+        key: &str,
+        url: &str,
+        info: &VideoInfo,
+    ) -> Result<(), AppError> {
+        let json =
+            serde_json::to_string(info).map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata_cache (cache_key, url, info, fetched_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![key, url, json, chrono::Utc::now().timestamp()],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Delete cache entries fetched before `before`, or every entry when None.
+    /// Returns the number of entries removed.
+    pub fn evict_metadata_cache(&self, before: Option<i64>) -> Result<u64, AppError> {
+        let conn = self.conn();
+        let removed = match before {
+            Some(cutoff) => conn.execute(
+                "DELETE FROM metadata_cache WHERE fetched_at < ?1",
+                params![cutoff],
+            ),
+            None => conn.execute("DELETE FROM metadata_cache", []),
+        }
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(removed as u64)
+    }
+}
@@ -1,5 +1,12 @@
+mod backup;
+mod groups;
 mod history;
+mod metadata_cache;
 mod queue;
+mod retention;
+mod stats;
+
+pub(crate) use backup::{copy_database, open_snapshot, snapshot_to_file};
 
 use crate::modules::types::AppError;
 use rusqlite::Connection;
@@ -8,10 +15,13 @@ use std::sync::Mutex;
 
 pub struct Database {
     conn: Mutex<Connection>,
+    /// Separate connection for UI reads (queue pages, summaries, history) so they
+    /// don't wait behind download writes. WAL lets it read while the writer commits.
+    read_conn: Mutex<Connection>,
 }
 
 /// Current schema version. Increment when adding new migrations.
-const SCHEMA_VERSION: u32 = 3;
+const SCHEMA_VERSION: u32 = 14;
 
 impl Database {
     pub fn new(app_data_dir: &Path) -> Result<Self, AppError> {
@@ -23,11 +33,28 @@ impl Database {
         let conn =
             Connection::open(&db_path).map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
+        // WAL mode + relaxed sync for write performance;
+        // foreign keys are needed for ON DELETE CASCADE from download_groups to downloads
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             PRAGMA synchronous=NORMAL;
+             PRAGMA foreign_keys = ON;",
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
         Self::create_tables(&conn)?;
         Self::run_migrations(&conn)?;
 
+        // Opened after migrations so it always sees the current schema
+        let read_conn =
+            Connection::open(&db_path).map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        read_conn
+            .execute_batch("PRAGMA query_only = ON;")
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
         Ok(Self {
             conn: Mutex::new(conn),
+            read_conn: Mutex::new(read_conn),
         })
     }
 
@@ -59,6 +86,22 @@ impl Database {
         Ok(())
     }
 
+    /// Run one migration step and record its version in the same transaction, so a
+    /// failed step leaves the database at the previous version instead of half-applied.
+    fn migrate_step(
+        conn: &Connection,
+        version: u32,
+        migrate: impl FnOnce(&Connection) -> Result<(), AppError>,
+    ) -> Result<(), AppError> {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        migrate(&tx)?;
+        Self::set_schema_version(&tx, version)?;
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
     fn run_migrations(conn: &Connection) -> Result<(), AppError> {
         let current = Self::get_schema_version(conn)?;
 
@@ -68,27 +111,283 @@ impl Database {
         }
 
         if current < 2 {
-            // v2: Add indexes for performance
-            conn.execute_batch(
-                "CREATE INDEX IF NOT EXISTS idx_downloads_video_id ON downloads(video_id);
-                 CREATE INDEX IF NOT EXISTS idx_downloads_status ON downloads(status);
-                 CREATE INDEX IF NOT EXISTS idx_history_video_id ON history(video_id);
-                 CREATE INDEX IF NOT EXISTS idx_history_downloaded_at ON history(downloaded_at);",
-            )
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            Self::migrate_step(conn, 2, |conn| {
+                // v2: Add indexes for performance
+                conn.execute_batch(
+                    "CREATE INDEX IF NOT EXISTS idx_downloads_video_id ON downloads(video_id);
+                     CREATE INDEX IF NOT EXISTS idx_downloads_status ON downloads(status);
+                     CREATE INDEX IF NOT EXISTS idx_history_video_id ON history(video_id);
+                     CREATE INDEX IF NOT EXISTS idx_history_downloaded_at ON history(downloaded_at);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
         }
 
         if current < 3 {
-            // v3: Add indexes for queue pagination performance
-            conn.execute_batch(
-                "CREATE INDEX IF NOT EXISTS idx_downloads_completed_at ON downloads(completed_at);
-                 CREATE INDEX IF NOT EXISTS idx_downloads_created_at ON downloads(created_at);",
-            )
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            Self::migrate_step(conn, 3, |conn| {
+                // v3: Add indexes for queue pagination performance
+                conn.execute_batch(
+                    "CREATE INDEX IF NOT EXISTS idx_downloads_completed_at ON downloads(completed_at);
+                     CREATE INDEX IF NOT EXISTS idx_downloads_created_at ON downloads(created_at);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
         }
 
-        if current < SCHEMA_VERSION {
-            Self::set_schema_version(conn, SCHEMA_VERSION)?;
+        if current < 4 {
+            Self::migrate_step(conn, 4, |conn| {
+                // v4: Expected duration and post-download verification results
+                conn.execute_batch(
+                    "ALTER TABLE downloads ADD COLUMN duration INTEGER;
+                     ALTER TABLE history ADD COLUMN duration INTEGER;
+                     ALTER TABLE history ADD COLUMN verify_status TEXT NOT NULL DEFAULT 'unverified';
+                     ALTER TABLE history ADD COLUMN verify_issues TEXT;
+                     ALTER TABLE history ADD COLUMN verified_at INTEGER;",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 5 {
+            Self::migrate_step(conn, 5, |conn| {
+                // v5: Content hash of the downloaded file for hash-based dedup
+                conn.execute_batch(
+                    "ALTER TABLE history ADD COLUMN content_hash TEXT;
+                     CREATE INDEX IF NOT EXISTS idx_history_content_hash ON history(content_hash);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 6 {
+            Self::migrate_step(conn, 6, |conn| {
+                // v6: Download groups (playlists/channels enqueued as one unit)
+                conn.execute_batch(
+                    "CREATE TABLE IF NOT EXISTS download_groups (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        source_url TEXT NOT NULL,
+                        group_type TEXT NOT NULL,
+                        title TEXT NOT NULL,
+                        options TEXT,
+                        created_at INTEGER NOT NULL
+                     );
+                     ALTER TABLE downloads ADD COLUMN group_id INTEGER REFERENCES download_groups(id) ON DELETE CASCADE;
+                     CREATE INDEX IF NOT EXISTS idx_downloads_group_id ON downloads(group_id);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 7 {
+            Self::migrate_step(conn, 7, |conn| {
+                // v7: Per-download options snapshot (JSON-encoded DownloadOptions)
+                conn.execute_batch("ALTER TABLE downloads ADD COLUMN options TEXT;")
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 8 {
+            Self::migrate_step(conn, 8, |conn| {
+                // v8: Channel/extractor columns and an FTS5 index for history search.
+                // External-content FTS table kept in sync by triggers.
+                conn.execute_batch(
+                    "ALTER TABLE history ADD COLUMN channel TEXT;
+                     ALTER TABLE history ADD COLUMN extractor TEXT;
+                     ALTER TABLE downloads ADD COLUMN channel TEXT;
+                     CREATE INDEX IF NOT EXISTS idx_history_extractor ON history(extractor);
+                     CREATE INDEX IF NOT EXISTS idx_history_file_size ON history(file_size);
+                     CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+                        title, channel, video_id, video_url, file_path,
+                        content='history', content_rowid='id',
+                        tokenize='unicode61 remove_diacritics 2'
+                     );
+                     CREATE TRIGGER IF NOT EXISTS history_fts_ai AFTER INSERT ON history BEGIN
+                        INSERT INTO history_fts(rowid, title, channel, video_id, video_url, file_path)
+                        VALUES (new.id, new.title, new.channel, new.video_id, new.video_url, new.file_path);
+                     END;
+                     CREATE TRIGGER IF NOT EXISTS history_fts_ad AFTER DELETE ON history BEGIN
+                        INSERT INTO history_fts(history_fts, rowid, title, channel, video_id, video_url, file_path)
+                        VALUES ('delete', old.id, old.title, old.channel, old.video_id, old.video_url, old.file_path);
+                     END;
+                     CREATE TRIGGER IF NOT EXISTS history_fts_au
+                     AFTER UPDATE OF title, channel, video_id, video_url, file_path ON history BEGIN
+                        INSERT INTO history_fts(history_fts, rowid, title, channel, video_id, video_url, file_path)
+                        VALUES ('delete', old.id, old.title, old.channel, old.video_id, old.video_url, old.file_path);
+                        INSERT INTO history_fts(rowid, title, channel, video_id, video_url, file_path)
+                        VALUES (new.id, new.title, new.channel, new.video_id, new.video_url, new.file_path);
+                     END;",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+                // Backfill the site for existing rows from their URLs
+                let rows: Vec<(u64, String)> = {
+                    let mut stmt = conn
+                        .prepare("SELECT id, video_url FROM history WHERE extractor IS NULL")
+                        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                    let rows = stmt
+                        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                        .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                    rows
+                };
+                for (id, url) in rows {
+                    conn.execute(
+                        "UPDATE history SET extractor = ?1 WHERE id = ?2",
+                        rusqlite::params![crate::ytdlp::metadata::site_from_url(&url), id],
+                    )
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                }
+
+                conn.execute_batch("INSERT INTO history_fts(history_fts) VALUES ('rebuild');")
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 9 {
+            Self::migrate_step(conn, 9, |conn| {
+                // v9: Richer metadata (channel URL, thumbnail, upload date, source playlist)
+                conn.execute_batch(
+                    "ALTER TABLE downloads ADD COLUMN channel_url TEXT;
+                     ALTER TABLE downloads ADD COLUMN thumbnail TEXT;
+                     ALTER TABLE downloads ADD COLUMN upload_date TEXT;
+                     ALTER TABLE downloads ADD COLUMN playlist_id TEXT;
+                     ALTER TABLE downloads ADD COLUMN playlist_title TEXT;
+                     ALTER TABLE history ADD COLUMN channel_url TEXT;
+                     ALTER TABLE history ADD COLUMN thumbnail TEXT;
+                     ALTER TABLE history ADD COLUMN upload_date TEXT;
+                     ALTER TABLE history ADD COLUMN playlist_id TEXT;
+                     ALTER TABLE history ADD COLUMN playlist_title TEXT;
+                     CREATE INDEX IF NOT EXISTS idx_history_channel ON history(channel);
+                     CREATE INDEX IF NOT EXISTS idx_history_playlist_id ON history(playlist_id);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 10 {
+            Self::migrate_step(conn, 10, |conn| {
+                // v10: Timing and site columns for download statistics
+                conn.execute_batch(
+                    "ALTER TABLE downloads ADD COLUMN started_at INTEGER;
+                     ALTER TABLE downloads ADD COLUMN extractor TEXT;
+                     ALTER TABLE history ADD COLUMN download_secs INTEGER;
+                     CREATE INDEX IF NOT EXISTS idx_downloads_extractor_status ON downloads(extractor, status);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+                let rows: Vec<(u64, String)> = {
+                    let mut stmt = conn
+                        .prepare("SELECT id, video_url FROM downloads WHERE extractor IS NULL")
+                        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                    let rows = stmt
+                        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                        .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                    rows
+                };
+                for (id, url) in rows {
+                    conn.execute(
+                        "UPDATE downloads SET extractor = ?1 WHERE id = ?2",
+                        rusqlite::params![crate::ytdlp::metadata::site_from_url(&url), id],
+                    )
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                }
+                Ok(())
+            })?;
+        }
+
+        if current < 11 {
+            Self::migrate_step(conn, 11, |conn| {
+                // v11: Canonical (extractor, id) identity for cross-site duplicate detection
+                conn.execute_batch(
+                    "ALTER TABLE downloads ADD COLUMN canonical_extractor TEXT;
+                     ALTER TABLE downloads ADD COLUMN canonical_id TEXT;
+                     ALTER TABLE history ADD COLUMN canonical_extractor TEXT;
+                     ALTER TABLE history ADD COLUMN canonical_id TEXT;
+                     CREATE INDEX IF NOT EXISTS idx_downloads_canonical ON downloads(canonical_extractor, canonical_id);
+                     CREATE INDEX IF NOT EXISTS idx_history_canonical ON history(canonical_extractor, canonical_id);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+                // Old rows have no extractor_key, so derive what the URL alone can tell
+                for table in ["downloads", "history"] {
+                    let rows: Vec<(u64, String)> = {
+                        let mut stmt = conn
+                            .prepare(&format!("SELECT id, video_url FROM {}", table))
+                            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                        let rows = stmt
+                            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                        rows
+                    };
+                    for (id, url) in rows {
+                        if let Some((extractor, video_id)) =
+                            crate::ytdlp::metadata::canonical_identity(&url)
+                        {
+                            conn.execute(
+                                &format!(
+                                    "UPDATE {} SET canonical_extractor = ?1, canonical_id = ?2 WHERE id = ?3",
+                                    table
+                                ),
+                                rusqlite::params![extractor, video_id, id],
+                            )
+                            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                        }
+                    }
+                }
+                Ok(())
+            })?;
+        }
+
+        if current < 12 {
+            Self::migrate_step(conn, 12, |conn| {
+                // v12: Cache of fetched video metadata, keyed by canonical identity or URL
+                conn.execute_batch(
+                    "CREATE TABLE IF NOT EXISTS metadata_cache (
+                        cache_key TEXT PRIMARY KEY,
+                        url TEXT NOT NULL,
+                        info TEXT NOT NULL,
+                        fetched_at INTEGER NOT NULL
+                    );
+                    CREATE INDEX IF NOT EXISTS idx_metadata_cache_fetched_at ON metadata_cache(fetched_at);",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 13 {
+            Self::migrate_step(conn, 13, |conn| {
+                // v13: Coded failure (code, params, stderr) next to the plain error message
+                conn.execute_batch("ALTER TABLE downloads ADD COLUMN error_detail TEXT;")
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
+        }
+
+        if current < 14 {
+            Self::migrate_step(conn, 14, |conn| {
+                // v14: Streams the chosen format carries, so re-verification expects the
+                // same streams as the check at download time
+                conn.execute_batch(
+                    "ALTER TABLE history ADD COLUMN expects_video INTEGER;
+                     ALTER TABLE history ADD COLUMN expects_audio INTEGER;",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(())
+            })?;
         }
 
         Ok(())
@@ -99,12 +398,19 @@ impl Database {
         self.conn.lock().unwrap_or_else(|e| e.into_inner())
     }
 
-    /// Delete all data from downloads and history tables (used by factory reset).
+    /// Read-only connection for UI queries. Never hold it while acquiring `conn()`.
+    pub(super) fn read_conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.read_conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Delete all data from downloads, groups and history tables (used by factory reset).
     /// Uses the live connection instead of deleting DB files to avoid stale state.
     pub fn clear_all_data(&self) -> Result<(), AppError> {
         let conn = self.conn();
-        conn.execute_batch("DELETE FROM downloads; DELETE FROM history;")
-            .map_err(|e| AppError::DatabaseError(format!("Failed to clear database: {}", e)))?;
+        conn.execute_batch(
+            "DELETE FROM downloads; DELETE FROM download_groups; DELETE FROM history; DELETE FROM metadata_cache;",
+        )
+        .map_err(|e| AppError::DatabaseError(format!("Failed to clear database: {}", e)))?;
         // Reclaim disk space
         conn.execute_batch("VACUUM;")
             .map_err(|e| AppError::DatabaseError(format!("Failed to vacuum database: {}", e)))?;
@@ -154,3 +460,87 @@ impl Database {
 }
 
 use rusqlite::OptionalExtension;
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::temp_database;
+    use super::*;
+
+    #[test]
+    fn test_failed_migration_step_rolls_back() {
+        let (_dir, db) = temp_database();
+        let conn = db.conn();
+        let result = Database::migrate_step(&conn, SCHEMA_VERSION + 1, |conn| {
+            conn.execute_batch("ALTER TABLE history ADD COLUMN half_applied TEXT;")
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            conn.execute_batch("ALTER TABLE history ADD COLUMN title TEXT;")
+                .map_err(|e| AppError::DatabaseError(e.to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(Database::get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        let half_applied: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('history') WHERE name = 'half_applied'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(half_applied, 0);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::Database;
+    use crate::ytdlp::types::{DownloadRequest, HistoryItem, VerifyStatus};
+
+    /// A migrated database in its own temporary directory, deleted with the returned guard
+    pub fn temp_database() -> (tempfile::TempDir, Database) {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let db = Database::new(dir.path()).expect("open database");
+        (dir, db)
+    }
+
+    pub fn download_request(video_id: &str) -> DownloadRequest {
+        serde_json::from_value(serde_json::json!({
+            "videoUrl": format!("https://www.youtube.com/watch?v={}", video_id),
+            "videoId": video_id,
+            "title": format!("Video {}", video_id),
+            "formatId": "best",
+            "qualityLabel": "1080p",
+            "outputDir": null,
+            "cookieBrowser": null,
+        }))
+        .expect("download request")
+    }
+
+    pub fn history_item(video_id: &str, downloaded_at: i64) -> HistoryItem {
+        HistoryItem {
+            id: 0,
+            video_url: format!("https://www.youtube.com/watch?v={}", video_id),
+            video_id: video_id.to_string(),
+            title: format!("Video {}", video_id),
+            quality_label: "1080p".to_string(),
+            format: "mp4".to_string(),
+            file_path: format!("/downloads/{}.mp4", video_id),
+            file_size: Some(1_000_000),
+            downloaded_at,
+            duration: Some(60),
+            verify_status: VerifyStatus::Unverified,
+            verify_issues: None,
+            verified_at: None,
+            content_hash: None,
+            channel: None,
+            extractor: Some("youtube".to_string()),
+            channel_url: None,
+            thumbnail: None,
+            upload_date: None,
+            playlist_id: None,
+            playlist_title: None,
+            download_secs: None,
+            canonical_extractor: None,
+            canonical_id: None,
+            expected_streams: None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
+use super::history::insert_history_row;
 use super::Database;
-use crate::modules::types::AppError;
+use crate::modules::types::{AppError, ErrorDetail};
 use crate::ytdlp::types::*;
 use rusqlite::{params, OptionalExtension};
 
@@ -19,67 +20,106 @@ fn map_download_row(row: &rusqlite::Row) -> rusqlite::Result<DownloadTaskInfo> {
         error_message: row.get(11)?,
         created_at: row.get(12)?,
         completed_at: row.get(13)?,
+        duration: row.get(14)?,
+        group_id: row.get(15)?,
+        options: row
+            .get::<_, Option<String>>(16)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        channel: row.get(17)?,
+        channel_url: row.get(18)?,
+        thumbnail: row.get(19)?,
+        upload_date: row.get(20)?,
+        playlist_id: row.get(21)?,
+        playlist_title: row.get(22)?,
+        started_at: row.get(23)?,
+        canonical_extractor: row.get(24)?,
+        canonical_id: row.get(25)?,
+        error_detail: row
+            .get::<_, Option<String>>(26)?
+            .and_then(|json| serde_json::from_str(&json).ok()),
     })
 }
 
-const DOWNLOAD_COLUMNS: &str = "id, video_url, video_id, title, format_id, quality_label, output_path, status, progress, speed, eta, error_message, created_at, completed_at";
+fn insert_download_row(
+    conn: &rusqlite::Connection,
+    req: &DownloadRequest,
+    options: &DownloadOptions,
+    group_id: Option<u64>,
+    created_at: i64,
+) -> Result<u64, AppError> {
+    let (canonical_extractor, canonical_id) = req.canonical_identity().unzip();
+    conn.execute(
+        "INSERT INTO downloads (video_url, video_id, title, format_id, quality_label, output_path, created_at, duration, group_id, options,
+                                channel, channel_url, thumbnail, upload_date, playlist_id, playlist_title, extractor,
+                                canonical_extractor, canonical_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+        params![
+            req.video_url,
+            req.video_id,
+            req.title,
+            options.format_id,
+            req.quality_label,
+            options.output_template,
+            created_at,
+            req.duration,
+            group_id,
+            encode_options(options)?,
+            req.channel,
+            req.channel_url,
+            req.thumbnail,
+            req.upload_date,
+            req.playlist_id,
+            req.playlist_title,
+            crate::ytdlp::metadata::site_from_url(&req.video_url),
+            canonical_extractor,
+            canonical_id,
+        ],
+    )
+    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+    Ok(conn.last_insert_rowid() as u64)
+}
+
+pub(super) fn insert_download_rows(
+    tx: &rusqlite::Transaction,
+    items: &[(DownloadRequest, DownloadOptions)],
+    group_id: Option<u64>,
+) -> Result<Vec<u64>, AppError> {
+    let created_at = chrono::Utc::now().timestamp();
+    items
+        .iter()
+        .map(|(req, options)| insert_download_row(tx, req, options, group_id, created_at))
+        .collect()
+}
+
+fn encode_options(options: &DownloadOptions) -> Result<String, AppError> {
+    serde_json::to_string(options).map_err(|e| AppError::DatabaseError(e.to_string()))
+}
+
+const DOWNLOAD_COLUMNS: &str = "id, video_url, video_id, title, format_id, quality_label, output_path, status, progress, speed, eta, error_message, created_at, completed_at, duration, group_id, options, channel, channel_url, thumbnail, upload_date, playlist_id, playlist_title, started_at, canonical_extractor, canonical_id, error_detail";
 
 impl Database {
     pub fn insert_download(
         &self,
         req: &DownloadRequest,
-        output_path: &str,
+        options: &DownloadOptions,
     ) -> Result<u64, AppError> {
         let conn = self.conn();
         let created_at = chrono::Utc::now().timestamp();
-
-        conn.execute(
-            "INSERT INTO downloads (video_url, video_id, title, format_id, quality_label, output_path, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                req.video_url,
-                req.video_id,
-                req.title,
-                req.format_id,
-                req.quality_label,
-                output_path,
-                created_at,
-            ],
-        ).map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
-        Ok(conn.last_insert_rowid() as u64)
+        insert_download_row(&conn, req, options, None, created_at)
     }
 
     /// Insert multiple downloads in a single transaction for batch/playlist operations.
     pub fn insert_downloads_batch(
         &self,
-        items: &[(DownloadRequest, String)],
+        items: &[(DownloadRequest, DownloadOptions)],
     ) -> Result<Vec<u64>, AppError> {
         let mut conn = self.conn();
         let tx = conn
             .transaction()
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-        let created_at = chrono::Utc::now().timestamp();
-        let mut ids = Vec::with_capacity(items.len());
-
-        for (req, output_path) in items {
-            tx.execute(
-                "INSERT INTO downloads (video_url, video_id, title, format_id, quality_label, output_path, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                params![
-                    req.video_url,
-                    req.video_id,
-                    req.title,
-                    req.format_id,
-                    req.quality_label,
-                    output_path,
-                    created_at,
-                ],
-            )
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-            ids.push(tx.last_insert_rowid() as u64);
-        }
+        let ids = insert_download_rows(&tx, items, None)?;
 
         tx.commit()
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -87,6 +127,19 @@ impl Database {
         Ok(ids)
     }
 
+    /// Put a task that is still downloading back to pending with new options,
+    /// so the dispatcher runs it again from the start.
+    pub fn requeue_with_options(&self, id: u64, options: &DownloadOptions) -> Result<(), AppError> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE downloads SET status = 'pending', options = ?1, progress = 0, speed = NULL, eta = NULL
+             WHERE id = ?2 AND status = 'downloading'",
+            params![encode_options(options)?, id],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
     pub fn update_download_status(
         &self,
         id: u64,
@@ -96,7 +149,7 @@ impl Database {
         let conn = self.conn();
 
         conn.execute(
-            "UPDATE downloads SET status = ?1, error_message = ?2 WHERE id = ?3",
+            "UPDATE downloads SET status = ?1, error_message = ?2, error_detail = NULL WHERE id = ?3",
             params![status.to_string(), error_msg, id],
         )
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -104,6 +157,22 @@ impl Database {
         Ok(())
     }
 
+    /// Mark a download failed with a coded error. The English rendering goes in
+    /// `error_message` for plain readers; the code and parameters are kept as JSON.
+    pub fn fail_download(&self, id: u64, detail: &ErrorDetail) -> Result<(), AppError> {
+        let json =
+            serde_json::to_string(detail).map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        let conn = self.conn();
+
+        conn.execute(
+            "UPDATE downloads SET status = 'failed', error_message = ?1, error_detail = ?2 WHERE id = ?3",
+            params![detail.to_string(), json, id],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Conditionally cancel a download only if it is still in a cancellable state.
     /// Returns true if the status was actually updated, false if the task was already
     /// completed/failed (preventing overwrite of a completed download's status).
@@ -136,8 +205,39 @@ impl Database {
         Ok(())
     }
 
+    /// Write buffered live progress for several downloads in one transaction.
+    /// Rows that have left the 'downloading' state meanwhile are left untouched.
+    pub fn update_progress_batch(&self, updates: &[(u64, LiveProgress)]) -> Result<(), AppError> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        {
+            let mut stmt = tx
+                .prepare(
+                    "UPDATE downloads SET progress = ?1, speed = ?2, eta = ?3
+                     WHERE id = ?4 AND status = 'downloading'",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            for (id, live) in updates {
+                stmt.execute(params![live.progress, live.speed, live.eta, id])
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
     pub fn get_download_queue(&self) -> Result<Vec<DownloadTaskInfo>, AppError> {
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt = conn
             .prepare(&format!(
                 "SELECT {} FROM downloads ORDER BY created_at DESC",
@@ -159,7 +259,7 @@ impl Database {
 
         let deleted = conn
             .execute(
-                "DELETE FROM downloads WHERE status IN ('completed', 'cancelled', 'failed')",
+                "DELETE FROM downloads WHERE status IN ('completed', 'needs_attention', 'cancelled', 'failed')",
                 [],
             )
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -196,10 +296,13 @@ impl Database {
         Ok(())
     }
 
+    /// Mark a download as finished with the given final status (`Completed` or
+    /// `NeedsAttention`) and insert its history row in a single transaction.
     pub fn complete_and_record(
         &self,
         id: u64,
         completed_at: i64,
+        status: &DownloadStatus,
         history: &HistoryItem,
     ) -> Result<(), AppError> {
         let mut conn = self.conn();
@@ -208,26 +311,12 @@ impl Database {
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
         tx.execute(
-            "UPDATE downloads SET status = 'completed', completed_at = ?1, progress = 100.0 WHERE id = ?2",
-            params![completed_at, id],
+            "UPDATE downloads SET status = ?1, completed_at = ?2, progress = 100.0 WHERE id = ?3",
+            params![status.to_string(), completed_at, id],
         )
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-        tx.execute(
-            "INSERT INTO history (video_url, video_id, title, quality_label, format, file_path, file_size, downloaded_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                history.video_url,
-                history.video_id,
-                history.title,
-                history.quality_label,
-                history.format,
-                history.file_path,
-                history.file_size,
-                history.downloaded_at,
-            ],
-        )
-        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        insert_history_row(&tx, history)?;
 
         tx.commit()
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -263,10 +352,10 @@ impl Database {
         let claimed_id: Option<u64> = {
             let conn = self.conn();
             conn.query_row(
-                "UPDATE downloads SET status = 'downloading'
+                "UPDATE downloads SET status = 'downloading', started_at = ?1
                  WHERE id = (SELECT id FROM downloads WHERE status = 'pending' ORDER BY created_at ASC LIMIT 1)
                  RETURNING id",
-                [],
+                [chrono::Utc::now().timestamp()],
                 |row| row.get(0),
             )
             .optional()
@@ -327,7 +416,7 @@ impl Database {
     ) -> Result<QueueResult, AppError> {
         let page_size = page_size.clamp(1, 100);
         let offset = (page as u64) * (page_size as u64);
-        let conn = self.conn();
+        let conn = self.read_conn();
 
         // Get status counts in one query
         let mut count_stmt = conn
@@ -337,6 +426,7 @@ impl Database {
         let mut active_count: u64 = 0;
         let mut pending_count: u64 = 0;
         let mut completed_count: u64 = 0;
+        let mut needs_attention_count: u64 = 0;
         let mut failed_count: u64 = 0;
         let mut cancelled_count: u64 = 0;
 
@@ -352,6 +442,7 @@ impl Database {
                 "downloading" => active_count = count,
                 "pending" => pending_count = count,
                 "completed" => completed_count = count,
+                "needs_attention" => needs_attention_count = count,
                 "failed" => failed_count = count,
                 "cancelled" => cancelled_count = count,
                 _ => {}
@@ -411,13 +502,26 @@ impl Database {
             active_count,
             pending_count,
             completed_count,
+            needs_attention_count,
             failed_count,
             cancelled_count,
         })
     }
 
-    pub fn get_queue_summary(&self, recent_completed_limit: u32) -> Result<QueueSummary, AppError> {
-        let conn = self.conn();
+    /// Summarize the queue. With `by_group`, items belonging to a download group are left
+    /// out of `active_items` and reported through `groups` instead.
+    pub fn get_queue_summary(
+        &self,
+        recent_completed_limit: u32,
+        by_group: bool,
+    ) -> Result<QueueSummary, AppError> {
+        let groups = if by_group {
+            self.get_download_groups()?
+        } else {
+            Vec::new()
+        };
+
+        let conn = self.read_conn();
 
         // Get status counts
         let mut count_stmt = conn
@@ -427,6 +531,7 @@ impl Database {
         let mut active_count: u64 = 0;
         let mut pending_count: u64 = 0;
         let mut completed_count: u64 = 0;
+        let mut needs_attention_count: u64 = 0;
         let mut total_count: u64 = 0;
 
         let rows = count_stmt
@@ -442,15 +547,21 @@ impl Database {
                 "downloading" => active_count = count,
                 "pending" => pending_count = count,
                 "completed" => completed_count = count,
+                "needs_attention" => needs_attention_count = count,
                 _ => {}
             }
         }
 
         // Get active items (downloading + pending)
+        let group_filter = if by_group {
+            " AND group_id IS NULL"
+        } else {
+            ""
+        };
         let mut active_stmt = conn
             .prepare(&format!(
-                "SELECT {} FROM downloads WHERE status IN ('downloading', 'pending') ORDER BY created_at ASC",
-                DOWNLOAD_COLUMNS
+                "SELECT {} FROM downloads WHERE status IN ('downloading', 'pending'){} ORDER BY created_at ASC",
+                DOWNLOAD_COLUMNS, group_filter
             ))
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
@@ -463,7 +574,7 @@ impl Database {
         // Get recent completed
         let mut completed_stmt = conn
             .prepare(&format!(
-                "SELECT {} FROM downloads WHERE status = 'completed' ORDER BY completed_at DESC LIMIT ?1",
+                "SELECT {} FROM downloads WHERE status IN ('completed', 'needs_attention') ORDER BY completed_at DESC LIMIT ?1",
                 DOWNLOAD_COLUMNS
             ))
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
@@ -480,12 +591,14 @@ impl Database {
             active_count,
             pending_count,
             completed_count,
+            needs_attention_count,
             total_count,
+            groups,
         })
     }
 
     pub fn get_active_downloads(&self) -> Result<Vec<DownloadTaskInfo>, AppError> {
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt = conn
             .prepare(&format!(
                 "SELECT {} FROM downloads WHERE status IN ('downloading', 'pending') ORDER BY created_at ASC",
@@ -0,0 +1,185 @@
+use super::Database;
+use crate::modules::types::AppError;
+use crate::ytdlp::types::*;
+use rusqlite::params;
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+impl Database {
+    /// Delete finished queue rows and history entries according to the policy, in one
+    /// transaction. Returns (queue rows removed, history entries removed).
+    pub fn apply_retention(&self, policy: &RetentionPolicy) -> Result<(u64, u64), AppError> {
+        let now = chrono::Utc::now().timestamp();
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut queue_removed = 0u64;
+        // Failed rows never get completed_at, so fall back to when they last started
+        let rules = [
+            (policy.completed_days, "'completed', 'needs_attention'"),
+            (policy.failed_days, "'failed', 'cancelled'"),
+        ];
+        for (days, statuses) in rules {
+            if let Some(days) = days {
+                let cutoff = now - days as i64 * SECS_PER_DAY;
+                queue_removed += tx
+                    .execute(
+                        &format!(
+                            "DELETE FROM downloads
+                             WHERE status IN ({}) AND COALESCE(completed_at, started_at, created_at) < ?1",
+                            statuses
+                        ),
+                        params![cutoff],
+                    )
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                    as u64;
+            }
+        }
+        if queue_removed > 0 {
+            tx.execute(
+                "DELETE FROM download_groups
+                 WHERE NOT EXISTS (SELECT 1 FROM downloads WHERE downloads.group_id = download_groups.id)",
+                [],
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        }
+
+        let mut history_removed = 0u64;
+        if policy.prune_missing_history {
+            history_removed +=
+                tx.execute("DELETE FROM history WHERE verify_status = 'missing'", [])
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))? as u64;
+        }
+        if let Some(max) = policy.history_max_entries {
+            history_removed +=
+                tx.execute(
+                    "DELETE FROM history WHERE id NOT IN (
+                         SELECT id FROM history ORDER BY downloaded_at DESC, id DESC LIMIT ?1
+                     )",
+                    params![max],
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))? as u64;
+        }
+
+        tx.commit()
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok((queue_removed, history_removed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{download_request, history_item, temp_database};
+    use super::*;
+
+    const DAY: i64 = SECS_PER_DAY;
+
+    /// Insert a queue row with the given status that finished `age_days` ago
+    fn seed_download(db: &Database, video_id: &str, status: &str, age_days: i64) -> u64 {
+        let id = db
+            .insert_download(&download_request(video_id), &DownloadOptions::default())
+            .unwrap();
+        let finished = chrono::Utc::now().timestamp() - age_days * DAY;
+        let completed_at = (status == "completed").then_some(finished);
+        db.conn()
+            .execute(
+                "UPDATE downloads SET status = ?1, completed_at = ?2, started_at = ?3, created_at = ?3 WHERE id = ?4",
+                params![status, completed_at, finished, id],
+            )
+            .unwrap();
+        id
+    }
+
+    fn queue_ids(db: &Database) -> Vec<String> {
+        let mut ids: Vec<_> = db
+            .get_download_queue()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.video_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn history_ids(db: &Database) -> Vec<String> {
+        let mut ids: Vec<_> = db
+            .get_all_history()
+            .unwrap()
+            .into_iter()
+            .map(|h| h.video_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_retention_queue_rules() {
+        let (_dir, db) = temp_database();
+        seed_download(&db, "completed_old", "completed", 10);
+        seed_download(&db, "completed_new", "completed", 1);
+        seed_download(&db, "failed_old", "failed", 40);
+        seed_download(&db, "failed_new", "failed", 10);
+        seed_download(&db, "pending_old", "pending", 100);
+
+        let policy = RetentionPolicy {
+            completed_days: Some(7),
+            failed_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(db.apply_retention(&policy).unwrap(), (2, 0));
+        assert_eq!(
+            queue_ids(&db),
+            ["completed_new", "failed_new", "pending_old"]
+        );
+    }
+
+    #[test]
+    fn test_retention_history_rules() {
+        let (_dir, db) = temp_database();
+        let now = chrono::Utc::now().timestamp();
+        let items: Vec<_> = ["a", "b", "c", "d"]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| history_item(id, now - i as i64 * DAY))
+            .collect();
+        db.insert_history_batch(&items).unwrap();
+        db.conn()
+            .execute(
+                "UPDATE history SET verify_status = 'missing' WHERE video_id = 'b'",
+                [],
+            )
+            .unwrap();
+
+        let prune = RetentionPolicy {
+            prune_missing_history: true,
+            ..Default::default()
+        };
+        assert_eq!(db.apply_retention(&prune).unwrap(), (0, 1));
+        assert_eq!(history_ids(&db), ["a", "c", "d"]);
+
+        let cap = RetentionPolicy {
+            history_max_entries: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(db.apply_retention(&cap).unwrap(), (0, 1));
+        // The oldest entry goes first
+        assert_eq!(history_ids(&db), ["a", "c"]);
+    }
+
+    #[test]
+    fn test_retention_without_rules_keeps_everything() {
+        let (_dir, db) = temp_database();
+        seed_download(&db, "completed_old", "completed", 1000);
+        db.insert_history_batch(&[history_item("a", 0)]).unwrap();
+
+        assert_eq!(
+            db.apply_retention(&RetentionPolicy::default()).unwrap(),
+            (0, 0)
+        );
+        assert_eq!(queue_ids(&db), ["completed_old"]);
+        assert_eq!(history_ids(&db), ["a"]);
+    }
+}
@@ -0,0 +1,263 @@
+use super::Database;
+use crate::modules::types::AppError;
+use crate::ytdlp::types::*;
+use rusqlite::Connection;
+
+/// Period labels over `history.downloaded_at`, in local time
+const DAY_PERIOD: &str = "date(downloaded_at, 'unixepoch', 'localtime')";
+/// Weeks are labelled by their Monday, so a week spanning New Year stays one bucket
+/// ('weekday 0' moves forward to Sunday, the week's last day)
+const WEEK_PERIOD: &str = "date(downloaded_at, 'unixepoch', 'localtime', 'weekday 0', '-6 days')";
+const MONTH_PERIOD: &str = "strftime('%Y-%m', downloaded_at, 'unixepoch', 'localtime')";
+
+/// Bytes and file counts from history grouped by a period label expression.
+/// `since` is a list of SQLite date modifiers applied to 'now' (local) for the window start.
+fn period_stats(
+    conn: &Connection,
+    period: &str,
+    since: &str,
+) -> Result<Vec<StatsPeriod>, AppError> {
+    let sql = format!(
+        "SELECT {} AS period, COALESCE(SUM(file_size), 0), COUNT(*)
+         FROM history
+         WHERE downloaded_at >= CAST(strftime('%s', 'now', 'localtime', {}, 'utc') AS INTEGER)
+         GROUP BY period ORDER BY period ASC",
+        period, since
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(StatsPeriod {
+                period: row.get(0)?,
+                bytes: row.get(1)?,
+                files: row.get(2)?,
+            })
+        })
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    Ok(rows)
+}
+
+impl Database {
+    /// Aggregate download statistics. Every figure is computed in SQL over indexed
+    /// columns (`downloaded_at`, `status`, `extractor`, `channel`).
+    pub fn get_download_statistics(
+        &self,
+        top_channels: u32,
+    ) -> Result<DownloadStatistics, AppError> {
+        let conn = self.read_conn();
+
+        let bytes_per_day = period_stats(&conn, DAY_PERIOD, "'start of day', '-29 days'")?;
+        let bytes_per_week = period_stats(
+            &conn,
+            WEEK_PERIOD,
+            "'start of day', '-6 days', 'weekday 1', '-77 days'",
+        )?;
+        let bytes_per_month = period_stats(&conn, MONTH_PERIOD, "'start of month', '-11 months'")?;
+
+        let status_counts = {
+            let mut stmt = conn
+                .prepare("SELECT status, COUNT(*) FROM downloads GROUP BY status ORDER BY status")
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok(StatusCount {
+                        status: DownloadStatus::parse(&row.get::<_, String>(0)?),
+                        count: row.get(1)?,
+                    })
+                })
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            rows
+        };
+
+        let extractors = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT COALESCE(extractor, 'unknown') AS site,
+                            SUM(status IN ('completed', 'needs_attention')),
+                            SUM(status = 'failed'),
+                            SUM(status = 'cancelled')
+                     FROM downloads
+                     GROUP BY site ORDER BY COUNT(*) DESC, site ASC",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    let succeeded: u64 = row.get(1)?;
+                    let failed: u64 = row.get(2)?;
+                    let finished = succeeded + failed;
+                    Ok(ExtractorStats {
+                        extractor: row.get(0)?,
+                        succeeded,
+                        failed,
+                        cancelled: row.get(3)?,
+                        success_rate: (finished > 0).then(|| succeeded as f64 / finished as f64),
+                    })
+                })
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            rows
+        };
+
+        let top_channels = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT channel, COUNT(*) AS files, COALESCE(SUM(file_size), 0) AS bytes
+                     FROM history
+                     WHERE channel IS NOT NULL AND channel != ''
+                     GROUP BY channel ORDER BY files DESC, bytes DESC LIMIT ?1",
+                )
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            let rows = stmt
+                .query_map([top_channels], |row| {
+                    Ok(ChannelStats {
+                        channel: row.get(0)?,
+                        files: row.get(1)?,
+                        bytes: row.get(2)?,
+                    })
+                })
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            rows
+        };
+
+        let average_throughput: Option<f64> = conn
+            .query_row(
+                "SELECT CAST(SUM(file_size) AS REAL) / SUM(download_secs)
+                 FROM history
+                 WHERE download_secs > 0 AND file_size IS NOT NULL",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let (library_files, library_bytes): (u64, u64) = conn
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(file_size), 0) FROM history WHERE verify_status != 'missing'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(DownloadStatistics {
+            bytes_per_day,
+            bytes_per_week,
+            bytes_per_month,
+            status_counts,
+            extractors,
+            top_channels,
+            average_throughput,
+            library_bytes,
+            library_files,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{download_request, history_item, temp_database};
+    use super::*;
+    use chrono::TimeZone;
+    use rusqlite::params;
+
+    fn local_noon(year: i32, month: u32, day: u32) -> i64 {
+        chrono::Local
+            .with_ymd_and_hms(year, month, day, 12, 0, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn test_week_buckets_span_new_year() {
+        let (_dir, db) = temp_database();
+        let items = [
+            history_item("a", local_noon(2025, 12, 29)),
+            history_item("b", local_noon(2026, 1, 1)),
+            history_item("c", local_noon(2026, 1, 4)),
+            history_item("d", local_noon(2026, 1, 5)),
+        ];
+        db.insert_history_batch(&items).unwrap();
+
+        let weeks = period_stats(&db.read_conn(), WEEK_PERIOD, "'-100 years'").unwrap();
+        let buckets: Vec<_> = weeks.iter().map(|w| (w.period.as_str(), w.files)).collect();
+        assert_eq!(buckets, [("2025-12-29", 3), ("2026-01-05", 1)]);
+    }
+
+    #[test]
+    fn test_download_statistics_aggregates() {
+        let (_dir, db) = temp_database();
+        let now = chrono::Utc::now().timestamp();
+        let mut items = vec![
+            history_item("a", now),
+            history_item("b", now),
+            history_item("c", now),
+        ];
+        items[0].channel = Some("Alpha".to_string());
+        items[0].download_secs = Some(10);
+        items[1].channel = Some("Alpha".to_string());
+        items[1].download_secs = Some(30);
+        items[2].channel = Some("Beta".to_string());
+        items[2].file_size = Some(4_000_000);
+        db.insert_history_batch(&items).unwrap();
+        db.conn()
+            .execute(
+                "UPDATE history SET verify_status = 'missing' WHERE video_id = 'c'",
+                [],
+            )
+            .unwrap();
+
+        for (video_id, status, extractor) in [
+            ("q1", "completed", "youtube"),
+            ("q2", "completed", "youtube"),
+            ("q3", "failed", "youtube"),
+            ("q4", "cancelled", "vimeo"),
+        ] {
+            let id = db
+                .insert_download(&download_request(video_id), &DownloadOptions::default())
+                .unwrap();
+            db.conn()
+                .execute(
+                    "UPDATE downloads SET status = ?1, extractor = ?2 WHERE id = ?3",
+                    params![status, extractor, id],
+                )
+                .unwrap();
+        }
+
+        let stats = db.get_download_statistics(1).unwrap();
+
+        let day_bytes: u64 = stats.bytes_per_day.iter().map(|p| p.bytes).sum();
+        assert_eq!(day_bytes, 6_000_000);
+        let month_files: u64 = stats.bytes_per_month.iter().map(|p| p.files).sum();
+        assert_eq!(month_files, 3);
+
+        assert_eq!(stats.top_channels.len(), 1);
+        assert_eq!(stats.top_channels[0].channel, "Alpha");
+        assert_eq!(stats.top_channels[0].files, 2);
+        assert_eq!(stats.top_channels[0].bytes, 2_000_000);
+
+        // 2 MB over 40 seconds
+        assert_eq!(stats.average_throughput, Some(50_000.0));
+        assert_eq!((stats.library_files, stats.library_bytes), (2, 2_000_000));
+
+        let youtube = &stats.extractors[0];
+        assert_eq!(youtube.extractor, "youtube");
+        assert_eq!((youtube.succeeded, youtube.failed), (2, 1));
+        assert_eq!(youtube.success_rate, Some(2.0 / 3.0));
+        let vimeo = &stats.extractors[1];
+        assert_eq!((vimeo.cancelled, vimeo.success_rate), (1, None));
+
+        let completed = stats
+            .status_counts
+            .iter()
+            .find(|s| matches!(s.status, DownloadStatus::Completed))
+            .unwrap();
+        assert_eq!(completed.count, 2);
+    }
+}
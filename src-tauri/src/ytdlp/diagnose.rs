@@ -0,0 +1,543 @@
+use crate::modules::types::{ErrorCode, ErrorDetail};
+use crate::ytdlp::security;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// `[extractor] id: message`, what follows `ERROR:` when an extractor raised it
+static EXTRACTOR_PREFIX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\[([\w:.-]+)\]\s+(?:([@\w.-]+):\s+)?(.*)$").expect("Invalid regex"));
+
+static HTTP_STATUS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)HTTP Error (\d{3})").expect("Invalid regex"));
+
+/// Lowercase markers per error code, checked in order against the error lines.
+/// Specific causes come before the generic "not available" wording they often include.
+const RULES: &[(ErrorCode, &[&str])] = &[
+    (
+        ErrorCode::CookieCopyFailed,
+        &[
+            "cookie database",
+            "cookies database",
+            "failed to decrypt with dpapi",
+        ],
+    ),
+    (
+        ErrorCode::RateLimited,
+        &[
+            "http error 429",
+            "too many requests",
+            "not a bot",
+            "rate-limited",
+            "rate limited",
+            // "This content isn't available, try again later" is a temporary block
+            "try again later",
+        ],
+    ),
+    (ErrorCode::PrivateVideo, &["private video"]),
+    (
+        ErrorCode::AgeRestricted,
+        &[
+            "confirm your age",
+            "age-restricted",
+            "age restricted",
+            "inappropriate for some users",
+        ],
+    ),
+    (
+        ErrorCode::GeoBlocked,
+        &[
+            "available in your country",
+            "not available from your location",
+            "geo restrict",
+            "geo-restrict",
+        ],
+    ),
+    (
+        ErrorCode::LoginRequired,
+        &[
+            "members-only",
+            "join this channel",
+            "sign in",
+            "login required",
+            "requires authentication",
+        ],
+    ),
+    (
+        ErrorCode::LiveNotStarted,
+        &["live event will begin", "premieres in"],
+    ),
+    (ErrorCode::NoFormats, &["no video formats found"]),
+    (
+        ErrorCode::FormatUnavailable,
+        &["requested format is not available"],
+    ),
+    (
+        ErrorCode::UnsupportedUrl,
+        &["unsupported url", "is not a valid url"],
+    ),
+    (
+        ErrorCode::VideoUnavailable,
+        &[
+            "video unavailable",
+            "not available",
+            "isn't available",
+            "has been removed",
+            "does not exist",
+        ],
+    ),
+    (
+        ErrorCode::FfmpegMissing,
+        &[
+            "ffmpeg is not installed",
+            "ffmpeg not found",
+            "ffprobe and ffmpeg not found",
+        ],
+    ),
+    (
+        ErrorCode::DiskFull,
+        &[
+            "no space left on device",
+            "errno 28",
+            "not enough space on the disk",
+        ],
+    ),
+    (
+        ErrorCode::PostprocessFailed,
+        &["postprocessing:", "conversion failed"],
+    ),
+    // Windows consoles on a legacy code page (cp949/cp932) crash yt-dlp's output
+    (
+        ErrorCode::EncodingError,
+        &[
+            "unicodeencodeerror",
+            "'cp949' codec",
+            "'cp932' codec",
+            "textiowrapper",
+        ],
+    ),
+];
+
+/// Checked after HTTP status codes, since HTTP errors are reported through urllib too
+const NETWORK_MARKERS: &[&str] = &[
+    "urlopen error",
+    "transporterror",
+    "connection reset",
+    "connection refused",
+    "connection aborted",
+    "timed out",
+    "getaddrinfo failed",
+    "name or service not known",
+    "temporary failure in name resolution",
+    "network is unreachable",
+    "incompleteread",
+];
+
+/// What went wrong in a failed yt-dlp run, read from its stderr.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnosis {
+    pub code: ErrorCode,
+    /// Extractor that raised the error, e.g. `youtube` or `youtube:tab`
+    pub extractor: Option<String>,
+    /// Video or playlist ID the error names
+    pub video_id: Option<String>,
+    pub http_status: Option<u16>,
+    pub exit_code: Option<i32>,
+    /// The final error without its `ERROR:`, extractor and ID prefixes, or the last
+    /// stderr line when yt-dlp printed no `ERROR:` line
+    pub message: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Whether running the same download again unattended can succeed, for automatic and
+/// group retries. Private, removed, region-locked and unsupported videos fail the same
+/// way every time; sign-in errors are kept retryable because changing the cookie setting
+/// fixes them. A manual retry is always allowed, since the user may have changed
+/// something else (e.g. connected through a VPN).
+pub fn retryable(code: ErrorCode) -> bool {
+    !matches!(
+        code,
+        ErrorCode::PrivateVideo
+            | ErrorCode::VideoUnavailable
+            | ErrorCode::UnsupportedUrl
+            | ErrorCode::GeoBlocked
+    )
+}
+
+impl Diagnosis {
+    pub fn retryable(&self) -> bool {
+        retryable(self.code)
+    }
+
+    /// The signed media URLs were refused (403/410), so a fresh extraction may succeed
+    pub fn url_expired(&self) -> bool {
+        matches!(self.http_status, Some(403 | 410))
+    }
+
+    /// The user-facing error, with the sanitized stderr attached
+    pub fn detail(&self, stderr: &str) -> ErrorDetail {
+        let mut detail = ErrorDetail::new(self.code);
+        match self.code {
+            ErrorCode::HttpError => {
+                detail = detail.with_param("status", self.http_status.unwrap_or_default());
+            }
+            ErrorCode::ExitCode => {
+                detail = detail.with_param("code", self.exit_code.unwrap_or_default());
+            }
+            ErrorCode::Ytdlp => {
+                let message = self.message.as_deref().unwrap_or_default();
+                detail = detail.with_param("detail", security::sanitize_error_message(message));
+            }
+            _ => {}
+        }
+        if let Some(extractor) = &self.extractor {
+            detail = detail.with_param("extractor", extractor);
+        }
+        detail.with_stderr(&security::sanitize_error_message(stderr))
+    }
+}
+
+/// Split `[extractor] id: message` into its parts
+fn split_error_line(line: &str) -> (Option<String>, Option<String>, String) {
+    match EXTRACTOR_PREFIX.captures(line) {
+        Some(caps) => (
+            Some(caps[1].to_string()),
+            caps.get(2).map(|m| m.as_str().to_string()),
+            caps[3].to_string(),
+        ),
+        None => (None, None, line.to_string()),
+    }
+}
+
+/// Classify a failed yt-dlp run from its stderr and exit code (None when killed).
+/// The last `ERROR:` line decides; output without one (a crash traceback) is read whole.
+pub fn diagnose(stderr: &str, exit_code: Option<i32>) -> Diagnosis {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for line in stderr.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("ERROR:") {
+            errors.push(rest.trim());
+        } else if let Some(rest) = line.strip_prefix("WARNING:") {
+            warnings.push(rest.trim().to_string());
+        }
+    }
+
+    let (extractor, video_id, message) = match errors.last() {
+        Some(line) => {
+            let (extractor, video_id, message) = split_error_line(line);
+            (extractor, video_id, Some(message))
+        }
+        None => {
+            let last_line = stderr.lines().map(str::trim).rfind(|l| !l.is_empty());
+            (None, None, last_line.map(String::from))
+        }
+    };
+
+    let haystack = if errors.is_empty() {
+        stderr.to_lowercase()
+    } else {
+        errors.join("\n").to_lowercase()
+    };
+    let http_status = HTTP_STATUS
+        .captures_iter(&haystack)
+        .last()
+        .and_then(|caps| caps[1].parse().ok());
+
+    let matched = RULES
+        .iter()
+        .find(|(_, markers)| markers.iter().any(|m| haystack.contains(m)))
+        .map(|(code, _)| *code);
+    let code = if let Some(code) = matched {
+        code
+    } else if exit_code == Some(120) && stderr.contains("Errno 22") {
+        ErrorCode::EncodingError
+    } else if http_status.is_some() {
+        ErrorCode::HttpError
+    } else if NETWORK_MARKERS.iter().any(|m| haystack.contains(m)) {
+        ErrorCode::NetworkError
+    } else if message.is_some() {
+        ErrorCode::Ytdlp
+    } else if exit_code.is_some() {
+        ErrorCode::ExitCode
+    } else {
+        ErrorCode::ProcessCrashed
+    };
+
+    Diagnosis {
+        code,
+        extractor,
+        video_id,
+        http_status,
+        exit_code,
+        message,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stderr as yt-dlp printed it, exit code, expected code, extractor, HTTP status
+    type Fixture = (
+        &'static str,
+        Option<i32>,
+        ErrorCode,
+        Option<&'static str>,
+        Option<u16>,
+    );
+
+    const FIXTURES: &[Fixture] = &[
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: Private video. Sign in if you've been granted access to this video. Use --cookies-from-browser or --cookies for the authentication. See  https://github.com/yt-dlp/yt-dlp/wiki/FAQ#how-do-i-pass-cookies-to-yt-dlp  for how to manually pass cookies",
+            Some(1),
+            ErrorCode::PrivateVideo,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: Sign in to confirm your age. This video may be inappropriate for some users. Use --cookies-from-browser or --cookies for the authentication.",
+            Some(1),
+            ErrorCode::AgeRestricted,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "WARNING: [youtube] dQw4w9WgXcQ: Sign in to confirm you\u{2019}re not a bot. This helps protect our community.\nERROR: [youtube] dQw4w9WgXcQ: Sign in to confirm you\u{2019}re not a bot. Use --cookies-from-browser or --cookies for the authentication.",
+            Some(1),
+            ErrorCode::RateLimited,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "WARNING: [youtube] Unable to download API page: HTTP Error 429: Too Many Requests (caused by <HTTPError 429: Too Many Requests>)\nERROR: [youtube] dQw4w9WgXcQ: Unable to download API page: HTTP Error 429: Too Many Requests (caused by <HTTPError 429: Too Many Requests>)",
+            Some(1),
+            ErrorCode::RateLimited,
+            Some("youtube"),
+            Some(429),
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: The uploader has not made this video available in your country\nYou might want to use a VPN or a proxy server (with --proxy) to workaround.",
+            Some(1),
+            ErrorCode::GeoBlocked,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: Video unavailable. This video has been removed by the uploader",
+            Some(1),
+            ErrorCode::VideoUnavailable,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "ERROR: [youtube:tab] PLxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx: The playlist does not exist.",
+            Some(1),
+            ErrorCode::VideoUnavailable,
+            Some("youtube:tab"),
+            None,
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: This content isn't available, try again later.",
+            Some(1),
+            ErrorCode::RateLimited,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: Join this channel to get access to members-only content like this video, and other exclusive perks.",
+            Some(1),
+            ErrorCode::LoginRequired,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: This live event will begin in 5 hours.",
+            Some(1),
+            ErrorCode::LiveNotStarted,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "ERROR: Unsupported URL: https://example.com/page",
+            Some(1),
+            ErrorCode::UnsupportedUrl,
+            None,
+            None,
+        ),
+        (
+            "ERROR: [generic] 'notaurl' is not a valid URL. Set --default-search \"ytsearch\" (or run  yt-dlp \"ytsearch:notaurl\" ) to search YouTube",
+            Some(1),
+            ErrorCode::UnsupportedUrl,
+            Some("generic"),
+            None,
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: Requested format is not available. Use --list-formats for a list of available formats",
+            Some(1),
+            ErrorCode::FormatUnavailable,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "[download] Got error: HTTP Error 403: Forbidden\nERROR: unable to download video data: HTTP Error 403: Forbidden",
+            Some(1),
+            ErrorCode::HttpError,
+            None,
+            Some(403),
+        ),
+        (
+            "ERROR: [generic] Unable to download webpage: HTTP Error 404: Not Found (caused by <HTTPError 404: Not Found>)",
+            Some(1),
+            ErrorCode::HttpError,
+            Some("generic"),
+            Some(404),
+        ),
+        (
+            "ERROR: [youtube] dQw4w9WgXcQ: Unable to download webpage: <urlopen error [Errno 11001] getaddrinfo failed> (caused by TransportError('<urlopen error [Errno 11001] getaddrinfo failed>'))",
+            Some(1),
+            ErrorCode::NetworkError,
+            Some("youtube"),
+            None,
+        ),
+        (
+            "ERROR: Could not copy Chrome cookie database. See  https://github.com/yt-dlp/yt-dlp/issues/7271  for more info",
+            Some(1),
+            ErrorCode::CookieCopyFailed,
+            None,
+            None,
+        ),
+        (
+            "ERROR: Failed to decrypt with DPAPI. See  https://github.com/yt-dlp/yt-dlp/issues/10927  for more info",
+            Some(1),
+            ErrorCode::CookieCopyFailed,
+            None,
+            None,
+        ),
+        (
+            "ERROR: You have requested merging of multiple formats but ffmpeg is not installed. Aborting due to --abort-on-error",
+            Some(1),
+            ErrorCode::FfmpegMissing,
+            None,
+            None,
+        ),
+        (
+            "ERROR: Postprocessing: Conversion failed!",
+            Some(1),
+            ErrorCode::PostprocessFailed,
+            None,
+            None,
+        ),
+        (
+            "ERROR: unable to write data: [Errno 28] No space left on device",
+            Some(1),
+            ErrorCode::DiskFull,
+            None,
+            None,
+        ),
+        (
+            "Traceback (most recent call last):\n  File \"yt_dlp\\__main__.py\", line 17, in <module>\nUnicodeEncodeError: 'cp949' codec can't encode character '\\u2013' in position 31: illegal multibyte sequence",
+            Some(1),
+            ErrorCode::EncodingError,
+            None,
+            None,
+        ),
+        (
+            "OSError: [Errno 22] Invalid argument",
+            Some(120),
+            ErrorCode::EncodingError,
+            None,
+            None,
+        ),
+        // "page" used to read as an age restriction
+        (
+            "ERROR: [youtube:tab] @somechannel: Unable to recognize tab page; please report this issue on  https://github.com/yt-dlp/yt-dlp/issues?q= , filling out the appropriate issue template.",
+            Some(1),
+            ErrorCode::Ytdlp,
+            Some("youtube:tab"),
+            None,
+        ),
+        // Exit code 2 is an options error, not a network problem
+        (
+            "Usage: yt-dlp [OPTIONS] URL [URL...]\n\nyt-dlp: error: no such option: --frobnicate",
+            Some(2),
+            ErrorCode::Ytdlp,
+            None,
+            None,
+        ),
+        ("", Some(1), ErrorCode::ExitCode, None, None),
+        ("", None, ErrorCode::ProcessCrashed, None, None),
+    ];
+
+    #[test]
+    fn test_diagnose_fixtures() {
+        for (stderr, exit_code, code, extractor, http_status) in FIXTURES {
+            let diagnosis = diagnose(stderr, *exit_code);
+            assert_eq!(diagnosis.code, *code, "{}", stderr);
+            assert_eq!(diagnosis.extractor.as_deref(), *extractor, "{}", stderr);
+            assert_eq!(diagnosis.http_status, *http_status, "{}", stderr);
+        }
+    }
+
+    #[test]
+    fn test_diagnosis_parts_and_detail() {
+        let stderr = "WARNING: [vimeo] 12345: Falling back to webpage\nERROR: [vimeo] 12345: Something unexpected happened";
+        let diagnosis = diagnose(stderr, Some(1));
+        assert_eq!(diagnosis.video_id.as_deref(), Some("12345"));
+        assert_eq!(
+            diagnosis.message.as_deref(),
+            Some("Something unexpected happened")
+        );
+        assert_eq!(
+            diagnosis.warnings,
+            vec!["[vimeo] 12345: Falling back to webpage"]
+        );
+
+        let detail = diagnosis.detail(stderr);
+        assert_eq!(detail.params["detail"], "Something unexpected happened");
+        assert_eq!(detail.params["extractor"], "vimeo");
+        assert_eq!(detail.stderr.as_deref(), Some(stderr));
+        assert_eq!(
+            detail.to_string(),
+            "yt-dlp error: Something unexpected happened"
+        );
+
+        let detail = diagnose("", Some(3)).detail("");
+        assert_eq!(detail.to_string(), "yt-dlp exited with code 3.");
+        assert_eq!(detail.stderr, None);
+    }
+
+    #[test]
+    fn test_retryable() {
+        let private = diagnose("ERROR: [youtube] abc: Private video", Some(1));
+        assert!(!private.retryable());
+        let geo = diagnose(
+            "ERROR: [youtube] abc: The uploader has not made this video available in your country",
+            Some(1),
+        );
+        assert!(!geo.retryable());
+        assert!(!retryable(ErrorCode::UnsupportedUrl));
+        assert!(diagnose("ERROR: Unable to download webpage: timed out", Some(1)).retryable());
+        assert!(retryable(ErrorCode::LoginRequired));
+        assert!(retryable(ErrorCode::HttpError));
+    }
+
+    #[test]
+    fn test_url_expired() {
+        let expired = diagnose(
+            "ERROR: unable to download video data: HTTP Error 403: Forbidden",
+            Some(1),
+        );
+        assert!(expired.url_expired());
+        let gone = diagnose(
+            "ERROR: unable to download video data: HTTP Error 410: Gone",
+            Some(1),
+        );
+        assert!(gone.url_expired());
+        // A 403 in a warning that yt-dlp recovered from doesn't count
+        let recovered = diagnose(
+            "WARNING: [youtube] HTTP Error 403: Forbidden; retrying\nERROR: [youtube] dQw4w9WgXcQ: Video unavailable",
+            Some(1),
+        );
+        assert!(!recovered.url_expired());
+    }
+}
@@ -3,19 +3,20 @@ use super::manager::DownloadManager;
 use crate::modules::logger;
 use crate::modules::types::AppError;
 use crate::ytdlp::types::*;
-use crate::ytdlp::{security, settings};
+use crate::ytdlp::{metadata, security, settings};
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
-#[tauri::command]
-#[specta::specta]
-pub async fn add_to_queue(app: AppHandle, request: DownloadRequest) -> Result<u64, AppError> {
+/// Validate a request and snapshot the options it will run with: URL and output
+/// directory checks, the output template, cookies, post-processing and extra args.
+/// Settings are resolved here, once, so later settings changes don't affect queued tasks.
+fn prepare_request(
+    request: &DownloadRequest,
+    settings: &AppSettings,
+) -> Result<DownloadOptions, AppError> {
     // Validate URL
     security::sanitize_url(&request.video_url)?;
 
-    // Get settings for download path and filename template
-    let settings = settings::get_settings(&app)?;
-
     // Determine output directory and validate path
     let output_dir = request
         .output_dir
@@ -29,11 +30,79 @@ pub async fn add_to_queue(app: AppHandle, request: DownloadRequest) -> Result<u6
         .to_string_lossy()
         .to_string();
 
+    // An explicit browser must be valid; an invalid global setting is just ignored
+    let cookie_browser = match &request.cookie_browser {
+        Some(browser) => Some(security::sanitize_cookie_browser(browser)?),
+        None => settings
+            .cookie_browser
+            .as_deref()
+            .and_then(|b| security::sanitize_cookie_browser(b).ok()),
+    };
+
+    let post_processing = request.post_processing.clone().unwrap_or_default();
+    security::sanitize_post_processing(&post_processing)?;
+
+    let extra_args = match &request.extra_args {
+        Some(args) => security::sanitize_extra_args(args)?,
+        None => Vec::new(),
+    };
+
+    Ok(DownloadOptions {
+        format_id: request.format_id.clone(),
+        output_template,
+        cookie_browser,
+        post_processing,
+        extra_args,
+        info_json: None,
+    })
+}
+
+/// Why a request would duplicate an existing download, by canonical identity:
+/// already queued (pending, downloading or paused) or already in history.
+fn find_duplicate(
+    db: &crate::ytdlp::db::Database,
+    request: &DownloadRequest,
+) -> Result<Option<&'static str>, AppError> {
+    let Some((extractor, id)) = request.canonical_identity() else {
+        return Ok(None);
+    };
+    if db.canonical_in_queue(&extractor, &id)? {
+        return Ok(Some("Already in queue"));
+    }
+    if db.find_canonical_in_history(&extractor, &id)?.is_some() {
+        return Ok(Some("Already downloaded"));
+    }
+    Ok(None)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn add_to_queue(app: AppHandle, request: DownloadRequest) -> Result<u64, AppError> {
+    // Get settings for download path and filename template
+    let settings = settings::get_settings(&app)?;
+    let mut options = prepare_request(&request, &settings)?;
+    options.info_json = metadata::saved_info_json(&app, &request.video_url);
+
     // Get database from state
     let db_state = app.state::<crate::DbState>();
 
+    if settings.dedup_policy != DedupPolicy::Allow && request.allow_duplicate != Some(true) {
+        if let Some(reason) = find_duplicate(&db_state, &request)? {
+            if settings.dedup_policy == DedupPolicy::Skip {
+                return Err(AppError::Duplicate(format!(
+                    "{}: {}",
+                    reason, request.video_url
+                )));
+            }
+            logger::warn_cat(
+                "download",
+                &format!("Enqueueing duplicate ({}): {}", reason, request.video_url),
+            );
+        }
+    }
+
     // Insert download record into DB with pending status
-    let task_id = db_state.insert_download(&request, &output_template)?;
+    let task_id = db_state.insert_download(&request, &options)?;
 
     // Try to acquire a download slot
     let manager = app.state::<Arc<DownloadManager>>();
@@ -83,6 +152,139 @@ pub async fn add_to_queue(app: AppHandle, request: DownloadRequest) -> Result<u6
     Ok(task_id)
 }
 
+/// How a batch item that duplicates a queued or downloaded video (`reason`) is handled,
+/// following the same dedup policy as `add_to_queue`. Returns the item's status and
+/// whether it is enqueued.
+fn duplicate_outcome(
+    policy: DedupPolicy,
+    allow_duplicate: bool,
+    reason: Option<&str>,
+) -> (BatchItemStatus, bool) {
+    match reason {
+        None => (BatchItemStatus::Accepted, true),
+        Some(_) if allow_duplicate || policy == DedupPolicy::Allow => {
+            (BatchItemStatus::Accepted, true)
+        }
+        Some(_) if policy == DedupPolicy::Skip => (BatchItemStatus::Duplicate, false),
+        Some(_) => (BatchItemStatus::AcceptedDuplicate, true),
+    }
+}
+
+/// Enqueue many downloads at once (e.g. a whole playlist).
+/// Every request is validated individually; valid, non-duplicate ones are inserted in a
+/// single transaction and the dispatcher is triggered once for the whole batch.
+/// When `group` is given, the accepted items are recorded under a new download group.
+/// Items already queued, repeated in the batch or already in history follow the dedup
+/// policy, as in `add_to_queue`. `allowDuplicate` on a request bypasses the checks.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_to_queue_batch(
+    app: AppHandle,
+    requests: Vec<DownloadRequest>,
+    group: Option<NewDownloadGroup>,
+) -> Result<Vec<BatchEnqueueResult>, AppError> {
+    enqueue_batch(&app, requests, group.as_ref())
+}
+
+/// Shared batch path for `add_to_queue_batch` and queue imports.
+pub(super) fn enqueue_batch(
+    app: &AppHandle,
+    requests: Vec<DownloadRequest>,
+    group: Option<&NewDownloadGroup>,
+) -> Result<Vec<BatchEnqueueResult>, AppError> {
+    if let Some(group) = group {
+        security::sanitize_url(&group.source_url)?;
+    }
+
+    let settings = settings::get_settings(app)?;
+    let db_state = app.state::<crate::DbState>();
+    let mut seen_ids = db_state.get_queued_video_ids()?;
+    let mut seen_keys = db_state.get_queued_canonical_keys()?;
+
+    let mut results = Vec::with_capacity(requests.len());
+    let mut accepted: Vec<(DownloadRequest, DownloadOptions)> = Vec::new();
+    let mut accepted_indexes: Vec<usize> = Vec::new();
+
+    for (index, request) in requests.into_iter().enumerate() {
+        let mut result = BatchEnqueueResult {
+            index: index as u32,
+            video_url: request.video_url.clone(),
+            status: BatchItemStatus::Accepted,
+            task_id: None,
+            message: None,
+        };
+
+        match prepare_request(&request, &settings) {
+            Err(e) => {
+                result.status = BatchItemStatus::Invalid;
+                result.message = Some(e.to_string());
+            }
+            Ok(mut options) => {
+                options.info_json = metadata::saved_info_json(app, &request.video_url);
+                let identity = request.canonical_identity();
+                // Requests without a video_id or identity can't be matched, so always accept them
+                let in_queue = {
+                    let new_id =
+                        request.video_id.is_empty() || seen_ids.insert(request.video_id.clone());
+                    let new_key = identity.clone().is_none_or(|key| seen_keys.insert(key));
+                    !(new_id && new_key)
+                };
+                let in_history = match &identity {
+                    Some((extractor, id)) if settings.dedup_policy != DedupPolicy::Allow => {
+                        db_state.find_canonical_in_history(extractor, id)?.is_some()
+                    }
+                    _ => false,
+                };
+
+                let reason = if in_queue {
+                    Some("Already in queue")
+                } else if in_history {
+                    Some("Already downloaded")
+                } else {
+                    None
+                };
+                let (status, enqueue) = duplicate_outcome(
+                    settings.dedup_policy,
+                    request.allow_duplicate == Some(true),
+                    reason,
+                );
+                if !matches!(status, BatchItemStatus::Accepted) {
+                    result.message = reason.map(String::from);
+                }
+                result.status = status;
+                if enqueue {
+                    accepted_indexes.push(results.len());
+                    accepted.push((request, options));
+                }
+            }
+        }
+        results.push(result);
+    }
+
+    if !accepted.is_empty() {
+        let ids = match group {
+            Some(group) => db_state.insert_group_with_downloads(group, &accepted)?.1,
+            None => db_state.insert_downloads_batch(&accepted)?,
+        };
+        for (slot, task_id) in accepted_indexes.into_iter().zip(ids) {
+            results[slot].task_id = Some(task_id);
+        }
+
+        logger::info_cat(
+            "download",
+            &format!(
+                "Batch enqueue: {} accepted of {} requests",
+                accepted.len(),
+                results.len()
+            ),
+        );
+
+        process_next_pending(app.clone());
+    }
+
+    Ok(results)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn start_download(app: AppHandle, request: DownloadRequest) -> Result<u64, AppError> {
@@ -146,3 +348,33 @@ pub async fn pause_download(_app: AppHandle, _task_id: u64) -> Result<(), AppErr
 pub async fn resume_download(_app: AppHandle, _task_id: u64) -> Result<(), AppError> {
     Err(AppError::NotImplemented("resume_download".to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_outcome_follows_policy() {
+        let queued = Some("Already in queue");
+        assert!(matches!(
+            duplicate_outcome(DedupPolicy::Allow, false, queued),
+            (BatchItemStatus::Accepted, true)
+        ));
+        assert!(matches!(
+            duplicate_outcome(DedupPolicy::Warn, false, queued),
+            (BatchItemStatus::AcceptedDuplicate, true)
+        ));
+        assert!(matches!(
+            duplicate_outcome(DedupPolicy::Skip, false, queued),
+            (BatchItemStatus::Duplicate, false)
+        ));
+        assert!(matches!(
+            duplicate_outcome(DedupPolicy::Skip, true, Some("Already downloaded")),
+            (BatchItemStatus::Accepted, true)
+        ));
+        assert!(matches!(
+            duplicate_outcome(DedupPolicy::Skip, false, None),
+            (BatchItemStatus::Accepted, true)
+        ));
+    }
+}
@@ -1,7 +1,9 @@
 use super::manager::DownloadManager;
 use crate::modules::logger;
+use crate::modules::types::{ErrorCode, ErrorDetail};
+use crate::ytdlp::diagnose::diagnose;
 use crate::ytdlp::types::*;
-use crate::ytdlp::{binary, progress, security, settings};
+use crate::ytdlp::{binary, library, metadata, progress, security, settings};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
@@ -47,7 +49,7 @@ async fn kill_process_tree(child: &mut tokio::process::Child) {
 
 /// Helper: emit an error download event to the frontend.
 /// Sanitizes the error message to remove sensitive system paths before sending to UI.
-fn emit_download_error(app: &AppHandle, task_id: u64, message: String) {
+fn emit_download_error(app: &AppHandle, task_id: u64, message: String, error: Option<ErrorDetail>) {
     let sanitized = security::sanitize_error_message(&message);
     let _ = app.emit(
         "download-event",
@@ -60,10 +62,30 @@ fn emit_download_error(app: &AppHandle, task_id: u64, message: String) {
             file_path: None,
             file_size: None,
             message: Some(sanitized),
+            error,
         },
     );
 }
 
+/// Helper: mark a task failed with a coded error and notify the frontend.
+fn record_coded_failure(app: &AppHandle, db: &crate::DbState, task_id: u64, detail: ErrorDetail) {
+    let _ = db.fail_download(task_id, &detail);
+    emit_download_error(app, task_id, detail.to_string(), Some(detail));
+}
+
+/// Persist a task's last live progress and drop it from the in-memory table.
+/// Called once the stdout reader has finished, before the task's final status is written.
+fn flush_progress(db: &crate::DbState, manager: &Arc<DownloadManager>, task_id: u64) {
+    if let Some(live) = manager.remove_progress(task_id) {
+        let _ = db.update_download_progress(
+            task_id,
+            live.progress,
+            live.speed.as_deref(),
+            live.eta.as_deref(),
+        );
+    }
+}
+
 /// Helper: handle a fatal download error by logging, updating DB, emitting event,
 /// and releasing the slot.
 fn handle_download_failure(
@@ -74,8 +96,9 @@ fn handle_download_failure(
     manager: &Arc<DownloadManager>,
 ) {
     logger::error_cat("download", &format!("[download:{}] {}", task_id, error_msg));
+    flush_progress(db, manager, task_id);
     let _ = db.update_download_status(task_id, &DownloadStatus::Failed, Some(error_msg));
-    emit_download_error(app, task_id, error_msg.to_string());
+    emit_download_error(app, task_id, error_msg.to_string(), None);
     manager.unregister_cancel(task_id);
     manager.release();
     process_next_pending(app.clone());
@@ -126,8 +149,11 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
         }
     };
 
-    // Guard: if the task was cancelled between being claimed and execution starting, bail out
-    if matches!(task.status, DownloadStatus::Cancelled) {
+    // Guard: if the task was cancelled or paused between being claimed and execution starting, bail out
+    if matches!(
+        task.status,
+        DownloadStatus::Cancelled | DownloadStatus::Paused
+    ) {
         manager.release();
         process_next_pending(app);
         return;
@@ -136,31 +162,37 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
     let ytdlp_path = match binary::resolve_ytdlp_path_with_app(&app).await {
         Ok(p) => p,
         Err(_e) => {
-            let error_msg = "yt-dlp not found. Please install via Homebrew or click Install.";
             logger::error_cat(
                 "download",
                 &format!("[download:{}] yt-dlp not found: {}", task_id, _e),
             );
-            let _ =
-                db_state.update_download_status(task_id, &DownloadStatus::Failed, Some(error_msg));
-            emit_download_error(&app, task_id, "yt-dlp not found".to_string());
+            record_coded_failure(
+                &app,
+                &db_state,
+                task_id,
+                ErrorDetail::new(ErrorCode::YtdlpNotFound),
+            );
             manager.release();
             process_next_pending(app);
             return;
         }
     };
 
-    let settings = match settings::get_settings(&app) {
-        Ok(s) => s,
-        Err(e) => {
-            logger::error_cat(
-                "download",
-                &format!("[download:{}] failed to get settings: {}", task_id, e),
-            );
-            manager.release();
-            process_next_pending(app);
-            return;
-        }
+    // Rows enqueued before options were stored fall back to the current settings
+    let options = match task.options.clone() {
+        Some(options) => options,
+        None => match settings::get_settings(&app) {
+            Ok(s) => legacy_options(&task, &s),
+            Err(e) => {
+                logger::error_cat(
+                    "download",
+                    &format!("[download:{}] failed to get settings: {}", task_id, e),
+                );
+                manager.release();
+                process_next_pending(app);
+                return;
+            }
+        },
     };
 
     // Send started event
@@ -175,56 +207,34 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
             file_path: None,
             file_size: None,
             message: None,
+            error: None,
         },
     );
 
     // Register cancel receiver before spawning process
     let mut cancel_rx = manager.register_cancel(task_id);
 
-    // Build yt-dlp args in a Vec for logging before passing to Command
-    let mut args: Vec<String> = Vec::new();
-    args.extend(["--format".to_string(), task.format_id.clone()]);
-    args.extend(["--output".to_string(), task.output_path.clone()]);
-    args.extend([
-        "--progress-template".to_string(),
-        progress::progress_template(),
-    ]);
-    args.push("--newline".to_string());
-    args.push("--no-playlist".to_string());
-    args.push("--no-overwrites".to_string());
-
-    // Force UTF-8 encoding inside yt-dlp (fixes cp949 crash on Korean Windows)
-    args.push("--encoding".to_string());
-    args.push("UTF-8".to_string());
-
-    // Sanitize filenames for Windows forbidden characters
-    #[cfg(target_os = "windows")]
-    {
-        args.push("--windows-filenames".to_string());
+    // yt-dlp writes the final video metadata here after the file is moved into place
+    let metadata_file = metadata_sidecar_path(&app, task_id);
+    if let Some(path) = &metadata_file {
+        let _ = tokio::fs::remove_file(path).await;
     }
 
-    // Pass ffmpeg location explicitly if available
-    if let Some(ffmpeg_path) = binary::resolve_ffmpeg_path_with_app(&app).await {
-        args.extend(["--ffmpeg-location".to_string(), ffmpeg_path]);
-    }
-
-    // Add cookie browser from settings if available (validated)
-    if let Some(browser) = &settings.cookie_browser {
-        if security::sanitize_cookie_browser(browser).is_ok() {
-            args.extend(["--cookies-from-browser".to_string(), browser.clone()]);
-        } else {
-            logger::warn_cat(
-                "download",
-                &format!(
-                    "[download:{}] skipping invalid cookie_browser: {}",
-                    task_id, browser
-                ),
-            );
-        }
-    }
+    // Start from the info JSON saved by the metadata fetch while its format URLs are valid
+    let info_json = match &options.info_json {
+        Some(path) if metadata::usable_info_json(path).await => Some(path.clone()),
+        _ => None,
+    };
 
-    // Add video URL
-    args.push(task.video_url.clone());
+    // Build yt-dlp args in a Vec for logging before passing to Command
+    let ffmpeg_path = binary::resolve_ffmpeg_path_with_app(&app).await;
+    let args = build_ytdlp_args(
+        &options,
+        &task.video_url,
+        ffmpeg_path,
+        metadata_file.as_deref(),
+        info_json.as_deref(),
+    );
 
     // Log the full command before spawning
     logger::info_cat(
@@ -277,7 +287,7 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
     };
 
     // Clone necessary data for the async task
-    let db_state_clone = db_state.inner().clone();
+    let manager_clone = manager.inner().clone();
     let app_clone = app.clone();
 
     // Save JoinHandle for stdout reader task
@@ -301,6 +311,7 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
             // "[download] Destination: /path/to/file.mp4"
             // "[Merger] Merging formats into "/path/to/file.mkv""
             // "[ExtractAudio] Destination: /path/to/file.mp3"
+            // "[VideoRemuxer] Remuxing video from webm to mp4; Destination: /path/to/file.mp4"
             if let Some(path) = line.strip_prefix("[Merger] Merging formats into \"") {
                 if let Some(path) = path.strip_suffix('"') {
                     actual_file_path = Some(path.to_string());
@@ -310,6 +321,10 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
                 .or_else(|| line.strip_prefix("[ExtractAudio] Destination: "))
             {
                 actual_file_path = Some(path.trim().to_string());
+            } else if line.starts_with("[VideoRemuxer]") {
+                if let Some((_, path)) = line.split_once("Destination: ") {
+                    actual_file_path = Some(path.trim().to_string());
+                }
             }
 
             if let Some(progress_info) = progress::parse_progress_line(&line) {
@@ -343,15 +358,18 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
                         file_path: None,
                         file_size: None,
                         message: None,
+                        error: None,
                     },
                 );
 
-                // Update DB progress
-                let _ = db_state_clone.update_download_progress(
+                // Keep progress in memory; it is flushed to the DB in batches
+                manager_clone.set_progress(
                     task_id,
-                    progress_info.percent,
-                    Some(&speed),
-                    Some(&eta),
+                    LiveProgress {
+                        progress: progress_info.percent,
+                        speed: Some(speed),
+                        eta: Some(eta),
+                    },
                 );
 
                 last_progress_percent = Some(progress_info.percent);
@@ -404,9 +422,14 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
             kill_process_tree(&mut child).await;
             let _ = stdout_handle.await;
             let _ = stderr_handle.await;
-            let error_msg = "다운로드 시간이 초과되었습니다 (최대 6시간).";
-            let _ = db_state.update_download_status(task_id, &DownloadStatus::Failed, Some(error_msg));
-            emit_download_error(&app, task_id, error_msg.to_string());
+            flush_progress(&db_state, &manager, task_id);
+            record_coded_failure(
+                &app,
+                &db_state,
+                task_id,
+                ErrorDetail::new(ErrorCode::Timeout)
+                    .with_param("seconds", DOWNLOAD_TIMEOUT.as_secs()),
+            );
             manager.unregister_cancel(task_id);
             manager.release();
             process_next_pending(app);
@@ -417,18 +440,30 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
             kill_process_tree(&mut child).await;
             let _ = stdout_handle.await;
             let _ = stderr_handle.await;
-            let _ = db_state.update_download_status(task_id, &DownloadStatus::Cancelled, None);
+            flush_progress(&db_state, &manager, task_id);
+            // A paused task was stopped by pause_group; keep its status so it can be resumed
+            let paused = matches!(
+                db_state.get_download(task_id),
+                Ok(Some(ref t)) if matches!(t.status, DownloadStatus::Paused)
+            );
+            let (event_type, message) = if paused {
+                ("paused", "Download paused.")
+            } else {
+                let _ = db_state.update_download_status(task_id, &DownloadStatus::Cancelled, None);
+                ("cancelled", "Download cancelled.")
+            };
             let _ = app.emit(
                 "download-event",
                 GlobalDownloadEvent {
                     task_id,
-                    event_type: "cancelled".to_string(),
+                    event_type: event_type.to_string(),
                     percent: None,
                     speed: None,
                     eta: None,
                     file_path: None,
                     file_size: None,
-                    message: Some("다운로드가 취소되었습니다.".to_string()),
+                    message: Some(message.to_string()),
+                    error: None,
                 },
             );
             manager.unregister_cancel(task_id);
@@ -441,6 +476,7 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
     // Await both stdout and stderr handles before checking result
     let actual_file_path = stdout_handle.await.ok().flatten();
     let stderr_output = stderr_handle.await.unwrap_or_default();
+    flush_progress(&db_state, &manager, task_id);
 
     // Log process exit for debugging
     let exit_code = status.code();
@@ -458,30 +494,133 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
         );
     }
 
+    // Only consulted when the run failed
+    let diagnosis = diagnose(&stderr_output, status.code());
+
     if status.success() {
-        // Use the actual file path parsed from yt-dlp stdout, falling back to the template path
-        let file_path = actual_file_path.unwrap_or_else(|| task.output_path.clone());
+        let printed = match &metadata_file {
+            Some(path) => read_metadata_sidecar(path).await,
+            None => None,
+        }
+        .unwrap_or_default();
+
+        // Prefer the final path reported by yt-dlp, then the one parsed from stdout,
+        // falling back to the template path
+        let file_path = printed
+            .filepath
+            .clone()
+            .or(actual_file_path)
+            .unwrap_or_else(|| options.output_template.clone());
+        let duration = task.duration.or(printed.duration.map(|d| d.round() as u64));
         let file_size = tokio::fs::metadata(&file_path)
             .await
             .ok()
             .map(|m| m.len())
             .unwrap_or(0);
 
+        // Probe the output before recording it; damaged files are kept but flagged.
+        // The chosen format's codecs say which streams to expect; audio extraction
+        // drops the video stream whatever was downloaded
+        let expected_streams = if options.post_processing.extract_audio_format.is_some() {
+            ExpectedStreams {
+                video: false,
+                audio: true,
+            }
+        } else {
+            library::streams_from_codecs(printed.vcodec.as_deref(), printed.acodec.as_deref())
+                .unwrap_or_else(|| library::expected_streams(&options.format_id))
+        };
+        let verification = library::verify_file(&app, &file_path, expected_streams, duration).await;
+        let final_status = match verification.status {
+            VerifyStatus::NeedsAttention | VerifyStatus::Missing => DownloadStatus::NeedsAttention,
+            VerifyStatus::Ok | VerifyStatus::Unverified => DownloadStatus::Completed,
+        };
+        let verify_issues = if verification.issues.is_empty() {
+            None
+        } else {
+            Some(verification.issues.join("\n"))
+        };
+        if let Some(issues) = &verify_issues {
+            logger::warn_cat(
+                "download",
+                &format!("[download:{}] verification issues: {}", task_id, issues),
+            );
+        }
+
+        // Hash the file contents (off the async runtime) for content-based dedup
+        let content_hash = if matches!(verification.status, VerifyStatus::Missing) {
+            None
+        } else {
+            match library::hash_file(&file_path).await {
+                Ok(hash) => Some(hash),
+                Err(e) => {
+                    logger::warn_cat(
+                        "download",
+                        &format!("[download:{}] failed to hash file: {}", task_id, e),
+                    );
+                    None
+                }
+            }
+        };
+
         // Mark as completed and insert history in a single transaction
         let completed_at = chrono::Utc::now().timestamp();
+        // What yt-dlp resolved is authoritative; fall back to what was known at enqueue time
+        let (canonical_extractor, canonical_id) = match (&printed.extractor_key, &printed.id) {
+            (Some(key), Some(id)) if !key.is_empty() && !id.is_empty() => {
+                (Some(metadata::canonical_extractor(key)), Some(id.clone()))
+            }
+            _ => (task.canonical_extractor.clone(), task.canonical_id.clone()),
+        };
         let history_item = HistoryItem {
             id: 0,
             video_url: task.video_url.clone(),
             video_id: task.video_id.clone(),
             title: task.title.clone(),
             quality_label: task.quality_label.clone(),
-            format: task.format_id.clone(),
+            format: options.format_id.clone(),
             file_path: file_path.clone(),
             file_size: Some(file_size),
             downloaded_at: completed_at,
+            duration,
+            verify_status: verification.status,
+            verify_issues: verify_issues.clone(),
+            verified_at: match verification.status {
+                VerifyStatus::Unverified => None,
+                _ => Some(completed_at),
+            },
+            content_hash: content_hash.clone(),
+            channel: task
+                .channel
+                .clone()
+                .or(printed.channel)
+                .or(printed.uploader),
+            extractor: printed
+                .extractor
+                .as_deref()
+                .and_then(|e| e.split(':').next())
+                .map(|e| e.to_lowercase())
+                .or_else(|| metadata::site_from_url(&task.video_url)),
+            channel_url: task
+                .channel_url
+                .clone()
+                .or(printed.channel_url)
+                .or(printed.uploader_url),
+            thumbnail: task.thumbnail.clone().or(printed.thumbnail),
+            upload_date: task.upload_date.clone().or(printed.upload_date),
+            playlist_id: task.playlist_id.clone().or(printed.playlist_id),
+            playlist_title: task.playlist_title.clone().or(printed.playlist_title),
+            download_secs: task
+                .started_at
+                .map(|started| (completed_at - started).max(0) as u64),
+            canonical_extractor,
+            canonical_id,
+            expected_streams: Some(expected_streams),
         };
 
-        if let Err(e) = db_state.complete_and_record(task_id, completed_at, &history_item) {
+        if let Err(e) =
+            db_state.complete_and_record(task_id, completed_at, &final_status, &history_item)
+        {
             logger::error_cat(
                 "download",
                 &format!(
@@ -496,12 +635,36 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
         logger::info_cat(
             "download",
             &format!(
-                "[download:{}] completed successfully, file_size={}",
-                task_id, file_size
+                "[download:{}] completed successfully, file_size={}, verify={}",
+                task_id, file_size, verification.status
             ),
         );
 
-        // Send completion event
+        // Identical content already in the library under another URL (re-upload, mirror, ...)
+        let mut notes: Vec<String> = verify_issues.into_iter().collect();
+        if let Some(hash) = &content_hash {
+            let same_content: Vec<HistoryItem> = db_state
+                .find_history_by_hash(hash, None)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|item| item.video_url != task.video_url)
+                .collect();
+            if let Some(existing) = same_content.first() {
+                logger::warn_cat(
+                    "download",
+                    &format!(
+                        "[download:{}] identical content already downloaded from {} ({})",
+                        task_id, existing.video_url, existing.file_path
+                    ),
+                );
+                notes.push(format!(
+                    "Identical file already in library: {}",
+                    existing.title
+                ));
+            }
+        }
+
+        // Send completion event (verification issues and duplicates are reported in the message)
         let _ = app.emit(
             "download-event",
             GlobalDownloadEvent {
@@ -512,79 +675,56 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
                 eta: None,
                 file_path: Some(file_path),
                 file_size: Some(file_size),
-                message: None,
+                message: if notes.is_empty() {
+                    None
+                } else {
+                    Some(notes.join("\n"))
+                },
+                error: None,
             },
         );
+    } else if info_json.is_some() && diagnosis.url_expired() {
+        // The saved format URLs were rejected before their expiry; extract again from the URL
+        logger::warn_cat(
+            "download",
+            &format!(
+                "[download:{}] info JSON URLs rejected, retrying from the video URL",
+                task_id
+            ),
+        );
+        let retry_options = DownloadOptions {
+            info_json: None,
+            ..options.clone()
+        };
+        if let Err(e) = db_state.requeue_with_options(task_id, &retry_options) {
+            logger::error_cat(
+                "download",
+                &format!("[download:{}] failed to requeue: {}", task_id, e),
+            );
+            let _ = db_state.update_download_status(
+                task_id,
+                &DownloadStatus::Failed,
+                Some(&e.to_string()),
+            );
+            emit_download_error(&app, task_id, e.to_string(), None);
+        }
     } else {
         // Download failed
-        let error_message = if let Some(code) = status.code() {
-            match code {
-                1 => {
-                    if stderr_output.contains("Could not copy") && stderr_output.contains("cookie")
-                    {
-                        "브라우저 쿠키에 접근할 수 없습니다. 브라우저를 완전히 종료하거나, Firefox 쿠키를 사용하세요.".to_string()
-                    } else if stderr_output.is_empty() {
-                        "다운로드 중 오류가 발생했습니다.".to_string()
-                    } else {
-                        // Include full stderr for better diagnostics
-                        let last_line = stderr_output
-                            .lines()
-                            .rev()
-                            .find(|l| !l.trim().is_empty())
-                            .unwrap_or("다운로드 중 오류가 발생했습니다.");
-                        format!("{}\n\n[stderr]: {}", last_line, stderr_output)
-                    }
-                }
-                2 => format!(
-                    "네트워크 연결 문제입니다. 인터넷 연결을 확인하세요.\n\n[stderr]: {}",
-                    stderr_output
-                ),
-                120 => {
-                    // Exit code 120: often a Windows encoding crash (cp949/cp932)
-                    let is_encoding_error = stderr_output.contains("cp949")
-                        || stderr_output.contains("cp932")
-                        || stderr_output.contains("TextIOWrapper")
-                        || stderr_output.contains("Errno 22")
-                        || stderr_output.contains("UnicodeEncodeError");
-                    if is_encoding_error {
-                        format!(
-                            "인코딩 오류로 다운로드에 실패했습니다.\n\n\
-                            Windows 설정 → 시간 및 언어 → 관리 언어 설정 → \
-                            시스템 로캘 변경 → 'Beta: 세계 언어 지원을 위해 Unicode UTF-8 사용'을 \
-                            활성화한 후 재시작하세요.\n\n[stderr]: {}",
-                            stderr_output
-                        )
-                    } else {
-                        format!(
-                            "yt-dlp exited with code: 120\n\n[stderr]: {}",
-                            stderr_output
-                        )
-                    }
-                }
-                _ => format!(
-                    "yt-dlp exited with code: {}\n\n[stderr]: {}",
-                    code, stderr_output
-                ),
-            }
-        } else {
-            format!(
-                "다운로드 프로세스가 예기치 않게 종료되었습니다.\n\n[stderr]: {}",
-                stderr_output
-            )
-        };
+        let detail = diagnosis.detail(&stderr_output);
 
-        // Log full error internally, sanitize for frontend
+        // Log full stderr internally; the frontend gets it separately in the detail
         logger::error_cat(
             "download",
-            &format!("[download:{}] failed: {}", task_id, error_message),
-        );
-        let sanitized_error = security::sanitize_error_message(&error_message);
-        let _ = db_state.update_download_status(
-            task_id,
-            &DownloadStatus::Failed,
-            Some(&sanitized_error),
+            &format!(
+                "[download:{}] failed ({:?}, retryable: {}): {}\n\n[stderr]: {}",
+                task_id,
+                detail.code,
+                diagnosis.retryable(),
+                detail,
+                stderr_output
+            ),
         );
-        emit_download_error(&app, task_id, sanitized_error);
+        record_coded_failure(&app, &db_state, task_id, detail);
     }
 
     // Release the download slot and process next pending
@@ -593,6 +733,29 @@ pub(super) async fn execute_download(app: AppHandle, task_id: u64) {
     process_next_pending(app);
 }
 
+/// How often buffered live progress is written to the database
+const PROGRESS_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Periodically write changed live progress to the DB in a single transaction,
+/// so a restart or a plain DB read still sees recent values.
+pub fn spawn_progress_flusher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(PROGRESS_FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let manager = app.state::<Arc<DownloadManager>>();
+            let updates = manager.take_dirty_progress();
+            if updates.is_empty() {
+                continue;
+            }
+            let db_state = app.state::<crate::DbState>();
+            if let Err(e) = db_state.update_progress_batch(&updates) {
+                logger::warn_cat("download", &format!("progress flush failed: {}", e));
+            }
+        }
+    });
+}
+
 /// Public wrapper for process_next_pending (used by retry_download in commands.rs)
 pub fn process_next_pending_public(app: AppHandle) {
     process_next_pending(app);
@@ -601,6 +764,9 @@ pub fn process_next_pending_public(app: AppHandle) {
 pub(super) fn process_next_pending(app: AppHandle) {
     let db_state = app.state::<crate::DbState>();
     let manager = app.state::<Arc<DownloadManager>>();
+    if manager.is_dispatch_paused() {
+        return;
+    }
 
     // Try to start pending tasks while slots are available
     while manager.try_acquire() {
@@ -635,6 +801,165 @@ pub(super) fn process_next_pending(app: AppHandle) {
     }
 }
 
+/// Options for rows enqueued before per-download options were stored.
+fn legacy_options(task: &DownloadTaskInfo, settings: &AppSettings) -> DownloadOptions {
+    DownloadOptions {
+        format_id: task.format_id.clone(),
+        output_template: task.output_path.clone(),
+        cookie_browser: settings
+            .cookie_browser
+            .as_deref()
+            .and_then(|b| security::sanitize_cookie_browser(b).ok()),
+        ..Default::default()
+    }
+}
+
+/// Fields yt-dlp prints to the metadata sidecar once the final file is in place
+const SIDECAR_TEMPLATE: &str = "after_move:%(.{filepath,channel,uploader,channel_url,uploader_url,duration,upload_date,thumbnail,extractor,playlist_id,playlist_title,id,extractor_key,vcodec,acodec})j";
+
+/// Video metadata written by `--print-to-file` at completion. Every field is optional
+/// since availability depends on the extractor.
+#[derive(Debug, Default, serde::Deserialize)]
+struct PrintedMetadata {
+    filepath: Option<String>,
+    channel: Option<String>,
+    uploader: Option<String>,
+    channel_url: Option<String>,
+    uploader_url: Option<String>,
+    duration: Option<f64>,
+    upload_date: Option<String>,
+    thumbnail: Option<String>,
+    extractor: Option<String>,
+    playlist_id: Option<String>,
+    playlist_title: Option<String>,
+    id: Option<String>,
+    extractor_key: Option<String>,
+    /// "none" when the chosen format has no video (or audio) stream
+    vcodec: Option<String>,
+    acodec: Option<String>,
+}
+
+fn metadata_sidecar_path(app: &AppHandle, task_id: u64) -> Option<String> {
+    let dir = app.path().app_cache_dir().ok()?.join("download-meta");
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(
+        dir.join(format!("{}.json", task_id))
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+/// Parse the sidecar contents. yt-dlp appends one JSON line per printed video;
+/// the last valid line wins.
+fn parse_printed_metadata(text: &str) -> Option<PrintedMetadata> {
+    text.lines()
+        .rev()
+        .find_map(|line| serde_json::from_str(line.trim()).ok())
+}
+
+async fn read_metadata_sidecar(path: &str) -> Option<PrintedMetadata> {
+    let text = tokio::fs::read_to_string(path).await.ok()?;
+    let _ = tokio::fs::remove_file(path).await;
+    parse_printed_metadata(&text)
+}
+
+/// Build the yt-dlp argument list from a download's options snapshot.
+/// Options were validated at enqueue time; cookies and extra args are re-checked here
+/// since they come back from the database. With `info_json`, yt-dlp loads the saved
+/// extraction instead of the URL.
+fn build_ytdlp_args(
+    options: &DownloadOptions,
+    video_url: &str,
+    ffmpeg_path: Option<String>,
+    metadata_file: Option<&str>,
+    info_json: Option<&str>,
+) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    args.extend(["--format".to_string(), options.format_id.clone()]);
+    args.extend(["--output".to_string(), options.output_template.clone()]);
+    args.extend([
+        "--progress-template".to_string(),
+        progress::progress_template(),
+    ]);
+    args.push("--newline".to_string());
+    args.push("--no-playlist".to_string());
+    args.push("--no-overwrites".to_string());
+
+    // Force UTF-8 encoding inside yt-dlp (fixes cp949 crash on Korean Windows)
+    args.push("--encoding".to_string());
+    args.push("UTF-8".to_string());
+
+    // Sanitize filenames for Windows forbidden characters
+    #[cfg(target_os = "windows")]
+    {
+        args.push("--windows-filenames".to_string());
+    }
+
+    // Pass ffmpeg location explicitly if available
+    if let Some(ffmpeg_path) = ffmpeg_path {
+        args.extend(["--ffmpeg-location".to_string(), ffmpeg_path]);
+    }
+
+    if let Some(browser) = &options.cookie_browser {
+        match security::sanitize_cookie_browser(browser) {
+            Ok(browser) => args.extend(["--cookies-from-browser".to_string(), browser]),
+            Err(_) => logger::warn_cat(
+                "download",
+                &format!("skipping invalid cookie_browser: {}", browser),
+            ),
+        }
+    }
+
+    let pp = &options.post_processing;
+    if pp.embed_metadata {
+        args.push("--embed-metadata".to_string());
+    }
+    if pp.embed_thumbnail {
+        args.push("--embed-thumbnail".to_string());
+    }
+    if pp.embed_subtitles {
+        args.push("--embed-subs".to_string());
+        if let Some(langs) = &pp.sub_langs {
+            args.extend(["--sub-langs".to_string(), langs.clone()]);
+        }
+    }
+    if let Some(format) = &pp.extract_audio_format {
+        args.extend([
+            "--extract-audio".to_string(),
+            "--audio-format".to_string(),
+            format.clone(),
+        ]);
+    }
+    if let Some(format) = &pp.remux_format {
+        args.extend(["--remux-video".to_string(), format.clone()]);
+    }
+
+    // Unlike --print, --print-to-file doesn't imply --quiet, so progress output is kept.
+    // The file name is itself an output template, so literal '%' must be doubled.
+    if let Some(path) = metadata_file {
+        args.extend([
+            "--print-to-file".to_string(),
+            SIDECAR_TEMPLATE.to_string(),
+            path.replace('%', "%%"),
+        ]);
+    }
+
+    match security::sanitize_extra_args(&options.extra_args) {
+        Ok(extra) => args.extend(extra),
+        Err(e) => logger::warn_cat("download", &format!("skipping extra args: {}", e)),
+    }
+
+    if let Some(path) = info_json {
+        args.extend(["--load-info-json".to_string(), path.to_string()]);
+        return args;
+    }
+
+    // End of options, so the URL can never be read as an option or option value
+    args.push("--".to_string());
+    args.push(video_url.to_string());
+    args
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -648,4 +973,85 @@ mod tests {
 
         assert_eq!(output, "345\n6789");
     }
+
+    #[test]
+    fn build_args_uses_options_snapshot() {
+        let options = DownloadOptions {
+            format_id: "bestaudio/best".to_string(),
+            output_template: "/downloads/%(title)s.%(ext)s".to_string(),
+            cookie_browser: Some("firefox".to_string()),
+            post_processing: PostProcessingOptions {
+                embed_metadata: true,
+                extract_audio_format: Some("mp3".to_string()),
+                ..Default::default()
+            },
+            extra_args: vec!["--limit-rate".to_string(), "2M".to_string()],
+            info_json: None,
+        };
+
+        let args = build_ytdlp_args(
+            &options,
+            "https://youtu.be/dQw4w9WgXcQ",
+            None,
+            Some("/cache/7.json"),
+            None,
+        );
+        let joined = args.join(" ");
+
+        assert!(joined.starts_with("--format bestaudio/best --output /downloads/%(title)s.%(ext)s"));
+        assert!(joined.contains("--cookies-from-browser firefox"));
+        assert!(joined.contains("--embed-metadata"));
+        assert!(joined.contains("--extract-audio --audio-format mp3"));
+        assert!(joined.contains("--limit-rate 2M"));
+        assert!(!joined.contains("--ffmpeg-location"));
+        assert!(joined.contains(&format!(
+            "--print-to-file {} /cache/7.json",
+            SIDECAR_TEMPLATE
+        )));
+        assert!(joined.ends_with("-- https://youtu.be/dQw4w9WgXcQ"));
+    }
+
+    #[test]
+    fn build_args_drops_tampered_extra_args() {
+        let options = DownloadOptions {
+            format_id: "best".to_string(),
+            output_template: "/downloads/%(title)s.%(ext)s".to_string(),
+            extra_args: vec!["--exec".to_string(), "rm -rf ~".to_string()],
+            ..Default::default()
+        };
+
+        let args = build_ytdlp_args(&options, "https://youtu.be/dQw4w9WgXcQ", None, None, None);
+        assert!(!args.iter().any(|a| a == "--exec"));
+    }
+
+    #[test]
+    fn build_args_loads_info_json_instead_of_url() {
+        let options = DownloadOptions {
+            format_id: "best".to_string(),
+            output_template: "/downloads/%(title)s.%(ext)s".to_string(),
+            ..Default::default()
+        };
+
+        let args = build_ytdlp_args(
+            &options,
+            "https://youtu.be/dQw4w9WgXcQ",
+            None,
+            None,
+            Some("/cache/info-json/abc.info.json"),
+        );
+        let joined = args.join(" ");
+
+        assert!(joined.ends_with("--load-info-json /cache/info-json/abc.info.json"));
+        assert!(!args.iter().any(|a| a == "https://youtu.be/dQw4w9WgXcQ"));
+    }
+
+    #[test]
+    fn parse_printed_metadata_takes_last_valid_line() {
+        let text = "{\"channel\": \"Old\"}\nnot json\n{\"channel\": \"Rick Astley\", \"duration\": 212.5, \"playlist_id\": null, \"extractor\": \"youtube\"}\n";
+        let meta = parse_printed_metadata(text).unwrap();
+        assert_eq!(meta.channel.as_deref(), Some("Rick Astley"));
+        assert_eq!(meta.duration, Some(212.5));
+        assert_eq!(meta.playlist_id, None);
+        assert!(parse_printed_metadata("").is_none());
+    }
 }
@@ -0,0 +1,113 @@
+use super::executor::process_next_pending;
+use super::manager::DownloadManager;
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::types::*;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+fn ensure_group_exists(app: &AppHandle, group_id: u64) -> Result<(), AppError> {
+    let db_state = app.state::<crate::DbState>();
+    if db_state.get_download_group(group_id)?.is_none() {
+        return Err(AppError::Custom("Download group not found".to_string()));
+    }
+    Ok(())
+}
+
+/// Signal running processes to stop and re-sync the active counter with the DB.
+fn stop_running(app: &AppHandle, task_ids: &[u64]) {
+    let manager = app.state::<Arc<DownloadManager>>();
+    for id in task_ids {
+        manager.send_cancel(*id);
+    }
+    let db_state = app.state::<crate::DbState>();
+    let actual_active = db_state.get_active_count().unwrap_or(0);
+    manager.sync_active_count(actual_active);
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_download_groups(app: AppHandle) -> Result<Vec<DownloadGroup>, AppError> {
+    let db_state = app.state::<crate::DbState>();
+    db_state.get_download_groups()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_download_group(app: AppHandle, group_id: u64) -> Result<DownloadGroup, AppError> {
+    let db_state = app.state::<crate::DbState>();
+    db_state
+        .get_download_group(group_id)?
+        .ok_or_else(|| AppError::Custom("Download group not found".to_string()))
+}
+
+/// Cancel every unfinished item in the group. Returns the number of items cancelled.
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_group(app: AppHandle, group_id: u64) -> Result<u32, AppError> {
+    ensure_group_exists(&app, group_id)?;
+    let db_state = app.state::<crate::DbState>();
+    let (cancelled, running) = db_state.cancel_group_downloads(group_id)?;
+    stop_running(&app, &running);
+
+    logger::info_cat(
+        "download",
+        &format!("Group {}: cancelled {} items", group_id, cancelled),
+    );
+    Ok(cancelled)
+}
+
+/// Pause the group: pending items are held back and running downloads are stopped.
+/// Partially downloaded files are kept so yt-dlp can continue them on resume.
+#[tauri::command]
+#[specta::specta]
+pub async fn pause_group(app: AppHandle, group_id: u64) -> Result<u32, AppError> {
+    ensure_group_exists(&app, group_id)?;
+    let db_state = app.state::<crate::DbState>();
+    let (paused, running) = db_state.pause_group_downloads(group_id)?;
+    stop_running(&app, &running);
+
+    logger::info_cat(
+        "download",
+        &format!("Group {}: paused {} items", group_id, paused),
+    );
+    Ok(paused)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_group(app: AppHandle, group_id: u64) -> Result<u32, AppError> {
+    ensure_group_exists(&app, group_id)?;
+    let db_state = app.state::<crate::DbState>();
+    let resumed = db_state.resume_group_downloads(group_id)?;
+    if resumed > 0 {
+        process_next_pending(app.clone());
+    }
+    Ok(resumed)
+}
+
+/// Requeue every failed item in the group. Returns the number of items requeued.
+#[tauri::command]
+#[specta::specta]
+pub async fn retry_failed_in_group(app: AppHandle, group_id: u64) -> Result<u32, AppError> {
+    ensure_group_exists(&app, group_id)?;
+    let db_state = app.state::<crate::DbState>();
+    let retried = db_state.retry_failed_in_group(group_id)?;
+    if retried > 0 {
+        process_next_pending(app.clone());
+    }
+    Ok(retried)
+}
+
+/// Delete the group and all of its queue items, stopping any that are still running.
+/// Download history and files on disk are not affected.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_group(app: AppHandle, group_id: u64) -> Result<(), AppError> {
+    ensure_group_exists(&app, group_id)?;
+    let db_state = app.state::<crate::DbState>();
+    let (_, running) = db_state.cancel_group_downloads(group_id)?;
+    stop_running(&app, &running);
+    db_state.delete_group(group_id)?;
+    Ok(())
+}
@@ -1,12 +1,17 @@
+use crate::ytdlp::types::{DownloadTaskInfo, LiveProgress};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Mutex;
 use tokio::sync::watch;
 
 pub struct DownloadManager {
     active_count: AtomicU32,
     max_concurrent: AtomicU32,
+    /// While set, no new pending items are started (e.g. during a restore)
+    dispatch_paused: AtomicBool,
     cancel_senders: Mutex<HashMap<u64, watch::Sender<bool>>>,
+    /// Live progress per running task; the bool marks entries not yet flushed to the DB
+    live_progress: Mutex<HashMap<u64, (LiveProgress, bool)>>,
 }
 
 impl DownloadManager {
@@ -14,7 +19,9 @@ impl DownloadManager {
         Self {
             active_count: AtomicU32::new(0),
             max_concurrent: AtomicU32::new(max_concurrent.clamp(1, 20)),
+            dispatch_paused: AtomicBool::new(false),
             cancel_senders: Mutex::new(HashMap::new()),
+            live_progress: Mutex::new(HashMap::new()),
         }
     }
 
@@ -32,6 +39,18 @@ impl DownloadManager {
             .store(val.clamp(1, 20), Ordering::SeqCst);
     }
 
+    pub fn pause_dispatch(&self) {
+        self.dispatch_paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume_dispatch(&self) {
+        self.dispatch_paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_dispatch_paused(&self) -> bool {
+        self.dispatch_paused.load(Ordering::SeqCst)
+    }
+
     // CAS loop to fix TOCTOU race condition
     pub fn try_acquire(&self) -> bool {
         loop {
@@ -93,7 +112,7 @@ impl DownloadManager {
         senders.remove(&task_id);
     }
 
-    /// 앱 종료 시 모든 활성 다운로드 취소. 동기적으로 cancel signal만 전송.
+    /// Cancel every active download on app exit. Only sends the cancel signals, synchronously.
     pub fn cancel_all(&self) {
         let mut senders = self
             .cancel_senders
@@ -103,4 +122,42 @@ impl DownloadManager {
             let _ = tx.send(true);
         }
     }
+
+    // Live progress methods
+    pub fn set_progress(&self, task_id: u64, progress: LiveProgress) {
+        let mut live = self.live_progress.lock().unwrap_or_else(|e| e.into_inner());
+        live.insert(task_id, (progress, true));
+    }
+
+    /// Take the entries changed since the last call, marking them as flushed.
+    pub fn take_dirty_progress(&self) -> Vec<(u64, LiveProgress)> {
+        let mut live = self.live_progress.lock().unwrap_or_else(|e| e.into_inner());
+        live.iter_mut()
+            .filter(|(_, (_, dirty))| *dirty)
+            .map(|(id, (progress, dirty))| {
+                *dirty = false;
+                (*id, progress.clone())
+            })
+            .collect()
+    }
+
+    /// Remove a task's live entry when it leaves the running state.
+    /// Returns its progress if it still had unflushed changes.
+    pub fn remove_progress(&self, task_id: u64) -> Option<LiveProgress> {
+        let mut live = self.live_progress.lock().unwrap_or_else(|e| e.into_inner());
+        live.remove(&task_id)
+            .and_then(|(progress, dirty)| dirty.then_some(progress))
+    }
+
+    /// Overlay live progress onto tasks read from the DB.
+    pub fn merge_live_progress(&self, tasks: &mut [DownloadTaskInfo]) {
+        let live = self.live_progress.lock().unwrap_or_else(|e| e.into_inner());
+        for task in tasks {
+            if let Some((progress, _)) = live.get(&task.id) {
+                task.progress = progress.progress;
+                task.speed = progress.speed.clone();
+                task.eta = progress.eta.clone();
+            }
+        }
+    }
 }
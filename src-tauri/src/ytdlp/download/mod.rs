@@ -1,9 +1,14 @@
 pub mod commands;
 mod executor;
+pub mod groups;
 mod manager;
+pub mod queue_io;
 
 // Re-export all items from commands (includes __cmd__ and __specta__fn__ generated by proc macros)
 pub use commands::*;
 pub use executor::execute_download_public;
 pub use executor::process_next_pending_public;
+pub use executor::spawn_progress_flusher;
+pub use groups::*;
 pub use manager::DownloadManager;
+pub use queue_io::*;
@@ -0,0 +1,599 @@
+use super::commands::enqueue_batch;
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::metadata::validate_url;
+use crate::ytdlp::security;
+use crate::ytdlp::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+const EXPORT_VERSION: u32 = 1;
+const DEFAULT_FORMAT_ID: &str = "bestvideo+bestaudio/best";
+const DEFAULT_QUALITY_LABEL: &str = "best";
+
+const CSV_COLUMNS: [&str; 14] = [
+    "video_url",
+    "video_id",
+    "title",
+    "channel",
+    "format_id",
+    "quality_label",
+    "output_dir",
+    "status",
+    "progress",
+    "error_message",
+    "created_at",
+    "completed_at",
+    "duration",
+    "group_title",
+];
+
+/// One queue entry as written to (and read back from) an export file.
+/// Only `video_url` is required when importing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct QueueExportItem {
+    video_url: String,
+    video_id: String,
+    title: String,
+    channel: Option<String>,
+    format_id: String,
+    quality_label: String,
+    output_dir: Option<String>,
+    status: Option<String>,
+    progress: Option<f32>,
+    error_message: Option<String>,
+    created_at: Option<i64>,
+    completed_at: Option<i64>,
+    duration: Option<u64>,
+    group_title: Option<String>,
+    /// Full options snapshot; JSON only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<DownloadOptions>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueueExport {
+    version: u32,
+    exported_at: i64,
+    items: Vec<QueueExportItem>,
+}
+
+/// JSON imports accept either a full export document or a bare array of items.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonImport {
+    Export { items: Vec<QueueExportItem> },
+    Items(Vec<QueueExportItem>),
+}
+
+fn to_export_item(task: DownloadTaskInfo, group_titles: &HashMap<u64, String>) -> QueueExportItem {
+    // output_path is the full yt-dlp template; only its directory is portable
+    let output_dir = Path::new(&task.output_path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|p| !p.is_empty());
+
+    QueueExportItem {
+        video_url: task.video_url,
+        video_id: task.video_id,
+        title: task.title,
+        channel: task.channel,
+        format_id: task.format_id,
+        quality_label: task.quality_label,
+        output_dir,
+        status: Some(task.status.to_string()),
+        progress: Some(task.progress),
+        error_message: task.error_message,
+        created_at: Some(task.created_at),
+        completed_at: task.completed_at,
+        duration: task.duration,
+        group_title: task.group_id.and_then(|id| group_titles.get(&id).cloned()),
+        options: task.options,
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn items_to_csv(items: &[QueueExportItem]) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push('\n');
+
+    for item in items {
+        let fields = [
+            item.video_url.clone(),
+            item.video_id.clone(),
+            item.title.clone(),
+            item.channel.clone().unwrap_or_default(),
+            item.format_id.clone(),
+            item.quality_label.clone(),
+            item.output_dir.clone().unwrap_or_default(),
+            item.status.clone().unwrap_or_default(),
+            item.progress.map(|p| p.to_string()).unwrap_or_default(),
+            item.error_message.clone().unwrap_or_default(),
+            item.created_at.map(|t| t.to_string()).unwrap_or_default(),
+            item.completed_at.map(|t| t.to_string()).unwrap_or_default(),
+            item.duration.map(|d| d.to_string()).unwrap_or_default(),
+            item.group_title.clone().unwrap_or_default(),
+        ];
+        let row = fields
+            .iter()
+            .map(|f| csv_escape(f))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&row);
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Parse RFC 4180 style CSV. Returns each record with the line number it starts on.
+/// Quoted fields may contain commas, doubled quotes and line breaks.
+fn parse_csv(text: &str) -> Result<Vec<(u32, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line: u32 = 1;
+    let mut record_line: u32 = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!(
+            "Unterminated quoted field starting on line {}",
+            record_line
+        ));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+
+    // Drop blank lines
+    records.retain(|(_, r)| !(r.len() == 1 && r[0].trim().is_empty()));
+    Ok(records)
+}
+
+/// Map CSV records to items. With a header row (containing `video_url` or `url`),
+/// columns are matched by name; otherwise the first column is taken as the URL.
+fn csv_to_items(records: Vec<(u32, Vec<String>)>) -> Vec<(u32, QueueExportItem)> {
+    let mut records = records.into_iter().peekable();
+
+    let header: Option<HashMap<String, usize>> = records.peek().and_then(|(_, first)| {
+        let names: HashMap<String, usize> = first
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.trim().to_lowercase(), i))
+            .collect();
+        (names.contains_key("video_url") || names.contains_key("url")).then_some(names)
+    });
+    if header.is_some() {
+        records.next();
+    }
+
+    records
+        .map(|(line, fields)| {
+            let get = |name: &str| -> Option<String> {
+                let index = match &header {
+                    Some(h) => *h.get(name)?,
+                    None if name == "video_url" => 0,
+                    None => return None,
+                };
+                fields
+                    .get(index)
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+            };
+
+            let item = QueueExportItem {
+                video_url: get("video_url").or_else(|| get("url")).unwrap_or_default(),
+                video_id: get("video_id").unwrap_or_default(),
+                title: get("title").unwrap_or_default(),
+                channel: get("channel"),
+                format_id: get("format_id").unwrap_or_default(),
+                quality_label: get("quality_label").unwrap_or_default(),
+                output_dir: get("output_dir"),
+                status: get("status"),
+                ..Default::default()
+            };
+            (line, item)
+        })
+        .collect()
+}
+
+/// Parse a yt-dlp style batch file: one URL per line, blank lines and lines
+/// starting with `#`, `;` or `]` are ignored.
+fn parse_url_list(text: &str) -> Vec<(u32, QueueExportItem)> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, raw)| {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with(['#', ';', ']']) {
+                return None;
+            }
+            Some((
+                i as u32 + 1,
+                QueueExportItem {
+                    video_url: line.to_string(),
+                    ..Default::default()
+                },
+            ))
+        })
+        .collect()
+}
+
+fn detect_format(path: &Path, text: &str) -> QueueFileFormat {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .as_deref()
+    {
+        Some("json") => QueueFileFormat::Json,
+        Some("csv") => QueueFileFormat::Csv,
+        _ if text.trim_start().starts_with(['[', '{']) => QueueFileFormat::Json,
+        _ => QueueFileFormat::UrlList,
+    }
+}
+
+fn parse_import(
+    text: &str,
+    format: QueueFileFormat,
+) -> Result<Vec<(u32, QueueExportItem)>, AppError> {
+    match format {
+        QueueFileFormat::Json => {
+            let parsed: JsonImport = serde_json::from_str(text)
+                .map_err(|e| AppError::FileError(format!("Invalid queue JSON: {}", e)))?;
+            let items = match parsed {
+                JsonImport::Export { items } | JsonImport::Items(items) => items,
+            };
+            Ok(items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| (i as u32 + 1, item))
+                .collect())
+        }
+        QueueFileFormat::Csv => parse_csv(text)
+            .map(csv_to_items)
+            .map_err(|e| AppError::FileError(format!("Invalid queue CSV: {}", e))),
+        QueueFileFormat::UrlList => Ok(parse_url_list(text)),
+    }
+}
+
+/// Validate an imported item and turn it into a download request.
+fn to_request(item: QueueExportItem) -> Result<DownloadRequest, String> {
+    if item.video_url.trim().is_empty() {
+        return Err("Missing URL".to_string());
+    }
+    if matches!(
+        item.status.as_deref(),
+        Some("completed" | "needs_attention")
+    ) {
+        return Err("Already completed".to_string());
+    }
+
+    let url = security::sanitize_url(&item.video_url).map_err(|e| e.to_string())?;
+    let validation = validate_url(url.clone()).map_err(|e| e.to_string())?;
+    if !validation.valid {
+        return Err("Unsupported URL".to_string());
+    }
+    if matches!(validation.url_type, UrlType::Playlist | UrlType::Channel) {
+        return Err("Playlist and channel URLs cannot be imported as single downloads".to_string());
+    }
+
+    let non_empty = |s: String, fallback: &str| {
+        if s.trim().is_empty() {
+            fallback.to_string()
+        } else {
+            s
+        }
+    };
+
+    // Cookies, post-processing and extra args are re-validated by the batch path
+    let options = item.options.unwrap_or_default();
+
+    Ok(DownloadRequest {
+        video_id: non_empty(item.video_id, &validation.video_id.unwrap_or_default()),
+        title: non_empty(item.title, &url),
+        format_id: non_empty(item.format_id, DEFAULT_FORMAT_ID),
+        quality_label: non_empty(item.quality_label, DEFAULT_QUALITY_LABEL),
+        output_dir: item.output_dir,
+        cookie_browser: options.cookie_browser,
+        duration: item.duration,
+        channel: item.channel,
+        channel_url: None,
+        thumbnail: None,
+        upload_date: None,
+        playlist_id: None,
+        playlist_title: None,
+        post_processing: Some(options.post_processing),
+        extra_args: Some(options.extra_args),
+        extractor_key: None,
+        allow_duplicate: None,
+        video_url: url,
+    })
+}
+
+/// Export the whole queue (all statuses) to a JSON, CSV or plain URL list file.
+/// Returns the number of exported items.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_queue(
+    app: AppHandle,
+    path: String,
+    format: QueueFileFormat,
+) -> Result<u32, AppError> {
+    let path = security::sanitize_output_path(&path)?;
+    let db_state = app.state::<crate::DbState>();
+
+    let group_titles: HashMap<u64, String> = db_state
+        .get_download_groups()?
+        .into_iter()
+        .map(|g| (g.id, g.title))
+        .collect();
+
+    // Oldest first, so a re-import keeps the original queue order
+    let mut tasks = db_state.get_download_queue()?;
+    tasks.reverse();
+    let items: Vec<QueueExportItem> = tasks
+        .into_iter()
+        .map(|t| to_export_item(t, &group_titles))
+        .collect();
+    let count = items.len() as u32;
+
+    let content = match format {
+        QueueFileFormat::Json => serde_json::to_string_pretty(&QueueExport {
+            version: EXPORT_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            items,
+        })
+        .map_err(|e| AppError::Custom(e.to_string()))?,
+        QueueFileFormat::Csv => items_to_csv(&items),
+        QueueFileFormat::UrlList => {
+            let mut out = items
+                .iter()
+                .map(|i| i.video_url.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            out.push('\n');
+            out
+        }
+    };
+
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| AppError::FileError(format!("Failed to write {}: {}", path, e)))?;
+
+    logger::info_cat(
+        "download",
+        &format!("Exported {} queue items to {}", count, path),
+    );
+    Ok(count)
+}
+
+/// Import downloads from a JSON, CSV or plain URL list file. The format is detected from
+/// the file extension (or content) unless given. Valid entries are enqueued through the
+/// batch path; everything else is reported back with its line number and reason.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_queue(
+    app: AppHandle,
+    path: String,
+    format: Option<QueueFileFormat>,
+) -> Result<QueueImportReport, AppError> {
+    let path = security::sanitize_output_path(&path)?;
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| AppError::FileError(format!("Failed to read {}: {}", path, e)))?;
+    let text = text.trim_start_matches('\u{feff}');
+
+    let format = format.unwrap_or_else(|| detect_format(Path::new(&path), text));
+    let entries = parse_import(text, format)?;
+
+    let mut report = QueueImportReport::default();
+    let mut requests = Vec::new();
+    let mut request_lines = Vec::new();
+
+    for (line, item) in entries {
+        let content = item.video_url.clone();
+        match to_request(item) {
+            Ok(request) => {
+                request_lines.push((line, content));
+                requests.push(request);
+            }
+            Err(reason) => report.rejected.push(RejectedImportLine {
+                line,
+                content,
+                reason,
+            }),
+        }
+    }
+
+    if !requests.is_empty() {
+        for result in enqueue_batch(&app, requests, None)? {
+            let (line, content) = request_lines[result.index as usize].clone();
+            match result.status {
+                BatchItemStatus::Accepted | BatchItemStatus::AcceptedDuplicate => {
+                    report.accepted += 1
+                }
+                BatchItemStatus::Duplicate | BatchItemStatus::Invalid => {
+                    report.rejected.push(RejectedImportLine {
+                        line,
+                        content,
+                        reason: result.message.unwrap_or_default(),
+                    })
+                }
+            }
+        }
+    }
+
+    report.rejected.sort_by_key(|r| r.line);
+
+    logger::info_cat(
+        "download",
+        &format!(
+            "Imported queue from {}: {} accepted, {} rejected",
+            path,
+            report.accepted,
+            report.rejected.len()
+        ),
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trips_quoted_fields() {
+        let item = QueueExportItem {
+            video_url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+            title: "Hello, \"World\"\nPart 2".to_string(),
+            status: Some("pending".to_string()),
+            ..Default::default()
+        };
+        let csv = items_to_csv(std::slice::from_ref(&item));
+        let items = csv_to_items(parse_csv(&csv).unwrap());
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].0, 2);
+        assert_eq!(items[0].1.video_url, item.video_url);
+        assert_eq!(items[0].1.title, item.title);
+        assert_eq!(items[0].1.status.as_deref(), Some("pending"));
+    }
+
+    #[test]
+    fn csv_without_header_uses_first_column() {
+        let records =
+            parse_csv("https://a.example/1,ignored\r\n\r\nhttps://a.example/2\n").unwrap();
+        let items = csv_to_items(records);
+        let urls: Vec<_> = items
+            .iter()
+            .map(|(l, i)| (*l, i.video_url.as_str()))
+            .collect();
+        assert_eq!(
+            urls,
+            vec![(1, "https://a.example/1"), (3, "https://a.example/2")]
+        );
+    }
+
+    #[test]
+    fn csv_header_matches_by_name() {
+        let records = parse_csv("Title,URL\nSong,https://a.example/1\n").unwrap();
+        let items = csv_to_items(records);
+        assert_eq!(items[0].1.video_url, "https://a.example/1");
+        assert_eq!(items[0].1.title, "Song");
+    }
+
+    #[test]
+    fn csv_rejects_unterminated_quote() {
+        assert!(parse_csv("\"https://a.example/1\n").is_err());
+    }
+
+    #[test]
+    fn url_list_skips_comments_and_blank_lines() {
+        let text =
+            "# my list\nhttps://a.example/1\n\n  ; note\n] also comment\n  https://a.example/2  \n";
+        let items: Vec<_> = parse_url_list(text)
+            .into_iter()
+            .map(|(l, i)| (l, i.video_url))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (2, "https://a.example/1".to_string()),
+                (6, "https://a.example/2".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn json_accepts_export_document_and_bare_array() {
+        let doc = r#"{"version":1,"exportedAt":0,"items":[{"videoUrl":"https://a.example/1"}]}"#;
+        let arr = r#"[{"videoUrl":"https://a.example/1"},{"videoUrl":"https://a.example/2"}]"#;
+        assert_eq!(parse_import(doc, QueueFileFormat::Json).unwrap().len(), 1);
+        assert_eq!(parse_import(arr, QueueFileFormat::Json).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn detects_format_from_extension_then_content() {
+        assert_eq!(
+            detect_format(Path::new("/q.csv"), "[]"),
+            QueueFileFormat::Csv
+        );
+        assert_eq!(
+            detect_format(Path::new("/q.txt"), " [ ]"),
+            QueueFileFormat::Json
+        );
+        assert_eq!(
+            detect_format(Path::new("/q.txt"), "https://a.example/1"),
+            QueueFileFormat::UrlList
+        );
+    }
+
+    #[test]
+    fn to_request_validates_and_fills_defaults() {
+        let item = QueueExportItem {
+            video_url: "https://youtu.be/dQw4w9WgXcQ".to_string(),
+            ..Default::default()
+        };
+        let req = to_request(item).unwrap();
+        assert_eq!(req.video_id, "dQw4w9WgXcQ");
+        assert_eq!(req.format_id, DEFAULT_FORMAT_ID);
+        assert_eq!(req.title, "https://youtu.be/dQw4w9WgXcQ");
+
+        let playlist = QueueExportItem {
+            video_url: "https://www.youtube.com/playlist?list=PL123".to_string(),
+            ..Default::default()
+        };
+        assert!(to_request(playlist).is_err());
+
+        let local = QueueExportItem {
+            video_url: "file:///etc/passwd".to_string(),
+            ..Default::default()
+        };
+        assert!(to_request(local).is_err());
+    }
+}
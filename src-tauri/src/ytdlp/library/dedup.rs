@@ -0,0 +1,207 @@
+use super::hash::hash_file;
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::db::Database;
+use crate::ytdlp::types::*;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+/// Hash every history file that exists on disk but has no content hash yet.
+/// Returns the number of rows that were hashed.
+async fn backfill_hashes(db: &Database) -> Result<u32, AppError> {
+    let mut hashed = 0u32;
+    for item in db.get_all_history()? {
+        if item.content_hash.is_some() || tokio::fs::metadata(&item.file_path).await.is_err() {
+            continue;
+        }
+        match hash_file(&item.file_path).await {
+            Ok(hash) => {
+                db.update_history_hash(item.id, &hash)?;
+                hashed += 1;
+            }
+            Err(e) => logger::warn_cat(
+                "library",
+                &format!("Failed to hash history {}: {}", item.id, e),
+            ),
+        }
+    }
+    Ok(hashed)
+}
+
+/// Group history items by identical file contents, regardless of source URL.
+/// Missing hashes are computed first so older history is covered too.
+pub async fn find_duplicate_files(app: &AppHandle) -> Result<Vec<DuplicateFileGroup>, AppError> {
+    let db = app.state::<crate::DbState>().inner().clone();
+
+    let hashed = backfill_hashes(&db).await?;
+    if hashed > 0 {
+        logger::info_cat(
+            "library",
+            &format!("Computed content hashes for {} history items", hashed),
+        );
+    }
+
+    Ok(group_by_hash(db.get_hash_duplicates()?))
+}
+
+/// Build duplicate groups from history items sorted by content hash.
+fn group_by_hash(items: Vec<HistoryItem>) -> Vec<DuplicateFileGroup> {
+    let mut groups: Vec<DuplicateFileGroup> = Vec::new();
+    for item in items {
+        let hash = item.content_hash.clone().unwrap_or_default();
+        match groups.last_mut() {
+            Some(group) if group.content_hash == hash => group.items.push(item),
+            _ => groups.push(DuplicateFileGroup {
+                content_hash: hash,
+                file_size: item.file_size,
+                items: vec![item],
+                reclaimable_bytes: 0,
+            }),
+        }
+    }
+
+    // Several rows may point at the same file (e.g. a re-download that was skipped
+    // by --no-overwrites); only distinct extra paths actually free space.
+    for group in &mut groups {
+        let mut paths: HashSet<&str> = HashSet::new();
+        paths.insert(group.items[0].file_path.as_str());
+        let extra_files = group.items[1..]
+            .iter()
+            .filter(|item| paths.insert(item.file_path.as_str()))
+            .count() as u64;
+        group.reclaimable_bytes = extra_files * group.file_size.unwrap_or(0);
+    }
+
+    groups
+}
+
+/// Remove the given history entries as duplicates, optionally deleting their files.
+/// Every entry must have a content hash, and at least one entry with the same hash
+/// must remain with its file on disk, so the last copy of a file can never be removed
+/// this way.
+pub async fn remove_duplicate_files(
+    app: &AppHandle,
+    history_ids: &[u64],
+    delete_files: bool,
+) -> Result<RemoveDuplicatesResult, AppError> {
+    let db = app.state::<crate::DbState>().inner().clone();
+    let to_remove: HashSet<u64> = history_ids.iter().copied().collect();
+    let mut result = RemoveDuplicatesResult::default();
+
+    // Existing paths kept by surviving rows, per hash; their files must not be deleted
+    let mut kept_paths: HashMap<String, HashSet<String>> = HashMap::new();
+
+    for &id in &to_remove {
+        let item = match db.get_history_item(id)? {
+            Some(item) => item,
+            None => {
+                result.errors.push(format!("History item {} not found", id));
+                continue;
+            }
+        };
+        let hash = match &item.content_hash {
+            Some(h) => h.clone(),
+            None => {
+                result.errors.push(format!(
+                    "{}: no content hash, not a known duplicate",
+                    item.title
+                ));
+                continue;
+            }
+        };
+
+        if !kept_paths.contains_key(&hash) {
+            // Only copies still on disk count; a kept row whose file is gone keeps nothing
+            let mut survivors: HashSet<String> = HashSet::new();
+            for other in db.find_history_by_hash(&hash, None)? {
+                if !to_remove.contains(&other.id)
+                    && tokio::fs::metadata(&other.file_path).await.is_ok()
+                {
+                    survivors.insert(other.file_path);
+                }
+            }
+            kept_paths.insert(hash.clone(), survivors);
+        }
+        let survivors = &kept_paths[&hash];
+        if survivors.is_empty() {
+            result.errors.push(format!(
+                "{}: refusing to remove every copy of this file",
+                item.title
+            ));
+            continue;
+        }
+
+        if delete_files && !survivors.contains(&item.file_path) {
+            match tokio::fs::remove_file(&item.file_path).await {
+                Ok(()) => {
+                    result.deleted_files += 1;
+                    result.freed_bytes += item.file_size.unwrap_or(0);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    result
+                        .errors
+                        .push(format!("{}: failed to delete file: {}", item.title, e));
+                    continue;
+                }
+            }
+        }
+
+        db.delete_history(id)?;
+        result.removed_entries += 1;
+    }
+
+    logger::info_cat(
+        "library",
+        &format!(
+            "Removed {} duplicate entries ({} files, {} bytes)",
+            result.removed_entries, result.deleted_files, result.freed_bytes
+        ),
+    );
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ytdlp::db::test_support::{history_item, temp_database};
+
+    #[tokio::test]
+    async fn test_duplicate_groups_from_disk() {
+        let (dir, db) = temp_database();
+        let write = |name: &str, contents: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let first = write("a.mp4", b"same bytes");
+        let copy = write("b.mp4", b"same bytes");
+        let other = write("c.mp4", b"different bytes");
+
+        let mut items = Vec::new();
+        for (i, path) in [&first, &copy, &other, &first].into_iter().enumerate() {
+            let mut item = history_item(&format!("video{}", i), i as i64);
+            item.file_path = path.clone();
+            item.file_size = Some(10);
+            items.push(item);
+        }
+        // Gone from disk, so it can't be hashed or grouped
+        let mut missing = history_item("video4", 4);
+        missing.file_path = dir.path().join("gone.mp4").to_string_lossy().to_string();
+        items.push(missing);
+        db.insert_history_batch(&items).unwrap();
+
+        assert_eq!(backfill_hashes(&db).await.unwrap(), 4);
+        assert_eq!(backfill_hashes(&db).await.unwrap(), 0);
+
+        let groups = group_by_hash(db.get_hash_duplicates().unwrap());
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.content_hash, hash_file(&first).await.unwrap());
+        let ids: Vec<_> = group.items.iter().map(|i| i.video_id.as_str()).collect();
+        assert_eq!(ids, ["video0", "video1", "video3"]);
+        // The second row for a.mp4 frees nothing; only b.mp4 is an extra file
+        assert_eq!(group.reclaimable_bytes, 10);
+    }
+}
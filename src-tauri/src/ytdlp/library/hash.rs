@@ -0,0 +1,60 @@
+use crate::modules::types::AppError;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+
+/// Read buffer size for streaming hashes
+const HASH_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// Compute the SHA-256 of a file as lowercase hex.
+/// Reads in fixed-size chunks on the blocking pool so large files neither load
+/// into memory nor stall the async runtime.
+pub async fn hash_file(path: &str) -> Result<String, AppError> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| AppError::FileError(format!("Failed to open file for hashing: {}", e)))?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; HASH_BUFFER_BYTES];
+        loop {
+            let n = file
+                .read(&mut buf)
+                .map_err(|e| AppError::FileError(format!("Failed to read file: {}", e)))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(hex::encode(hasher.finalize()))
+    })
+    .await
+    .map_err(|e| AppError::Custom(format!("Hash task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hash_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.txt");
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            hash_file(&path.to_string_lossy()).await.unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        // Larger than one read buffer
+        let big = dir.path().join("big.bin");
+        let data = vec![7u8; HASH_BUFFER_BYTES * 2 + 3];
+        std::fs::write(&big, &data).unwrap();
+        assert_eq!(
+            hash_file(&big.to_string_lossy()).await.unwrap(),
+            hex::encode(Sha256::digest(&data))
+        );
+
+        assert!(hash_file(&dir.path().join("missing").to_string_lossy())
+            .await
+            .is_err());
+    }
+}
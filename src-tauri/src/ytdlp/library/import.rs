@@ -0,0 +1,345 @@
+use super::reconcile::{probe_source_tags, scan_roots, Candidate};
+use super::verify::streams_from_codecs;
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::binary;
+use crate::ytdlp::metadata;
+use crate::ytdlp::types::*;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// `Title [id]` as produced by yt-dlp's default `%(title)s [%(id)s].%(ext)s` template
+static FILENAME_ID: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.*?)\s*\[([A-Za-z0-9_-]{6,})\]$").unwrap());
+
+static URL_IN_TEXT: Lazy<Regex> = Lazy::new(|| Regex::new(r#"https?://[^\s"'<>]+"#).unwrap());
+
+/// The subset of a yt-dlp `.info.json` used to build a history row.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct InfoJson {
+    id: Option<String>,
+    title: Option<String>,
+    webpage_url: Option<String>,
+    extractor: Option<String>,
+    extractor_key: Option<String>,
+    channel: Option<String>,
+    uploader: Option<String>,
+    channel_url: Option<String>,
+    uploader_url: Option<String>,
+    duration: Option<f64>,
+    upload_date: Option<String>,
+    thumbnail: Option<String>,
+    playlist_id: Option<String>,
+    playlist_title: Option<String>,
+    format_id: Option<String>,
+    height: Option<u64>,
+    vcodec: Option<String>,
+    acodec: Option<String>,
+}
+
+/// Split a file stem like `Never Gonna Give You Up [dQw4w9WgXcQ]` into (id, title).
+pub(crate) fn id_from_file_stem(stem: &str) -> Option<(String, String)> {
+    let captures = FILENAME_ID.captures(stem.trim())?;
+    let title = captures[1].trim();
+    let id = captures[2].to_string();
+    let title = if title.is_empty() {
+        id.clone()
+    } else {
+        title.to_string()
+    };
+    Some((id, title))
+}
+
+/// Source URL and video ID from embedded container tags, if the URL is a recognised video URL.
+fn id_from_tags(tags: &str) -> Option<(String, String)> {
+    URL_IN_TEXT.find_iter(tags).find_map(|m| {
+        let validation = metadata::validate_url(m.as_str().to_string()).ok()?;
+        Some((validation.video_id?, validation.normalized_url?))
+    })
+}
+
+fn youtube_url(video_id: &str) -> String {
+    format!("https://www.youtube.com/watch?v={}", video_id)
+}
+
+/// Identity used to match scanned files against history: the canonical
+/// (extractor, id) pair when known, so equal IDs from different sites stay apart.
+fn identity_key(item: &HistoryItem) -> (String, String) {
+    let extractor = item
+        .canonical_extractor
+        .as_deref()
+        .or(item.extractor.as_deref())
+        .unwrap_or_default()
+        .to_lowercase();
+    let id = item
+        .canonical_id
+        .clone()
+        .unwrap_or_else(|| item.video_id.clone());
+    (extractor, id)
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Identify a scanned media file, trying the info.json sidecar, then the file name,
+/// then the embedded tags.
+async fn identify(
+    app: &AppHandle,
+    ffprobe: Option<&str>,
+    candidate: &Candidate,
+) -> Option<(HistoryItem, ImportIdSource)> {
+    let path = &candidate.path;
+    let stem = file_stem(path);
+    let downloaded_at = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp());
+
+    let mut item = HistoryItem {
+        id: 0,
+        video_url: String::new(),
+        video_id: String::new(),
+        title: stem.clone(),
+        quality_label: String::new(),
+        format: String::new(),
+        file_path: path.to_string_lossy().to_string(),
+        file_size: Some(candidate.size),
+        downloaded_at,
+        duration: None,
+        verify_status: VerifyStatus::Unverified,
+        verify_issues: None,
+        verified_at: None,
+        content_hash: None,
+        channel: None,
+        extractor: None,
+        channel_url: None,
+        thumbnail: None,
+        upload_date: None,
+        playlist_id: None,
+        playlist_title: None,
+        download_secs: None,
+        canonical_extractor: None,
+        canonical_id: None,
+        expected_streams: None,
+    };
+
+    let sidecar = path.with_file_name(format!("{}.info.json", stem));
+    if let Ok(text) = tokio::fs::read_to_string(&sidecar).await {
+        if let Ok(info) = serde_json::from_str::<InfoJson>(&text) {
+            if let Some(id) = info.id.filter(|id| !id.is_empty()) {
+                item.video_url = info.webpage_url.unwrap_or_default();
+                item.extractor = info
+                    .extractor
+                    .as_deref()
+                    .and_then(|e| e.split(':').next())
+                    .map(|e| e.to_lowercase())
+                    .or_else(|| metadata::site_from_url(&item.video_url));
+                let canonical = match info.extractor_key.filter(|k| !k.is_empty()) {
+                    Some(key) => Some((metadata::canonical_extractor(&key), id.clone())),
+                    None => metadata::canonical_identity(&item.video_url),
+                };
+                (item.canonical_extractor, item.canonical_id) = canonical.unzip();
+                item.video_id = id;
+                item.title = info.title.unwrap_or(item.title);
+                item.channel = info.channel.or(info.uploader);
+                item.channel_url = info.channel_url.or(info.uploader_url);
+                item.duration = info.duration.map(|d| d.round() as u64);
+                item.upload_date = info.upload_date;
+                item.thumbnail = info.thumbnail;
+                item.playlist_id = info.playlist_id;
+                item.playlist_title = info.playlist_title;
+                item.format = info.format_id.unwrap_or_default();
+                item.quality_label = info.height.map(|h| format!("{}p", h)).unwrap_or_default();
+                item.expected_streams =
+                    streams_from_codecs(info.vcodec.as_deref(), info.acodec.as_deref());
+                return Some((item, ImportIdSource::InfoJson));
+            }
+        }
+    }
+
+    if let Some((id, title)) = id_from_file_stem(&stem) {
+        // Without a sidecar the site is unknown; 11-character IDs are YouTube's format
+        if id.len() == 11 {
+            item.video_url = youtube_url(&id);
+            item.extractor = Some("youtube".to_string());
+            item.canonical_extractor = Some("youtube".to_string());
+            item.canonical_id = Some(id.clone());
+        }
+        item.video_id = id;
+        item.title = title;
+        return Some((item, ImportIdSource::FileName));
+    }
+
+    let tags = probe_source_tags(app, ffprobe?, path).await?;
+    let (id, url) = id_from_tags(&tags)?;
+    item.extractor = metadata::site_from_url(&url);
+    (item.canonical_extractor, item.canonical_id) = metadata::canonical_identity(&url).unzip();
+    item.video_id = id;
+    item.video_url = url;
+    Some((item, ImportIdSource::EmbeddedTags))
+}
+
+/// Walk `folder` for media files downloaded outside the app and match them to video IDs.
+/// With `dry_run` only the report is returned; otherwise new entries are added to history
+/// in one transaction, so duplicate checks cover them from then on. The app keeps no
+/// yt-dlp `--download-archive` file; history is the only record of past downloads.
+/// Files whose site cannot be determined have no URL and are reported but not imported.
+pub async fn import_library_folder(
+    app: &AppHandle,
+    folder: &str,
+    dry_run: bool,
+) -> Result<LibraryImportReport, AppError> {
+    let root = PathBuf::from(folder.trim());
+    if !root.is_dir() {
+        return Err(AppError::FileError(format!(
+            "Not a folder: {}",
+            root.display()
+        )));
+    }
+
+    let db = app.state::<crate::DbState>().inner().clone();
+    let mut known_paths: HashSet<String> = HashSet::new();
+    let mut known_ids: HashSet<(String, String)> = HashSet::new();
+    for item in db.get_all_history()? {
+        known_ids.insert(identity_key(&item));
+        known_paths.insert(item.file_path);
+    }
+
+    let mut candidates = tokio::task::spawn_blocking(move || scan_roots(&[root]))
+        .await
+        .map_err(|e| AppError::Custom(format!("Library scan failed: {}", e)))?;
+    candidates.sort_by(|a, b| a.path.cmp(&b.path));
+    let ffprobe = binary::resolve_ffprobe_path_with_app(app).await;
+
+    let mut report = LibraryImportReport {
+        dry_run,
+        scanned_files: candidates.len() as u64,
+        ..Default::default()
+    };
+    let mut seen_ids: HashSet<(String, String)> = HashSet::new();
+    let mut new_items: Vec<HistoryItem> = Vec::new();
+
+    for candidate in &candidates {
+        let (item, detected_by) = match identify(app, ffprobe.as_deref(), candidate).await {
+            Some(found) => found,
+            None => {
+                report
+                    .unidentified
+                    .push(candidate.path.to_string_lossy().to_string());
+                continue;
+            }
+        };
+
+        let key = identity_key(&item);
+        let status = if item.video_url.is_empty() {
+            ImportEntryStatus::NoSourceUrl
+        } else if known_paths.contains(&item.file_path) || known_ids.contains(&key) {
+            ImportEntryStatus::AlreadyInHistory
+        } else if !seen_ids.insert(key) {
+            ImportEntryStatus::DuplicateInScan
+        } else {
+            ImportEntryStatus::New
+        };
+
+        report.entries.push(LibraryImportEntry {
+            file_path: item.file_path.clone(),
+            file_size: candidate.size,
+            video_id: item.video_id.clone(),
+            video_url: item.video_url.clone(),
+            title: item.title.clone(),
+            channel: item.channel.clone(),
+            extractor: item.extractor.clone(),
+            detected_by,
+            status,
+        });
+        if status == ImportEntryStatus::New {
+            new_items.push(item);
+        }
+    }
+
+    if !dry_run && !new_items.is_empty() {
+        report.imported = db.insert_history_batch(&new_items)?;
+    }
+
+    logger::info_cat(
+        "library",
+        &format!(
+            "{} folder {}: {} files, {} new, {} unidentified",
+            if dry_run { "Scanned" } else { "Imported" },
+            folder,
+            report.scanned_files,
+            new_items.len(),
+            report.unidentified.len()
+        ),
+    );
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_from_file_stem() {
+        assert_eq!(
+            id_from_file_stem("Never Gonna Give You Up [dQw4w9WgXcQ]"),
+            Some((
+                "dQw4w9WgXcQ".to_string(),
+                "Never Gonna Give You Up".to_string()
+            ))
+        );
+        assert_eq!(
+            id_from_file_stem("[dQw4w9WgXcQ]"),
+            Some(("dQw4w9WgXcQ".to_string(), "dQw4w9WgXcQ".to_string()))
+        );
+        assert_eq!(id_from_file_stem("Holiday [2019]"), None);
+        assert_eq!(id_from_file_stem("No id here"), None);
+    }
+
+    #[test]
+    fn test_id_from_tags() {
+        let tags = "Some description\nhttps://www.youtube.com/watch?v=dQw4w9WgXcQ\n";
+        assert_eq!(
+            id_from_tags(tags),
+            Some((
+                "dQw4w9WgXcQ".to_string(),
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string()
+            ))
+        );
+        assert_eq!(id_from_tags("no url"), None);
+    }
+
+    fn history_item(extractor: &str, id: &str) -> HistoryItem {
+        HistoryItem {
+            extractor: Some(extractor.to_string()),
+            ..crate::ytdlp::db::test_support::history_item(id, 0)
+        }
+    }
+
+    #[test]
+    fn test_identity_key_separates_sites() {
+        assert_ne!(
+            identity_key(&history_item("youtube", "76979871abc")),
+            identity_key(&history_item("vimeo", "76979871abc"))
+        );
+        let canonical = HistoryItem {
+            canonical_extractor: Some("youtube".to_string()),
+            canonical_id: Some("dQw4w9WgXcQ".to_string()),
+            ..history_item("YouTube", "dQw4w9WgXcQ")
+        };
+        assert_eq!(
+            identity_key(&canonical),
+            identity_key(&history_item("youtube", "dQw4w9WgXcQ"))
+        );
+    }
+}
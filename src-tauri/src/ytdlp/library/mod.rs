@@ -0,0 +1,13 @@
+mod dedup;
+mod hash;
+mod import;
+mod reconcile;
+mod relocate;
+mod verify;
+
+pub use dedup::{find_duplicate_files, remove_duplicate_files};
+pub use hash::hash_file;
+pub use import::import_library_folder;
+pub use reconcile::{background_reconcile, reconcile_library, spawn_startup_reconcile};
+pub use relocate::move_library;
+pub use verify::{expected_streams, streams_from_codecs, verify_file, verify_library};
@@ -0,0 +1,375 @@
+use super::hash::hash_file;
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::binary;
+use crate::ytdlp::settings;
+use crate::ytdlp::types::*;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Only one reconciliation may walk the library at a time
+static RECONCILING: AtomicBool = AtomicBool::new(false);
+
+/// Clears `RECONCILING` when a run ends, including by panic or cancellation
+struct ReconcileGuard;
+
+impl Drop for ReconcileGuard {
+    fn drop(&mut self) {
+        RECONCILING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Delay before the startup reconciliation, so it doesn't compete with queue recovery
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+/// How deep below a library root files are searched
+const MAX_SCAN_DEPTH: usize = 8;
+/// Timeout for reading container tags with ffprobe
+const TAG_PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Extensions yt-dlp produces for finished downloads. Partial files (.part, .ytdl)
+/// and sidecars (.json, .vtt, ...) are never relink candidates.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "mkv", "webm", "mov", "avi", "flv", "m4a", "mp3", "opus", "ogg", "flac", "wav", "aac",
+];
+
+pub(super) struct Candidate {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Whether `file_name` contains `video_id` as a whole token, e.g. `Title [dQw4w9WgXcQ].mp4`.
+/// IDs are made of `[A-Za-z0-9_-]`, so a neighbouring character from that set means the
+/// match is part of a longer word.
+pub(crate) fn filename_has_video_id(file_name: &str, video_id: &str) -> bool {
+    if video_id.is_empty() {
+        return false;
+    }
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    file_name.match_indices(video_id).any(|(start, _)| {
+        let before = file_name[..start].chars().next_back();
+        let after = file_name[start + video_id.len()..].chars().next();
+        !before.is_some_and(is_id_char) && !after.is_some_and(is_id_char)
+    })
+}
+
+fn is_media_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| MEDIA_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Collect media files below the given roots, skipping hidden directories.
+pub(super) fn scan_roots(roots: &[PathBuf]) -> Vec<Candidate> {
+    let mut found = Vec::new();
+    let mut seen_dirs = HashSet::new();
+    let mut stack: Vec<(PathBuf, usize)> = roots.iter().map(|r| (r.clone(), 0)).collect();
+
+    while let Some((dir, depth)) = stack.pop() {
+        let canonical = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        if !seen_dirs.insert(canonical) {
+            continue;
+        }
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_type = match entry.file_type() {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if file_type.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if !hidden && depth < MAX_SCAN_DEPTH {
+                    stack.push((path, depth + 1));
+                }
+            } else if is_media_file(&path) {
+                if let Ok(meta) = entry.metadata() {
+                    found.push(Candidate {
+                        path,
+                        size: meta.len(),
+                    });
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// Read the container tags yt-dlp's `--embed-metadata` writes the source URL into.
+/// Returns all tag values joined, or `None` if the file can't be probed.
+pub(super) async fn probe_source_tags(
+    app: &AppHandle,
+    ffprobe: &str,
+    path: &Path,
+) -> Option<String> {
+    let mut cmd = binary::command_with_path_app(ffprobe, app);
+    cmd.args([
+        "-v",
+        "error",
+        "-show_entries",
+        "format_tags=purl,comment,description,synopsis",
+        "-of",
+        "default=noprint_wrappers=1:nokey=1",
+        "-i",
+    ]);
+    cmd.arg(path);
+    cmd.stdin(Stdio::null());
+    cmd.kill_on_drop(true);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    match tokio::time::timeout(TAG_PROBE_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Folders searched for moved files: the download path plus the configured library roots.
+fn library_roots(app: &AppHandle) -> Vec<PathBuf> {
+    let settings = settings::get_settings(app).unwrap_or_default();
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for root in std::iter::once(settings.download_path).chain(settings.library_roots) {
+        let root = PathBuf::from(root.trim());
+        if root.as_os_str().is_empty() || !root.is_dir() || roots.contains(&root) {
+            continue;
+        }
+        roots.push(root);
+    }
+    roots
+}
+
+/// Per-run caches so each candidate is hashed or probed at most once.
+#[derive(Default)]
+struct MatchCache {
+    hashes: HashMap<PathBuf, Option<String>>,
+    tags: HashMap<PathBuf, Option<String>>,
+}
+
+/// Find a new location for a missing history file among unclaimed candidates.
+/// Tries the cheap filename match first, then content hashes of same-sized files,
+/// then embedded source URLs of files with the same extension.
+async fn find_relink(
+    app: &AppHandle,
+    ffprobe: Option<&str>,
+    item: &HistoryItem,
+    candidates: &[Candidate],
+    claimed: &HashSet<PathBuf>,
+    cache: &mut MatchCache,
+) -> Option<(usize, RelinkMatch)> {
+    let unclaimed = || {
+        candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !claimed.contains(&c.path))
+    };
+
+    if let Some((i, _)) = unclaimed().find(|(_, c)| {
+        c.path
+            .file_name()
+            .is_some_and(|n| filename_has_video_id(&n.to_string_lossy(), &item.video_id))
+    }) {
+        return Some((i, RelinkMatch::VideoId));
+    }
+
+    if let (Some(hash), Some(size)) = (&item.content_hash, item.file_size) {
+        for (i, c) in unclaimed().filter(|(_, c)| c.size == size) {
+            if !cache.hashes.contains_key(&c.path) {
+                let computed = hash_file(&c.path.to_string_lossy()).await.ok();
+                cache.hashes.insert(c.path.clone(), computed);
+            }
+            if cache.hashes[&c.path].as_deref() == Some(hash.as_str()) {
+                return Some((i, RelinkMatch::ContentHash));
+            }
+        }
+    }
+
+    let ffprobe = ffprobe?;
+    let extension = Path::new(&item.file_path)
+        .extension()
+        .map(|e| e.to_ascii_lowercase());
+    for (i, c) in unclaimed().filter(|(_, c)| {
+        extension.is_none() || c.path.extension().map(|e| e.to_ascii_lowercase()) == extension
+    }) {
+        if !cache.tags.contains_key(&c.path) {
+            let tags = probe_source_tags(app, ffprobe, &c.path).await;
+            cache.tags.insert(c.path.clone(), tags);
+        }
+        if let Some(tags) = &cache.tags[&c.path] {
+            if tags.contains(&item.video_url) || filename_has_video_id(tags, &item.video_id) {
+                return Some((i, RelinkMatch::EmbeddedMetadata));
+            }
+        }
+    }
+
+    None
+}
+
+/// Check every history row against the disk. Rows whose file moved are relinked when
+/// a match is found in the library roots; the rest are marked missing. Unless `full`,
+/// rows already marked missing are not searched for again, since hashing and probing
+/// the library for them would repeat on every run.
+pub async fn reconcile_library(
+    app: &AppHandle,
+    full: bool,
+) -> Result<LibraryReconcileSummary, AppError> {
+    if RECONCILING.swap(true, Ordering::SeqCst) {
+        return Err(AppError::Custom(
+            "Library reconciliation is already running".to_string(),
+        ));
+    }
+    let _guard = ReconcileGuard;
+    run_reconcile(app, full).await
+}
+
+async fn run_reconcile(app: &AppHandle, full: bool) -> Result<LibraryReconcileSummary, AppError> {
+    let db = app.state::<crate::DbState>().inner().clone();
+    let items = db.get_all_history()?;
+    let mut summary = LibraryReconcileSummary {
+        checked: items.len() as u64,
+        ..Default::default()
+    };
+
+    // Files still referenced by a present row can't be another row's moved file
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+    let mut missing: Vec<HistoryItem> = Vec::new();
+    let mut known_missing: u64 = 0;
+    for item in items {
+        if tokio::fs::metadata(&item.file_path).await.is_ok() {
+            // The file came back (e.g. a drive was reconnected)
+            if item.verify_status == VerifyStatus::Missing {
+                db.relink_history(item.id, &item.file_path, None)?;
+            }
+            claimed.insert(PathBuf::from(&item.file_path));
+        } else if !full && item.verify_status == VerifyStatus::Missing {
+            known_missing += 1;
+        } else {
+            missing.push(item);
+        }
+    }
+    summary.missing = missing.len() as u64 + known_missing;
+    summary.still_missing = known_missing;
+    if missing.is_empty() {
+        return Ok(summary);
+    }
+
+    let roots = library_roots(app);
+    let candidates = tokio::task::spawn_blocking(move || scan_roots(&roots))
+        .await
+        .map_err(|e| AppError::Custom(format!("Library scan failed: {}", e)))?;
+    let ffprobe = binary::resolve_ffprobe_path_with_app(app).await;
+
+    let mut cache = MatchCache::default();
+    let mut unresolved: Vec<u64> = Vec::new();
+    for item in &missing {
+        match find_relink(
+            app,
+            ffprobe.as_deref(),
+            item,
+            &candidates,
+            &claimed,
+            &mut cache,
+        )
+        .await
+        {
+            Some((i, matched_by)) => {
+                let candidate = &candidates[i];
+                let new_path = candidate.path.to_string_lossy().to_string();
+                db.relink_history(item.id, &new_path, Some(candidate.size))?;
+                claimed.insert(candidate.path.clone());
+                summary.relinked.push(RelinkedFile {
+                    history_id: item.id,
+                    old_path: item.file_path.clone(),
+                    new_path,
+                    matched_by,
+                });
+            }
+            None => unresolved.push(item.id),
+        }
+    }
+
+    db.mark_history_missing(&unresolved, chrono::Utc::now().timestamp())?;
+    summary.still_missing += unresolved.len() as u64;
+
+    logger::info_cat(
+        "library",
+        &format!(
+            "Reconciled {} history items: {} missing, {} relinked, {} still missing",
+            summary.checked,
+            summary.missing,
+            summary.relinked.len(),
+            summary.still_missing
+        ),
+    );
+
+    Ok(summary)
+}
+
+/// Reconcile in the background and notify the frontend when done. Used at startup
+/// and from the periodic maintenance job; only newly missing files are searched for.
+pub async fn background_reconcile(app: &AppHandle) {
+    match reconcile_library(app, false).await {
+        Ok(summary) => {
+            let _ = app.emit("library-reconcile-summary", summary);
+        }
+        Err(e) => logger::warn_cat(
+            "library",
+            &format!("Background reconciliation skipped: {}", e),
+        ),
+    }
+}
+
+/// Run a reconciliation shortly after startup.
+pub fn spawn_startup_reconcile(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        background_reconcile(&app).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filename_has_video_id() {
+        assert!(filename_has_video_id(
+            "Never Gonna Give You Up [dQw4w9WgXcQ].mp4",
+            "dQw4w9WgXcQ"
+        ));
+        assert!(filename_has_video_id("dQw4w9WgXcQ.webm", "dQw4w9WgXcQ"));
+        assert!(!filename_has_video_id("xdQw4w9WgXcQ.mp4", "dQw4w9WgXcQ"));
+        assert!(!filename_has_video_id("dQw4w9WgXcQ-2.mp4", "dQw4w9WgXcQ"));
+        assert!(!filename_has_video_id("anything.mp4", ""));
+    }
+
+    #[test]
+    fn test_reconcile_guard_clears_flag_on_panic() {
+        RECONCILING.store(true, Ordering::SeqCst);
+        let result = std::panic::catch_unwind(|| {
+            let _guard = ReconcileGuard;
+            panic!("scan failed");
+        });
+        assert!(result.is_err());
+        assert!(!RECONCILING.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_is_media_file() {
+        assert!(is_media_file(Path::new("/a/b.MKV")));
+        assert!(!is_media_file(Path::new("/a/b.mp4.part")));
+        assert!(!is_media_file(Path::new("/a/b.info.json")));
+    }
+}
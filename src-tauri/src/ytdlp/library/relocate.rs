@@ -0,0 +1,152 @@
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::types::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Move a file, falling back to copy + delete when a rename isn't possible
+/// (e.g. the destination is on another drive).
+async fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+    tokio::fs::copy(from, to).await?;
+    if let Err(e) = tokio::fs::remove_file(from).await {
+        let _ = tokio::fs::remove_file(to).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Where a file under `from_root` ends up under `to_root`, or `None` if it isn't under `from_root`.
+pub(crate) fn relocated_path(
+    file_path: &Path,
+    from_root: &Path,
+    to_root: &Path,
+) -> Option<PathBuf> {
+    file_path
+        .strip_prefix(from_root)
+        .ok()
+        .filter(|rel| !rel.as_os_str().is_empty())
+        .map(|rel| to_root.join(rel))
+}
+
+/// Move every history file under `from_root` to the same relative location under `to_root`,
+/// then rewrite all affected `file_path` values in one transaction. If the database update
+/// fails, the files are moved back.
+pub async fn move_library(
+    app: &AppHandle,
+    from_root: &str,
+    to_root: &str,
+) -> Result<MoveLibraryResult, AppError> {
+    let from_root = PathBuf::from(from_root.trim());
+    let to_root = PathBuf::from(to_root.trim());
+    if !from_root.is_absolute() || !to_root.is_absolute() {
+        return Err(AppError::FileError(
+            "Library folders must be absolute paths".to_string(),
+        ));
+    }
+    if to_root.starts_with(&from_root) || from_root.starts_with(&to_root) {
+        return Err(AppError::FileError(
+            "Source and destination folders must not contain each other".to_string(),
+        ));
+    }
+
+    let db = app.state::<crate::DbState>().inner().clone();
+    let mut result = MoveLibraryResult::default();
+
+    // Several rows can point at the same file; move it once and update them all
+    let mut by_path: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    for item in db.get_all_history()? {
+        if Path::new(&item.file_path).starts_with(&from_root) {
+            by_path.entry(item.file_path).or_default().push(item.id);
+        }
+    }
+
+    let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut updates: Vec<(u64, String)> = Vec::new();
+    for (path, ids) in by_path {
+        let source = PathBuf::from(&path);
+        let dest = match relocated_path(&source, &from_root, &to_root) {
+            Some(dest) => dest,
+            None => continue,
+        };
+        if tokio::fs::metadata(&source).await.is_err() {
+            result.skipped += ids.len() as u64;
+            continue;
+        }
+        if tokio::fs::metadata(&dest).await.is_ok() {
+            result
+                .errors
+                .push(format!("{}: destination already exists", dest.display()));
+            continue;
+        }
+        if let Err(e) = move_file(&source, &dest).await {
+            result
+                .errors
+                .push(format!("{}: failed to move: {}", source.display(), e));
+            continue;
+        }
+        let new_path = dest.to_string_lossy().to_string();
+        updates.extend(ids.into_iter().map(|id| (id, new_path.clone())));
+        moved.push((source, dest));
+    }
+
+    if let Err(e) = db.update_history_paths(&updates) {
+        for (source, dest) in &moved {
+            if let Err(err) = move_file(dest, source).await {
+                logger::error_cat(
+                    "library",
+                    &format!(
+                        "Failed to move {} back after aborted library move: {}",
+                        dest.display(),
+                        err
+                    ),
+                );
+            }
+        }
+        return Err(e);
+    }
+
+    result.moved_files = moved.len() as u64;
+    result.updated_entries = updates.len() as u64;
+
+    logger::info_cat(
+        "library",
+        &format!(
+            "Moved library {} -> {}: {} files, {} entries, {} skipped, {} errors",
+            from_root.display(),
+            to_root.display(),
+            result.moved_files,
+            result.updated_entries,
+            result.skipped,
+            result.errors.len()
+        ),
+    );
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relocated_path() {
+        let from = Path::new("/media/old");
+        let to = Path::new("/media/new");
+        assert_eq!(
+            relocated_path(Path::new("/media/old/ch/a.mp4"), from, to),
+            Some(PathBuf::from("/media/new/ch/a.mp4"))
+        );
+        assert_eq!(
+            relocated_path(Path::new("/media/older/a.mp4"), from, to),
+            None
+        );
+        assert_eq!(relocated_path(from, from, to), None);
+    }
+}
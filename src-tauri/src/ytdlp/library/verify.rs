@@ -0,0 +1,418 @@
+use crate::modules::logger;
+use crate::ytdlp::binary;
+use crate::ytdlp::types::*;
+use futures_util::StreamExt;
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Timeout for a single ffprobe run
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Number of files probed in parallel during library-wide verification
+const VERIFY_CONCURRENCY: usize = 4;
+/// Allowed duration mismatch: the larger of an absolute and a relative tolerance
+const DURATION_TOLERANCE_SECS: f64 = 2.0;
+const DURATION_TOLERANCE_RATIO: f64 = 0.02;
+
+/// The subset of `ffprobe -of json` output used for verification.
+#[derive(Debug, Default)]
+pub(crate) struct ProbeData {
+    pub format_duration: Option<f64>,
+    /// (codec_type, duration, is_attached_picture)
+    pub streams: Vec<(String, Option<f64>, bool)>,
+}
+
+/// Streams of the format yt-dlp actually chose, from its `vcodec`/`acodec` ("none" when
+/// the format lacks that stream). None when yt-dlp reported neither.
+pub fn streams_from_codecs(vcodec: Option<&str>, acodec: Option<&str>) -> Option<ExpectedStreams> {
+    if vcodec.is_none() && acodec.is_none() {
+        return None;
+    }
+    let present = |codec: Option<&str>| codec.is_some_and(|c| !c.is_empty() && c != "none");
+    Some(ExpectedStreams {
+        video: present(vcodec),
+        audio: present(acodec),
+    })
+}
+
+/// Streams a yt-dlp format selector guarantees whichever alternative is used, for
+/// downloads recorded without codecs. `best` falls back to audio-only on audio sites
+/// and bare format IDs (e.g. "137") may be either, so they guarantee nothing.
+pub fn expected_streams(format_id: &str) -> ExpectedStreams {
+    format_id
+        .split('/')
+        .map(|alternative| {
+            let alternative = alternative.trim();
+            if alternative.contains('+') {
+                return ExpectedStreams {
+                    video: true,
+                    audio: true,
+                };
+            }
+            match alternative.split('[').next().unwrap_or("") {
+                "bestaudio" | "worstaudio" | "ba" | "wa" => ExpectedStreams {
+                    video: false,
+                    audio: true,
+                },
+                "bestvideo" | "worstvideo" | "bv" | "wv" => ExpectedStreams {
+                    video: true,
+                    audio: false,
+                },
+                _ => ExpectedStreams::default(),
+            }
+        })
+        .reduce(|a, b| ExpectedStreams {
+            video: a.video && b.video,
+            audio: a.audio && b.audio,
+        })
+        .unwrap_or_default()
+}
+
+/// Parse ffprobe JSON output. ffprobe reports durations as decimal strings.
+pub(crate) fn parse_probe_json(stdout: &str) -> Option<ProbeData> {
+    let json: serde_json::Value = serde_json::from_str(stdout).ok()?;
+    let parse_duration = |v: &serde_json::Value| {
+        v.as_str()
+            .and_then(|s| s.parse::<f64>().ok())
+            .or_else(|| v.as_f64())
+    };
+
+    let format_duration = parse_duration(&json["format"]["duration"]);
+    let streams = json["streams"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|s| {
+                    (
+                        s["codec_type"].as_str().unwrap_or("").to_string(),
+                        parse_duration(&s["duration"]),
+                        s["disposition"]["attached_pic"].as_i64() == Some(1),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(ProbeData {
+        format_duration,
+        streams,
+    })
+}
+
+/// Judge probe data against what the download was supposed to produce.
+/// `stderr` holds ffprobe's `-v error` output; any error there indicates a damaged container.
+pub(crate) fn evaluate_probe(
+    probe: &ProbeData,
+    stderr: &str,
+    expected: ExpectedStreams,
+    expected_duration: Option<u64>,
+) -> VerificationResult {
+    let mut issues = Vec::new();
+
+    let has_video = probe
+        .streams
+        .iter()
+        .any(|(kind, _, attached_pic)| kind == "video" && !attached_pic);
+    let has_audio = probe.streams.iter().any(|(kind, _, _)| kind == "audio");
+
+    if probe.streams.is_empty() {
+        issues.push("No media streams found".to_string());
+    } else {
+        if expected.video && !has_video {
+            issues.push("Expected a video stream but none was found".to_string());
+        }
+        if expected.audio && !has_audio {
+            issues.push("Expected an audio stream but none was found".to_string());
+        }
+    }
+
+    match (probe.format_duration, expected_duration) {
+        (None, _) => issues.push("Container reports no duration".to_string()),
+        (Some(actual), Some(expected)) if expected > 0 => {
+            let expected = expected as f64;
+            let tolerance = DURATION_TOLERANCE_SECS.max(expected * DURATION_TOLERANCE_RATIO);
+            if (actual - expected).abs() > tolerance {
+                issues.push(format!(
+                    "Duration mismatch: file is {:.1}s, expected {:.0}s",
+                    actual, expected
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    // A stream that ends well before the container is a typical sign of an interrupted merge
+    if let Some(container) = probe.format_duration {
+        let tolerance = DURATION_TOLERANCE_SECS.max(container * DURATION_TOLERANCE_RATIO);
+        for (kind, duration, attached_pic) in &probe.streams {
+            if *attached_pic || (kind != "video" && kind != "audio") {
+                continue;
+            }
+            if let Some(d) = duration {
+                if container - d > tolerance {
+                    issues.push(format!(
+                        "The {} stream ends early ({:.1}s of {:.1}s)",
+                        kind, d, container
+                    ));
+                }
+            }
+        }
+    }
+
+    let errors: Vec<&str> = stderr
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+    if !errors.is_empty() {
+        issues.push(format!(
+            "Container may be truncated or corrupt: {}",
+            errors.last().copied().unwrap_or_default()
+        ));
+    }
+
+    VerificationResult {
+        status: if issues.is_empty() {
+            VerifyStatus::Ok
+        } else {
+            VerifyStatus::NeedsAttention
+        },
+        issues,
+        probed_duration: probe.format_duration,
+        has_video,
+        has_audio,
+    }
+}
+
+fn status_only(status: VerifyStatus, issue: Option<String>) -> VerificationResult {
+    VerificationResult {
+        status,
+        issues: issue.into_iter().collect(),
+        probed_duration: None,
+        has_video: false,
+        has_audio: false,
+    }
+}
+
+/// Probe a downloaded file with ffprobe and check that the expected streams exist,
+/// that the duration roughly matches, and that the container is readable to the end.
+/// Returns `Unverified` (not an error) when ffprobe is not installed.
+pub async fn verify_file(
+    app: &AppHandle,
+    file_path: &str,
+    expected: ExpectedStreams,
+    expected_duration: Option<u64>,
+) -> VerificationResult {
+    if tokio::fs::metadata(file_path).await.is_err() {
+        return status_only(VerifyStatus::Missing, Some("File not found".to_string()));
+    }
+
+    let ffprobe = match binary::resolve_ffprobe_path_with_app(app).await {
+        Some(p) => p,
+        None => return status_only(VerifyStatus::Unverified, None),
+    };
+
+    let mut cmd = binary::command_with_path_app(&ffprobe, app);
+    cmd.args([
+        "-v",
+        "error",
+        "-show_entries",
+        "format=duration:stream=codec_type,duration:stream_disposition=attached_pic",
+        "-of",
+        "json",
+        "-i",
+        file_path,
+    ]);
+    cmd.stdin(Stdio::null());
+    cmd.kill_on_drop(true);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = match tokio::time::timeout(PROBE_TIMEOUT, cmd.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            logger::warn_cat("verify", &format!("Failed to run ffprobe: {}", e));
+            return status_only(VerifyStatus::Unverified, None);
+        }
+        Err(_) => {
+            return status_only(
+                VerifyStatus::NeedsAttention,
+                Some("ffprobe timed out reading the file".to_string()),
+            );
+        }
+    };
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let probe = if output.status.success() {
+        parse_probe_json(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        None
+    };
+
+    match probe {
+        Some(probe) => evaluate_probe(&probe, &stderr, expected, expected_duration),
+        None => {
+            let reason = stderr
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .unwrap_or("unreadable output");
+            status_only(
+                VerifyStatus::NeedsAttention,
+                Some(format!(
+                    "ffprobe could not read the file: {}",
+                    reason.trim()
+                )),
+            )
+        }
+    }
+}
+
+/// Re-verify every history row and store the results. Files are probed a few at a time.
+/// Rows recorded before the chosen format's streams were stored are judged by what
+/// their format selector guarantees.
+pub async fn verify_library(
+    app: &AppHandle,
+) -> Result<LibraryVerifySummary, crate::modules::types::AppError> {
+    let db = app.state::<crate::DbState>().inner().clone();
+    let items = db.get_all_history()?;
+
+    logger::info_cat(
+        "verify",
+        &format!("Re-verifying {} history items", items.len()),
+    );
+
+    let results: Vec<(u64, VerificationResult)> = futures_util::stream::iter(items)
+        .map(|item| async move {
+            let expected = item
+                .expected_streams
+                .unwrap_or_else(|| expected_streams(&item.format));
+            let result = verify_file(app, &item.file_path, expected, item.duration).await;
+            (item.id, result)
+        })
+        .buffer_unordered(VERIFY_CONCURRENCY)
+        .collect()
+        .await;
+
+    let verified_at = chrono::Utc::now().timestamp();
+    let mut summary = LibraryVerifySummary::default();
+    for (id, result) in &results {
+        summary.checked += 1;
+        match result.status {
+            VerifyStatus::Ok => summary.ok += 1,
+            VerifyStatus::NeedsAttention => summary.needs_attention += 1,
+            VerifyStatus::Missing => summary.missing += 1,
+            VerifyStatus::Unverified => summary.unverified += 1,
+        }
+        if let Err(e) = db.update_history_verification(*id, result, verified_at) {
+            logger::error_cat(
+                "verify",
+                &format!("Failed to store verification for history {}: {}", id, e),
+            );
+        }
+    }
+
+    logger::info_cat(
+        "verify",
+        &format!(
+            "Library verification done: {} ok, {} need attention, {} missing, {} unverified",
+            summary.ok, summary.needs_attention, summary.missing, summary.unverified
+        ),
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MERGED_MP4: &str = r#"{
+        "streams": [
+            {"codec_type": "video", "duration": "212.040000", "disposition": {"attached_pic": 0}},
+            {"codec_type": "audio", "duration": "212.091000", "disposition": {"attached_pic": 0}}
+        ],
+        "format": {"duration": "212.091000"}
+    }"#;
+
+    const BOTH: ExpectedStreams = ExpectedStreams {
+        video: true,
+        audio: true,
+    };
+    const AUDIO: ExpectedStreams = ExpectedStreams {
+        video: false,
+        audio: true,
+    };
+
+    #[test]
+    fn test_expected_streams() {
+        assert_eq!(expected_streams("bestvideo[height<=1080]+bestaudio"), BOTH);
+        // The "/best" fallback may pick an audio-only format
+        assert_eq!(
+            expected_streams("bestvideo[height<=1080]+bestaudio/best[height<=1080]"),
+            ExpectedStreams::default()
+        );
+        assert_eq!(expected_streams("bestaudio/ba[ext=m4a]"), AUDIO);
+        assert_eq!(
+            expected_streams("best[height<=720]"),
+            ExpectedStreams::default()
+        );
+        assert_eq!(expected_streams("137"), ExpectedStreams::default());
+    }
+
+    #[test]
+    fn test_streams_from_codecs() {
+        assert_eq!(
+            streams_from_codecs(Some("avc1.64001F"), Some("mp4a.40.2")),
+            Some(BOTH)
+        );
+        // An audio-only site reached through bestvideo+bestaudio/best
+        assert_eq!(streams_from_codecs(Some("none"), Some("opus")), Some(AUDIO));
+        assert_eq!(streams_from_codecs(None, None), None);
+    }
+
+    #[test]
+    fn test_healthy_file_passes() {
+        let probe = parse_probe_json(MERGED_MP4).unwrap();
+        let result = evaluate_probe(&probe, "", BOTH, Some(212));
+        assert_eq!(result.status, VerifyStatus::Ok);
+        assert!(result.has_video && result.has_audio);
+    }
+
+    #[test]
+    fn test_short_duration_needs_attention() {
+        let probe = parse_probe_json(MERGED_MP4).unwrap();
+        let result = evaluate_probe(&probe, "", BOTH, Some(600));
+        assert_eq!(result.status, VerifyStatus::NeedsAttention);
+    }
+
+    #[test]
+    fn test_missing_audio_and_probe_errors() {
+        let json = r#"{"streams": [{"codec_type": "video"}], "format": {"duration": "10.0"}}"#;
+        let probe = parse_probe_json(json).unwrap();
+        let result = evaluate_probe(
+            &probe,
+            "[mov,mp4,m4a,3gp,3g2,mj2 @ 0x1] moov atom not found",
+            BOTH,
+            None,
+        );
+        assert_eq!(result.status, VerifyStatus::NeedsAttention);
+        assert_eq!(result.issues.len(), 2);
+    }
+
+    #[test]
+    fn test_cover_art_is_not_video() {
+        let json = r#"{"streams": [
+            {"codec_type": "audio", "duration": "180.0"},
+            {"codec_type": "video", "disposition": {"attached_pic": 1}}
+        ], "format": {"duration": "180.0"}}"#;
+        let probe = parse_probe_json(json).unwrap();
+        let result = evaluate_probe(&probe, "", AUDIO, Some(180));
+        assert_eq!(result.status, VerifyStatus::Ok);
+        assert!(!result.has_video);
+    }
+}
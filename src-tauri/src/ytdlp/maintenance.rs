@@ -0,0 +1,59 @@
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::types::RetentionResult;
+use crate::ytdlp::{library, metadata, settings};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often retention rules, log cleanup and library reconciliation run while the app runs
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Log retention, also applied once at startup
+pub const LOG_MAX_AGE_DAYS: u32 = 30;
+pub const LOG_MAX_ENTRIES: u64 = 50_000;
+
+/// Apply the configured retention rules to the queue and history, and trim old logs
+/// and expired metadata cache entries.
+pub fn run_maintenance(app: &AppHandle) -> Result<RetentionResult, AppError> {
+    let policy = settings::get_settings(app)?.retention_policy();
+    let db = app.state::<crate::DbState>();
+    let (queue_rows_removed, history_removed) = db.apply_retention(&policy)?;
+
+    let log_db = app.state::<crate::LogDbState>();
+    let logs_removed = log_db.cleanup_old_logs(LOG_MAX_AGE_DAYS, LOG_MAX_ENTRIES)?;
+
+    let metadata_removed = metadata::evict_expired_metadata(app)?;
+
+    let result = RetentionResult {
+        queue_rows_removed,
+        history_removed,
+        logs_removed,
+        metadata_removed,
+    };
+    if queue_rows_removed > 0 || history_removed > 0 {
+        logger::info_cat(
+            "maintenance",
+            &format!(
+                "Retention removed {} queue rows, {} history entries, {} logs",
+                queue_rows_removed, history_removed, logs_removed
+            ),
+        );
+    }
+    Ok(result)
+}
+
+/// Enforce retention and reconcile history with the disk periodically. The first run
+/// happens one interval after startup, since both already run at launch.
+pub fn spawn_maintenance(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+        // The first tick completes immediately; skip it
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_maintenance(&app) {
+                logger::warn_cat("maintenance", &format!("Maintenance failed: {}", e));
+            }
+            library::background_reconcile(&app).await;
+        }
+    });
+}
@@ -0,0 +1,157 @@
+use super::cache::{cache_key, cached_video_info};
+use super::cancel;
+use crate::modules::logger;
+use crate::modules::types::AppError;
+use crate::ytdlp::security;
+use crate::ytdlp::types::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Parallel yt-dlp extractions per batch; more risks HTTP 429 from YouTube
+const DEFAULT_CONCURRENCY: u32 = 3;
+const MAX_CONCURRENCY: u32 = 8;
+
+fn emit_item(app: &AppHandle, event: BatchMetadataEvent) {
+    let _ = app.emit("batch-metadata-event", event);
+}
+
+/// Fetch metadata for many URLs at once (e.g. a pasted list). URLs that point at the
+/// same video are fetched once. At most `concurrency` yt-dlp processes run at a time,
+/// cached results are used where fresh, and each result or per-URL error is emitted as
+/// a `batch-metadata-event` as soon as it is ready. `batch_id` is chosen by the caller
+/// so the batch can be stopped with `cancel_metadata_batch`, which kills its running
+/// yt-dlp processes.
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_video_info_batch(
+    app: AppHandle,
+    urls: Vec<String>,
+    batch_id: String,
+    concurrency: Option<u32>,
+) -> Result<BatchMetadataSummary, AppError> {
+    let (mut cancel_rx, _registration) = cancel::register(&batch_id)?;
+    let mut summary = BatchMetadataSummary {
+        batch_id: batch_id.clone(),
+        requested: urls.len() as u32,
+        ..Default::default()
+    };
+
+    // Group input positions by video so duplicates share one fetch
+    let mut unique: Vec<(String, Vec<u32>)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (index, url) in urls.into_iter().enumerate() {
+        let index = index as u32;
+        match security::sanitize_url(&url) {
+            Ok(url) => match positions.get(&cache_key(&url)) {
+                Some(&slot) => unique[slot].1.push(index),
+                None => {
+                    positions.insert(cache_key(&url), unique.len());
+                    unique.push((url, vec![index]));
+                }
+            },
+            Err(e) => {
+                summary.failed += 1;
+                emit_item(
+                    &app,
+                    BatchMetadataEvent {
+                        batch_id: batch_id.clone(),
+                        indexes: vec![index],
+                        url,
+                        info: None,
+                        error: Some(e.to_string()),
+                        error_detail: e.detail().cloned(),
+                    },
+                );
+            }
+        }
+    }
+    summary.unique = unique.len() as u32;
+
+    logger::info_cat(
+        "metadata",
+        &format!(
+            "Batch {}: fetching {} unique URLs of {}",
+            batch_id, summary.unique, summary.requested
+        ),
+    );
+
+    let limit = concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, MAX_CONCURRENCY);
+    let semaphore = Arc::new(Semaphore::new(limit as usize));
+    let mut tasks = JoinSet::new();
+    for (url, indexes) in unique {
+        let app = app.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let result = match semaphore.acquire_owned().await {
+                Ok(_permit) => cached_video_info(&app, &url, false).await,
+                Err(e) => Err(AppError::Custom(e.to_string())),
+            };
+            (url, indexes, result)
+        });
+    }
+
+    loop {
+        tokio::select! {
+            _ = cancel_rx.changed() => {
+                // Dropping our waiters stops every extraction no one else is waiting on
+                tasks.abort_all();
+                while tasks.join_next().await.is_some() {}
+                summary.cancelled = true;
+                break;
+            }
+            next = tasks.join_next() => {
+                let Some(joined) = next else { break };
+                let Ok((url, indexes, result)) = joined else { continue };
+                let (info, error, error_detail) = match result {
+                    Ok(info) => {
+                        summary.succeeded += 1;
+                        (Some(info), None, None)
+                    }
+                    Err(e) => {
+                        summary.failed += 1;
+                        (None, Some(e.to_string()), e.detail().cloned())
+                    }
+                };
+                emit_item(
+                    &app,
+                    BatchMetadataEvent {
+                        batch_id: batch_id.clone(),
+                        indexes,
+                        url,
+                        info,
+                        error,
+                        error_detail,
+                    },
+                );
+            }
+        }
+    }
+
+    logger::info_cat(
+        "metadata",
+        &format!(
+            "Batch {} {}: {} succeeded, {} failed",
+            batch_id,
+            if summary.cancelled {
+                "cancelled"
+            } else {
+                "finished"
+            },
+            summary.succeeded,
+            summary.failed
+        ),
+    );
+    Ok(summary)
+}
+
+/// Stop a batch metadata fetch. Returns false if no batch with this ID is running.
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_metadata_batch(batch_id: String) -> Result<bool, AppError> {
+    Ok(cancel::cancel(&batch_id))
+}
@@ -0,0 +1,345 @@
+use super::fetch::fetch_video_info_uncached;
+use super::validation::canonical_identity;
+use crate::modules::logger;
+use crate::modules::types::{AppError, ErrorCode};
+use crate::ytdlp::settings;
+use crate::ytdlp::types::VideoInfo;
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::task::AbortHandle;
+
+type MetadataFuture = Shared<BoxFuture<'static, Result<VideoInfo, AppError>>>;
+
+/// A running extraction and the callers waiting on it
+struct InFlight {
+    /// Tells this extraction apart from a later one for the same key
+    id: u64,
+    future: MetadataFuture,
+    abort: AbortHandle,
+    waiters: usize,
+}
+
+/// yt-dlp extractions currently running, by cache key. Callers asking for the same
+/// video while one is in flight await the same result instead of spawning another process.
+static IN_FLIGHT: Lazy<Mutex<HashMap<String, InFlight>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_FETCH_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Counts one caller as waiting on an in-flight extraction. When the last waiter is
+/// dropped before the result is in (a cancelled batch), the extraction is aborted,
+/// which kills its yt-dlp process; callers that joined it from elsewhere keep it alive.
+struct Waiter {
+    key: String,
+    id: u64,
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entry) = in_flight.get_mut(&self.key).filter(|e| e.id == self.id) else {
+            return;
+        };
+        entry.waiters -= 1;
+        if entry.waiters == 0 {
+            if let Some(entry) = in_flight.remove(&self.key) {
+                entry.abort.abort();
+            }
+        }
+    }
+}
+
+/// Saved info JSON older than this is never loaded; the download extracts from the URL
+const INFO_JSON_MAX_AGE: Duration = Duration::from_secs(3 * 60 * 60);
+/// Signed format URLs must stay valid at least this long for a download to start from them
+const URL_EXPIRY_MARGIN_SECS: i64 = 15 * 60;
+
+/// Cache key for a URL: the canonical identity when the URL alone gives one (so
+/// youtu.be, /shorts/ and watch links share an entry), otherwise the URL itself.
+pub(super) fn cache_key(url: &str) -> String {
+    match canonical_identity(url) {
+        Some((extractor, id)) => format!("{}:{}", extractor, id),
+        None => url.trim().to_string(),
+    }
+}
+
+/// Video metadata from the cache when fresh, otherwise from yt-dlp. `force_refresh`
+/// skips the cache lookup but still joins an extraction that is already running.
+pub(super) async fn cached_video_info(
+    app: &AppHandle,
+    url: &str,
+    force_refresh: bool,
+) -> Result<VideoInfo, AppError> {
+    let key = cache_key(url);
+    let ttl_hours = settings::get_settings(app)
+        .unwrap_or_default()
+        .metadata_cache_ttl_hours;
+    let db = app.state::<crate::DbState>().inner().clone();
+
+    if !force_refresh && ttl_hours > 0 {
+        let fresh_since = chrono::Utc::now().timestamp() - ttl_hours as i64 * 3600;
+        match db.get_cached_metadata(&key, fresh_since) {
+            Ok(Some(info)) => {
+                logger::info_cat("metadata", &format!("Metadata cache hit: {}", key));
+                return Ok(info);
+            }
+            Ok(None) => {}
+            Err(e) => logger::warn_cat("metadata", &format!("Metadata cache read failed: {}", e)),
+        }
+    }
+
+    let (future, _waiter) = {
+        let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = in_flight.get_mut(&key) {
+            logger::info_cat("metadata", &format!("Joining in-flight fetch: {}", key));
+            entry.waiters += 1;
+            let waiter = Waiter { key, id: entry.id };
+            (entry.future.clone(), waiter)
+        } else {
+            let id = NEXT_FETCH_ID.fetch_add(1, Ordering::Relaxed);
+            // The fetch runs as its own task so joined callers share one yt-dlp process;
+            // it leaves the map when done, or when its last waiter goes away
+            let task = tokio::spawn({
+                let app = app.clone();
+                let url = url.to_string();
+                let key = key.clone();
+                async move {
+                    let result = fetch_video_info_uncached(&app, &url).await;
+                    if let Ok(info) = &result {
+                        if let Err(e) = db.put_cached_metadata(&key, &url, info) {
+                            logger::warn_cat(
+                                "metadata",
+                                &format!("Metadata cache write failed: {}", e),
+                            );
+                        }
+                    }
+                    let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+                    if in_flight.get(&key).is_some_and(|e| e.id == id) {
+                        in_flight.remove(&key);
+                    }
+                    result
+                }
+            });
+            let abort = task.abort_handle();
+            let future = async move {
+                task.await.unwrap_or_else(|e| {
+                    Err(if e.is_cancelled() {
+                        AppError::coded(ErrorCode::Cancelled)
+                    } else {
+                        AppError::MetadataError(e.to_string())
+                    })
+                })
+            }
+            .boxed()
+            .shared();
+            in_flight.insert(
+                key.clone(),
+                InFlight {
+                    id,
+                    future: future.clone(),
+                    abort,
+                    waiters: 1,
+                },
+            );
+            (future, Waiter { key, id })
+        }
+    };
+
+    future.await
+}
+
+fn info_json_dir(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_cache_dir().ok()?.join("info-json"))
+}
+
+/// Where the raw yt-dlp info JSON for a URL is kept. Keys can be URLs, so the file
+/// name is their hash.
+fn info_json_file(app: &AppHandle, url: &str) -> Option<PathBuf> {
+    let digest = hex::encode(Sha256::digest(cache_key(url).as_bytes()));
+    Some(info_json_dir(app)?.join(format!("{}.info.json", digest)))
+}
+
+/// Keep the raw `--dump-json` output of a metadata fetch so the download can skip
+/// extraction. Failures only cost the shortcut, so they are logged and ignored.
+pub(super) async fn save_info_json(app: &AppHandle, url: &str, raw: &str) {
+    let Some(path) = info_json_file(app, url) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = tokio::fs::create_dir_all(dir).await;
+    }
+    if let Err(e) = tokio::fs::write(&path, raw).await {
+        logger::warn_cat("metadata", &format!("Failed to save info JSON: {}", e));
+    }
+}
+
+/// Saved info JSON for a URL, if one was written recently enough to be worth loading.
+pub fn saved_info_json(app: &AppHandle, url: &str) -> Option<String> {
+    let path = info_json_file(app, url)?;
+    let age = std::fs::metadata(&path)
+        .ok()?
+        .modified()
+        .ok()?
+        .elapsed()
+        .ok()?;
+    (age < INFO_JSON_MAX_AGE).then(|| path.to_string_lossy().to_string())
+}
+
+/// Earliest `expire=` timestamp among the signed media URLs in an info JSON
+/// (YouTube and other Google-hosted streams carry one).
+fn earliest_url_expiry(info: &serde_json::Value) -> Option<i64> {
+    let urls = std::iter::once(&info["url"])
+        .chain(
+            info["requested_formats"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|f| &f["url"]),
+        )
+        .chain(
+            info["formats"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|f| &f["url"]),
+        );
+    urls.filter_map(|u| u.as_str())
+        .filter_map(|u| {
+            let (_, query) = u.split_once('?')?;
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("expire="))
+                .and_then(|v| v.parse::<i64>().ok())
+        })
+        .min()
+}
+
+/// Whether a task's saved info JSON can still be used for `--load-info-json`: it must
+/// exist, be younger than the max age, and its signed URLs must not be about to expire.
+pub async fn usable_info_json(path: &str) -> bool {
+    let Ok(meta) = tokio::fs::metadata(path).await else {
+        return false;
+    };
+    let fresh = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .is_some_and(|age| age < INFO_JSON_MAX_AGE);
+    if !fresh {
+        return false;
+    }
+    let Ok(text) = tokio::fs::read_to_string(path).await else {
+        return false;
+    };
+    let Ok(info) = serde_json::from_str::<serde_json::Value>(&text) else {
+        return false;
+    };
+    match earliest_url_expiry(&info) {
+        Some(expire) => expire > chrono::Utc::now().timestamp() + URL_EXPIRY_MARGIN_SECS,
+        None => true,
+    }
+}
+
+/// Delete saved info JSON files, all of them or only those older than `max_age`.
+fn remove_info_json_files(app: &AppHandle, max_age: Option<Duration>) -> u64 {
+    let Some(dir) = info_json_dir(app) else {
+        return 0;
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let expired = match max_age {
+            None => true,
+            Some(max_age) => file_age(&path).is_none_or(|age| age >= max_age),
+        };
+        if expired && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+fn file_age(path: &Path) -> Option<Duration> {
+    std::fs::metadata(path)
+        .ok()?
+        .modified()
+        .ok()?
+        .elapsed()
+        .ok()
+}
+
+/// Delete cached video metadata and saved info JSON. With `expired_only`, only entries
+/// older than the configured TTL are removed. Returns the number of cache entries removed.
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_metadata_cache(
+    app: AppHandle,
+    expired_only: Option<bool>,
+) -> Result<u64, AppError> {
+    let db = app.state::<crate::DbState>();
+    if expired_only.unwrap_or(false) {
+        evict_expired_metadata(&app)
+    } else {
+        remove_info_json_files(&app, None);
+        db.evict_metadata_cache(None)
+    }
+}
+
+/// Remove cache entries older than the configured TTL (all of them if caching is off),
+/// and info JSON files too old to be loaded.
+pub fn evict_expired_metadata(app: &AppHandle) -> Result<u64, AppError> {
+    remove_info_json_files(app, Some(INFO_JSON_MAX_AGE));
+    let ttl_hours = settings::get_settings(app)?.metadata_cache_ttl_hours;
+    let db = app.state::<crate::DbState>();
+    let cutoff = chrono::Utc::now().timestamp() - ttl_hours as i64 * 3600;
+    db.evict_metadata_cache(Some(cutoff))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            cache_key("https://youtu.be/dQw4w9WgXcQ"),
+            cache_key("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=10")
+        );
+        assert_eq!(
+            cache_key("https://youtu.be/dQw4w9WgXcQ"),
+            "youtube:dQw4w9WgXcQ"
+        );
+        assert_eq!(
+            cache_key(" https://vimeo.com/76979871 "),
+            "https://vimeo.com/76979871"
+        );
+    }
+
+    #[test]
+    fn test_earliest_url_expiry() {
+        let info = serde_json::json!({
+            "formats": [
+                {"url": "https://rr1.googlevideo.com/videoplayback?expire=1700003600&ei=x"},
+                {"url": "https://rr1.googlevideo.com/videoplayback?ei=y&expire=1700000000"},
+                {"url": "https://example.com/manifest.m3u8"},
+            ],
+            "requested_formats": [
+                {"url": "https://rr1.googlevideo.com/videoplayback?expire=1700007200"},
+            ],
+        });
+        assert_eq!(earliest_url_expiry(&info), Some(1700000000));
+        assert_eq!(
+            earliest_url_expiry(&serde_json::json!({"url": "https://vimeo.com/a.mp4"})),
+            None
+        );
+    }
+}
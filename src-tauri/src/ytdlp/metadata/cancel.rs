@@ -0,0 +1,45 @@
+use crate::modules::types::AppError;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// Cancel senders of running long metadata operations (streamed playlist fetches,
+/// batch fetches), by caller-chosen ID
+static ACTIVE: Lazy<Mutex<HashMap<String, watch::Sender<bool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Removes an operation from the registry however it ends
+pub(super) struct Registration(String);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        ACTIVE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.0);
+    }
+}
+
+/// Register a cancellable operation. Fails if one with the same ID is already running.
+pub(super) fn register(id: &str) -> Result<(watch::Receiver<bool>, Registration), AppError> {
+    let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    if active.contains_key(id) {
+        return Err(AppError::Custom(format!(
+            "Operation {} is already running",
+            id
+        )));
+    }
+    let (tx, rx) = watch::channel(false);
+    active.insert(id.to_string(), tx);
+    Ok((rx, Registration(id.to_string())))
+}
+
+/// Signal a running operation to stop. Returns false if no operation has this ID.
+pub(super) fn cancel(id: &str) -> bool {
+    let active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    match active.get(id) {
+        Some(tx) => tx.send(true).is_ok(),
+        None => false,
+    }
+}
@@ -0,0 +1,393 @@
+use super::fetch::playlist_entry;
+use super::map_stderr_error;
+use super::validation::CHANNEL_PATTERNS;
+use crate::modules::logger;
+use crate::modules::types::{AppError, ErrorCode, ErrorDetail};
+use crate::ytdlp::types::*;
+use crate::ytdlp::{binary, security};
+use chrono::{Days, Months, NaiveDate};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Same limit as whole-playlist fetches; a channel tab can hold thousands of entries
+const CHANNEL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Dates yt-dlp accepts for --dateafter/--datebefore: YYYYMMDD, or now/today/yesterday
+/// with an optional offset such as "today-2weeks"
+static DATE_BOUND: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:(\d{8})|(now|today|yesterday)(?:([+-])(\d+)(day|week|month|year)s?)?)$")
+        .unwrap()
+});
+
+impl ChannelTab {
+    fn path(&self) -> &'static str {
+        match self {
+            ChannelTab::Videos => "videos",
+            ChannelTab::Shorts => "shorts",
+            ChannelTab::Streams => "streams",
+            ChannelTab::Playlists => "playlists",
+        }
+    }
+
+    fn from_path(path: &str) -> Option<Self> {
+        match path {
+            "videos" => Some(ChannelTab::Videos),
+            "shorts" => Some(ChannelTab::Shorts),
+            "streams" => Some(ChannelTab::Streams),
+            "playlists" => Some(ChannelTab::Playlists),
+            _ => None,
+        }
+    }
+}
+
+/// The channel's root URL (`/channel/ID`, `/@handle` or `/c/name`), without any tab or query.
+fn channel_base_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    CHANNEL_PATTERNS
+        .iter()
+        .find_map(|p| p.find(url))
+        .map(|m| m.as_str().to_string())
+}
+
+fn tab_url(base: &str, tab: ChannelTab) -> String {
+    format!("{}/{}", base, tab.path())
+}
+
+fn validate_date_bound(value: &str) -> Result<String, AppError> {
+    let value = value.trim().to_lowercase();
+    if DATE_BOUND.is_match(&value) {
+        Ok(value)
+    } else {
+        Err(AppError::Custom(format!(
+            "Invalid date '{}': use YYYYMMDD or e.g. today-2weeks",
+            value
+        )))
+    }
+}
+
+/// A validated date bound as YYYYMMDD, resolving relative forms against `today` the way
+/// yt-dlp does, so it can be compared with `upload_date` in a match filter.
+fn resolve_date_bound(value: &str, today: NaiveDate) -> Option<String> {
+    let caps = DATE_BOUND.captures(value)?;
+    if let Some(date) = caps.get(1) {
+        return Some(date.as_str().to_string());
+    }
+    let mut date = match &caps[2] {
+        "yesterday" => today.checked_sub_days(Days::new(1))?,
+        _ => today,
+    };
+    if let (Some(sign), Some(count), Some(unit)) = (caps.get(3), caps.get(4), caps.get(5)) {
+        let count: u32 = count.as_str().parse().ok()?;
+        let forward = sign.as_str() == "+";
+        date = match unit.as_str() {
+            "day" | "week" => {
+                let days = Days::new(count as u64 * if unit.as_str() == "week" { 7 } else { 1 });
+                if forward {
+                    date.checked_add_days(days)?
+                } else {
+                    date.checked_sub_days(days)?
+                }
+            }
+            _ => {
+                let months = Months::new(count * if unit.as_str() == "year" { 12 } else { 1 });
+                if forward {
+                    date.checked_add_months(months)?
+                } else {
+                    date.checked_sub_months(months)?
+                }
+            }
+        };
+    }
+    Some(date.format("%Y%m%d").to_string())
+}
+
+/// Channel identity from the playlist-level fields of a channel page.
+fn channel_identity(json: &serde_json::Value, base_url: &str) -> ChannelIdentity {
+    let str_field = |key: &str| {
+        json[key]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    };
+    let thumbnails = json["thumbnails"].as_array();
+    let avatar = thumbnails
+        .and_then(|list| {
+            list.iter()
+                .find(|t| t["id"].as_str() == Some("avatar_uncropped"))
+                .or_else(|| {
+                    list.iter()
+                        .find(|t| t["id"].as_str().is_some_and(|id| id.contains("avatar")))
+                })
+        })
+        .and_then(|t| t["url"].as_str())
+        .map(|s| s.to_string());
+
+    ChannelIdentity {
+        channel_id: str_field("channel_id"),
+        name: str_field("channel").or_else(|| str_field("uploader")),
+        handle: str_field("uploader_id").filter(|id| id.starts_with('@')),
+        url: str_field("channel_url").unwrap_or_else(|| base_url.to_string()),
+        avatar,
+        subscriber_count: json["channel_follower_count"].as_u64(),
+    }
+}
+
+/// Entries of a Playlists tab are playlists, so they get playlist URLs
+fn playlist_tab_entry(entry: &serde_json::Value) -> Option<PlaylistEntry> {
+    let id = entry["id"].as_str().filter(|s| !s.is_empty())?.to_string();
+    let url = entry["url"]
+        .as_str()
+        .filter(|u| u.starts_with("http"))
+        .map(|u| u.to_string())
+        .unwrap_or_else(|| format!("https://www.youtube.com/playlist?list={}", id));
+    Some(PlaylistEntry {
+        url,
+        video_id: id,
+        title: entry["title"].as_str().map(|s| s.to_string()),
+        duration: None,
+        thumbnail: entry["thumbnails"]
+            .as_array()
+            .and_then(|arr| arr.last())
+            .and_then(|t| t["url"].as_str())
+            .map(|s| s.to_string()),
+    })
+}
+
+/// Run `yt-dlp --flat-playlist --dump-single-json` with extra arguments and parse the result.
+async fn dump_single_json(
+    app: &AppHandle,
+    extra_args: &[String],
+    url: &str,
+) -> Result<serde_json::Value, AppError> {
+    let ytdlp_path = binary::resolve_ytdlp_path_with_app(app).await?;
+    let settings = crate::ytdlp::settings::get_settings(app).unwrap_or_default();
+
+    let mut cmd = binary::command_with_path_app(&ytdlp_path, app);
+    cmd.arg("--flat-playlist").arg("--dump-single-json");
+    cmd.arg("--encoding").arg("UTF-8");
+    cmd.args(extra_args);
+    if let Some(browser) = &settings.cookie_browser {
+        if security::sanitize_cookie_browser(browser).is_ok() {
+            cmd.arg("--cookies-from-browser").arg(browser);
+        }
+    }
+    cmd.arg("--").arg(url);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = tokio::time::timeout(CHANNEL_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            ErrorDetail::new(ErrorCode::Timeout).with_param("seconds", CHANNEL_TIMEOUT.as_secs())
+        })?
+        .map_err(|e| AppError::MetadataError(format!("Failed to execute yt-dlp: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        logger::error_cat(
+            "metadata",
+            &format!(
+                "channel fetch failed: {}",
+                security::sanitize_error_message(&stderr)
+            ),
+        );
+        return Err(map_stderr_error(&stderr, output.status.code()));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| AppError::MetadataError(format!("Failed to parse JSON: {}", e)))
+}
+
+/// List the tabs a YouTube channel offers, with the channel's identity.
+/// The channel page itself lists its Videos, Shorts and Live tabs; Playlists is
+/// always offered since yt-dlp doesn't report it there.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_channel_tabs(app: AppHandle, url: String) -> Result<ChannelTabs, AppError> {
+    let url = security::sanitize_url(&url)?;
+    let base = channel_base_url(&url)
+        .ok_or_else(|| AppError::InvalidUrl("Not a YouTube channel URL".to_string()))?;
+    logger::info_cat("metadata", &format!("Listing channel tabs: {}", base));
+
+    // Entries of the channel root are its tabs; no videos are listed
+    let json = dump_single_json(&app, &[], &base).await?;
+    let mut tabs: Vec<ChannelTab> = json["entries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            entry["url"]
+                .as_str()
+                .or_else(|| entry["webpage_url"].as_str())
+        })
+        .filter_map(|u| {
+            let path = u.split(['?', '#']).next()?.trim_end_matches('/');
+            ChannelTab::from_path(path.rsplit('/').next()?)
+        })
+        .collect();
+    tabs.dedup();
+    // A channel page without tab entries lists its videos directly
+    if tabs.is_empty() {
+        tabs.push(ChannelTab::Videos);
+    }
+    if !tabs.contains(&ChannelTab::Playlists) {
+        tabs.push(ChannelTab::Playlists);
+    }
+
+    Ok(ChannelTabs {
+        channel: channel_identity(&json, &base),
+        tabs,
+    })
+}
+
+/// Fetch the entries of one channel tab, optionally limited to an upload date range
+/// and a maximum number of entries (newest first).
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_channel_tab(
+    app: AppHandle,
+    request: ChannelTabRequest,
+) -> Result<ChannelTabResult, AppError> {
+    let url = security::sanitize_url(&request.url)?;
+    let base = channel_base_url(&url)
+        .ok_or_else(|| AppError::InvalidUrl("Not a YouTube channel URL".to_string()))?;
+    let url = tab_url(&base, request.tab);
+
+    let mut args: Vec<String> = Vec::new();
+    let date_after = request
+        .date_after
+        .as_deref()
+        .map(validate_date_bound)
+        .transpose()?;
+    let date_before = request
+        .date_before
+        .as_deref()
+        .map(validate_date_bound)
+        .transpose()?;
+    if date_after.is_some() || date_before.is_some() {
+        // Flat entries carry no upload date unless yt-dlp estimates it from "2 weeks ago"
+        args.extend([
+            "--extractor-args".to_string(),
+            "youtubetab:approximate_date".to_string(),
+        ]);
+    }
+    if let Some(date) = &date_after {
+        args.extend(["--dateafter".to_string(), date.clone()]);
+        // Tabs list newest first, so stop at the first older upload instead of walking
+        // the whole tab; entries without a date (e.g. playlists) don't stop it
+        let today = chrono::Local::now().date_naive();
+        if let Some(absolute) = resolve_date_bound(date, today) {
+            args.extend([
+                "--break-match-filters".to_string(),
+                format!("upload_date>=?{}", absolute),
+            ]);
+        }
+    }
+    if let Some(date) = &date_before {
+        args.extend(["--datebefore".to_string(), date.clone()]);
+    }
+    let limit = request.limit.filter(|l| *l > 0).map(|l| l as usize);
+    let date_bounded = date_after.is_some() || date_before.is_some();
+    // With date bounds, --playlist-end would count the entries skipped as out of range,
+    // so the limit is applied to the matching entries below instead
+    if let Some(limit) = limit.filter(|_| !date_bounded) {
+        args.extend(["--playlist-end".to_string(), limit.to_string()]);
+    }
+
+    logger::info_cat(
+        "metadata",
+        &format!("Fetching channel tab: {} {:?}", url, args),
+    );
+    let json = dump_single_json(&app, &args, &url).await?;
+
+    let entries: Vec<PlaylistEntry> = json["entries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match request.tab {
+            ChannelTab::Playlists => playlist_tab_entry(entry),
+            _ => playlist_entry(entry),
+        })
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    Ok(ChannelTabResult {
+        channel: channel_identity(&json, &base),
+        tab: request.tab,
+        url,
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_base_url() {
+        assert_eq!(
+            channel_base_url("https://www.youtube.com/@LinusTechTips/shorts?view=0").as_deref(),
+            Some("https://www.youtube.com/@LinusTechTips")
+        );
+        assert_eq!(
+            channel_base_url("https://youtube.com/channel/UCXuqSBlHAE6Xw-yeJA0Tunw").as_deref(),
+            Some("https://youtube.com/channel/UCXuqSBlHAE6Xw-yeJA0Tunw")
+        );
+        assert_eq!(
+            channel_base_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            None
+        );
+        assert_eq!(
+            tab_url("https://www.youtube.com/@x", ChannelTab::Streams),
+            "https://www.youtube.com/@x/streams"
+        );
+    }
+
+    #[test]
+    fn test_validate_date_bound() {
+        assert_eq!(validate_date_bound("20240131").unwrap(), "20240131");
+        assert_eq!(validate_date_bound("Today-2weeks").unwrap(), "today-2weeks");
+        assert!(validate_date_bound("now").is_ok());
+        assert!(validate_date_bound("2024-01-31").is_err());
+        assert!(validate_date_bound("--exec rm").is_err());
+    }
+
+    #[test]
+    fn test_resolve_date_bound() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let resolve = |value| resolve_date_bound(value, today);
+        assert_eq!(resolve("20240131").as_deref(), Some("20240131"));
+        assert_eq!(resolve("today").as_deref(), Some("20240331"));
+        assert_eq!(resolve("yesterday").as_deref(), Some("20240330"));
+        assert_eq!(resolve("today-2weeks").as_deref(), Some("20240317"));
+        assert_eq!(resolve("now-1month").as_deref(), Some("20240229"));
+        assert_eq!(resolve("today+1year").as_deref(), Some("20250331"));
+        assert_eq!(resolve("2024-01-31"), None);
+    }
+
+    #[test]
+    fn test_channel_identity() {
+        let json = serde_json::json!({
+            "channel_id": "UCabc",
+            "channel": "Example",
+            "uploader_id": "@example",
+            "channel_url": "https://www.youtube.com/channel/UCabc",
+            "channel_follower_count": 1234,
+            "thumbnails": [
+                {"id": "banner_uncropped", "url": "https://yt3/banner"},
+                {"id": "avatar_uncropped", "url": "https://yt3/avatar"},
+            ],
+        });
+        let identity = channel_identity(&json, "https://www.youtube.com/@example");
+        assert_eq!(identity.channel_id.as_deref(), Some("UCabc"));
+        assert_eq!(identity.handle.as_deref(), Some("@example"));
+        assert_eq!(identity.avatar.as_deref(), Some("https://yt3/avatar"));
+        assert_eq!(identity.subscriber_count, Some(1234));
+    }
+}
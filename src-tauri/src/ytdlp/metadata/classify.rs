@@ -0,0 +1,188 @@
+use super::map_stderr_error;
+use super::validation::{canonical_extractor, validate_url};
+use crate::modules::logger;
+use crate::modules::types::{AppError, ErrorCode, ErrorDetail};
+use crate::ytdlp::types::*;
+use crate::ytdlp::{binary, security};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const CLASSIFY_TIMEOUT: Duration = Duration::from_secs(60);
+/// Classifications are kept this long; what a URL points at rarely changes
+const CLASSIFY_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const CLASSIFY_CACHE_MAX: usize = 500;
+
+static CLASSIFY_CACHE: Lazy<Mutex<HashMap<String, (Instant, UrlValidation)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cached(url: &str) -> Option<UrlValidation> {
+    let cache = CLASSIFY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get(url)
+        .filter(|(at, _)| at.elapsed() < CLASSIFY_CACHE_TTL)
+        .map(|(_, result)| result.clone())
+}
+
+fn store(url: &str, result: &UrlValidation) {
+    let mut cache = CLASSIFY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.len() >= CLASSIFY_CACHE_MAX {
+        cache.retain(|_, (at, _)| at.elapsed() < CLASSIFY_CACHE_TTL);
+        // Still full of fresh entries: drop the oldest one
+        if cache.len() >= CLASSIFY_CACHE_MAX {
+            if let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, (at, _))| *at)
+                .map(|(k, _)| k.clone())
+            {
+                cache.remove(&oldest);
+            }
+        }
+    }
+    cache.insert(url.to_string(), (Instant::now(), result.clone()));
+}
+
+/// Build a classification from yt-dlp's `--flat-playlist --dump-single-json` output.
+fn classification_from_json(json: &serde_json::Value, url: &str) -> UrlValidation {
+    let is_collection = matches!(
+        json["_type"].as_str(),
+        Some("playlist") | Some("multi_video")
+    );
+    // Same normalization as the stored canonical identity; unresolved "url" results
+    // name their extractor key in ie_key
+    let extractor = json["extractor_key"]
+        .as_str()
+        .or_else(|| json["ie_key"].as_str())
+        .map(canonical_extractor)
+        .filter(|e| !e.is_empty());
+    let id = json["id"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let normalized = json["webpage_url"].as_str().unwrap_or(url).to_string();
+
+    UrlValidation {
+        valid: true,
+        url_type: if is_collection {
+            UrlType::Playlist
+        } else {
+            UrlType::Video
+        },
+        normalized_url: Some(normalized),
+        video_id: if is_collection { None } else { id.clone() },
+        playlist_id: if is_collection { id.clone() } else { None },
+        extractor,
+        is_collection,
+        canonical_id: id,
+    }
+}
+
+/// Classify a URL more precisely than `validate_url` by asking yt-dlp which extractor
+/// handles it and whether it is a single item or a collection (Vimeo showcase,
+/// SoundCloud set, Bandcamp album, ...). Only one entry is extracted. Recognised
+/// YouTube URLs are answered without running yt-dlp. Results are cached for an hour.
+#[tauri::command]
+#[specta::specta]
+pub async fn classify_url(app: AppHandle, url: String) -> Result<UrlValidation, AppError> {
+    let quick = validate_url(url)?;
+    let Some(url) = quick.normalized_url.clone().filter(|_| quick.valid) else {
+        return Ok(quick);
+    };
+    let known_youtube = quick.extractor.as_deref() == Some("youtube")
+        && (quick.video_id.is_some() || quick.is_collection);
+    if known_youtube {
+        return Ok(quick);
+    }
+    if let Some(result) = cached(&url) {
+        return Ok(result);
+    }
+
+    logger::info_cat("metadata", &format!("Classifying URL: {}", url));
+    let ytdlp_path = binary::resolve_ytdlp_path_with_app(&app).await?;
+    let settings = crate::ytdlp::settings::get_settings(&app).unwrap_or_default();
+
+    let mut cmd = binary::command_with_path_app(&ytdlp_path, &app);
+    cmd.arg("--flat-playlist").arg("--dump-single-json");
+    cmd.arg("--playlist-items").arg("1");
+    cmd.arg("--encoding").arg("UTF-8");
+    if let Some(browser) = &settings.cookie_browser {
+        if security::sanitize_cookie_browser(browser).is_ok() {
+            cmd.arg("--cookies-from-browser").arg(browser);
+        }
+    }
+    cmd.arg("--").arg(&url);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = tokio::time::timeout(CLASSIFY_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            ErrorDetail::new(ErrorCode::Timeout).with_param("seconds", CLASSIFY_TIMEOUT.as_secs())
+        })?
+        .map_err(|e| AppError::MetadataError(format!("Failed to execute yt-dlp: {}", e)))?;
+
+    let result = if output.status.success() {
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| AppError::MetadataError(format!("Failed to parse JSON: {}", e)))?;
+        classification_from_json(&json, &url)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        match map_stderr_error(&stderr, output.status.code()) {
+            // No extractor handles it: a definite answer, so it is cached too
+            AppError::Coded(ErrorDetail {
+                code: ErrorCode::UnsupportedUrl,
+                ..
+            }) => UrlValidation {
+                valid: false,
+                url_type: UrlType::Unknown,
+                ..quick
+            },
+            e => return Err(e),
+        }
+    };
+
+    store(&url, &result);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classification_from_json() {
+        let album = serde_json::json!({
+            "_type": "playlist",
+            "id": "1234567",
+            "extractor": "Bandcamp:album",
+            "extractor_key": "BandcampAlbum",
+            "webpage_url": "https://artist.bandcamp.com/album/x",
+            "entries": [{"_type": "url", "url": "https://artist.bandcamp.com/track/a"}],
+        });
+        let result = classification_from_json(&album, "https://artist.bandcamp.com/album/x");
+        assert!(result.is_collection);
+        assert!(matches!(result.url_type, UrlType::Playlist));
+        assert_eq!(result.extractor.as_deref(), Some("bandcampalbum"));
+        assert_eq!(result.canonical_id.as_deref(), Some("1234567"));
+        assert_eq!(result.playlist_id.as_deref(), Some("1234567"));
+        assert_eq!(result.video_id, None);
+
+        let video =
+            serde_json::json!({"id": "76979871", "extractor": "vimeo", "extractor_key": "Vimeo"});
+        let result = classification_from_json(&video, "https://vimeo.com/76979871");
+        assert!(!result.is_collection);
+        assert_eq!(result.extractor.as_deref(), Some("vimeo"));
+        assert_eq!(result.video_id.as_deref(), Some("76979871"));
+        assert_eq!(result.playlist_id, None);
+        assert_eq!(
+            result.normalized_url.as_deref(),
+            Some("https://vimeo.com/76979871")
+        );
+    }
+}
@@ -1,7 +1,8 @@
+use super::cache::{cached_video_info, save_info_json};
 use super::map_stderr_error;
 use super::validation::{PLAYLIST_PATTERN, VIDEO_PATTERNS};
 use crate::modules::logger;
-use crate::modules::types::AppError;
+use crate::modules::types::{AppError, ErrorCode, ErrorDetail};
 use crate::ytdlp::types::*;
 use crate::ytdlp::{binary, security};
 use std::time::Duration;
@@ -10,17 +11,31 @@ use tauri::AppHandle;
 /// Timeout for metadata fetch operations (2 minutes)
 const METADATA_TIMEOUT: Duration = Duration::from_secs(120);
 
-/// Fetch video metadata using yt-dlp --dump-json
+/// Fetch video metadata, served from the metadata cache when a fresh entry exists.
+/// `force_refresh` always runs yt-dlp (and updates the cache).
 #[tauri::command]
 #[specta::specta]
-pub async fn fetch_video_info(app: AppHandle, url: String) -> Result<VideoInfo, AppError> {
+pub async fn fetch_video_info(
+    app: AppHandle,
+    url: String,
+    force_refresh: Option<bool>,
+) -> Result<VideoInfo, AppError> {
     let url = security::sanitize_url(&url)?;
+    cached_video_info(&app, &url, force_refresh.unwrap_or(false)).await
+}
+
+/// Fetch video metadata using yt-dlp --dump-json
+pub(super) async fn fetch_video_info_uncached(
+    app: &AppHandle,
+    url: &str,
+) -> Result<VideoInfo, AppError> {
     logger::info_cat("metadata", &format!("Fetching video info: {}", url));
-    let ytdlp_path = binary::resolve_ytdlp_path_with_app(&app).await?;
-    let settings = crate::ytdlp::settings::get_settings(&app).unwrap_or_default();
+    let ytdlp_path = binary::resolve_ytdlp_path_with_app(app).await?;
+    let settings = crate::ytdlp::settings::get_settings(app).unwrap_or_default();
 
-    // Run yt-dlp with --dump-json
-    let mut cmd = binary::command_with_path_app(&ytdlp_path, &app);
+    // Run yt-dlp with --dump-json; killed if the fetch is aborted (cancelled batch)
+    let mut cmd = binary::command_with_path_app(&ytdlp_path, app);
+    cmd.kill_on_drop(true);
     cmd.arg("--dump-json").arg("--no-playlist");
     cmd.arg("--encoding").arg("UTF-8");
     if let Some(browser) = &settings.cookie_browser {
@@ -28,7 +43,7 @@ pub async fn fetch_video_info(app: AppHandle, url: String) -> Result<VideoInfo,
             cmd.arg("--cookies-from-browser").arg(browser);
         }
     }
-    cmd.arg(&url);
+    cmd.arg(url);
 
     #[cfg(target_os = "windows")]
     {
@@ -39,9 +54,7 @@ pub async fn fetch_video_info(app: AppHandle, url: String) -> Result<VideoInfo,
     let output = tokio::time::timeout(METADATA_TIMEOUT, cmd.output())
         .await
         .map_err(|_| {
-            AppError::MetadataError(
-                "메타데이터 요청 시간이 초과되었습니다. 네트워크 연결을 확인하세요.".to_string(),
-            )
+            ErrorDetail::new(ErrorCode::Timeout).with_param("seconds", METADATA_TIMEOUT.as_secs())
         })?
         .map_err(|e| AppError::MetadataError(format!("Failed to execute yt-dlp: {}", e)))?;
 
@@ -54,7 +67,7 @@ pub async fn fetch_video_info(app: AppHandle, url: String) -> Result<VideoInfo,
                 security::sanitize_error_message(&stderr)
             ),
         );
-        return Err(map_stderr_error(&stderr));
+        return Err(map_stderr_error(&stderr, output.status.code()));
     }
 
     // Parse JSON output
@@ -91,46 +104,41 @@ pub async fn fetch_video_info(app: AppHandle, url: String) -> Result<VideoInfo,
         .unwrap_or("")
         .to_string();
 
-    let webpage_url = json["webpage_url"].as_str().unwrap_or(&url).to_string();
+    let webpage_url = json["webpage_url"].as_str().unwrap_or(url).to_string();
 
     let filesize_approx = json["filesize_approx"].as_u64();
 
+    let extractor_key = json["extractor_key"].as_str().map(|s| s.to_string());
+
     // Extract formats
     let formats = json["formats"]
         .as_array()
         .ok_or_else(|| AppError::MetadataError("Missing formats array".to_string()))?
         .iter()
-        .filter_map(|format| {
-            let format_id = format["format_id"].as_str()?.to_string();
-            let ext = format["ext"].as_str()?.to_string();
-            let resolution = format["resolution"].as_str().map(|s| s.to_string());
-            let quality_label = format["format_note"].as_str().map(|s| s.to_string());
-            let filesize = format["filesize"].as_u64();
-            let vcodec = format["vcodec"].as_str().map(|s| s.to_string());
-            let acodec = format["acodec"].as_str().map(|s| s.to_string());
-
-            let has_video = vcodec.as_deref() != Some("none");
-            let has_audio = acodec.as_deref() != Some("none");
-
-            Some(FormatInfo {
-                format_id,
-                ext,
-                resolution,
-                quality_label,
-                filesize,
-                vcodec,
-                acodec,
-                has_video,
-                has_audio,
-            })
-        })
+        .filter_map(parse_format)
         .collect();
 
+    let description = json["description"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let chapters = parse_chapters(&json);
+    let tags = json["tags"]
+        .as_array()
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
     logger::info_cat(
         "metadata",
         &format!("Video info fetched: {} ({})", title, video_id),
     );
 
+    save_info_json(app, url, &stdout).await;
+
     Ok(VideoInfo {
         url: webpage_url,
         video_id,
@@ -142,9 +150,77 @@ pub async fn fetch_video_info(app: AppHandle, url: String) -> Result<VideoInfo,
         channel_url,
         formats,
         filesize_approx,
+        extractor_key,
+        description,
+        chapters,
+        view_count: json["view_count"].as_u64(),
+        like_count: json["like_count"].as_u64(),
+        age_limit: json["age_limit"].as_u64().map(|n| n as u32),
+        tags,
+        availability: json["availability"].as_str().map(|s| s.to_string()),
+        live_status: json["live_status"].as_str().map(|s| s.to_string()),
+    })
+}
+
+/// Map one entry of yt-dlp's `formats` array. Entries without an ID or extension are skipped.
+fn parse_format(format: &serde_json::Value) -> Option<FormatInfo> {
+    let str_field = |key: &str| format[key].as_str().map(|s| s.to_string());
+    let format_id = str_field("format_id")?;
+    let ext = str_field("ext")?;
+    let vcodec = str_field("vcodec");
+    let acodec = str_field("acodec");
+
+    let has_video = vcodec.as_deref() != Some("none");
+    let has_audio = acodec.as_deref() != Some("none");
+
+    Some(FormatInfo {
+        format_id,
+        ext,
+        resolution: str_field("resolution"),
+        quality_label: str_field("format_note"),
+        filesize: format["filesize"].as_u64(),
+        filesize_approx: format["filesize_approx"].as_u64(),
+        vcodec,
+        acodec,
+        has_video,
+        has_audio,
+        fps: format["fps"].as_f64(),
+        tbr: format["tbr"].as_f64(),
+        vbr: format["vbr"].as_f64(),
+        abr: format["abr"].as_f64(),
+        dynamic_range: str_field("dynamic_range"),
+        language: str_field("language"),
+        audio_channels: format["audio_channels"].as_u64().map(|n| n as u32),
+        protocol: str_field("protocol"),
+        has_drm: match &format["has_drm"] {
+            serde_json::Value::Bool(drm) => *drm,
+            serde_json::Value::String(s) => s == "maybe",
+            _ => false,
+        },
     })
 }
 
+/// Chapters with a start and end time; untitled ones get a numbered title.
+fn parse_chapters(json: &serde_json::Value) -> Vec<VideoChapter> {
+    json["chapters"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(i, chapter)| {
+            Some(VideoChapter {
+                title: chapter["title"]
+                    .as_str()
+                    .filter(|t| !t.is_empty())
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| format!("Chapter {}", i + 1)),
+                start_time: chapter["start_time"].as_f64()?,
+                end_time: chapter["end_time"].as_f64()?,
+            })
+        })
+        .collect()
+}
+
 /// Fetch playlist metadata and entries using yt-dlp --flat-playlist
 #[tauri::command]
 #[specta::specta]
@@ -191,16 +267,13 @@ pub async fn fetch_playlist_info(
     let output = tokio::time::timeout(playlist_timeout, cmd.output())
         .await
         .map_err(|_| {
-            AppError::MetadataError(
-                "재생목록 메타데이터 요청 시간이 초과되었습니다. 네트워크 연결을 확인하세요."
-                    .to_string(),
-            )
+            ErrorDetail::new(ErrorCode::Timeout).with_param("seconds", playlist_timeout.as_secs())
         })?
         .map_err(|e| AppError::MetadataError(format!("Failed to execute yt-dlp: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        return Err(map_stderr_error(&stderr));
+        return Err(map_stderr_error(&stderr, output.status.code()));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -243,12 +316,44 @@ pub async fn fetch_playlist_info(
 
     // Extract playlist-level metadata from the first entry or any entry with playlist info
     let first_entry = &all_entries[0];
+    let (playlist_id, title, channel_name) = playlist_header(first_entry, &url);
+
+    // Try to extract total count from yt-dlp's playlist_count field
+    let video_count: Option<u64> =
+        first_entry["playlist_count"]
+            .as_u64()
+            .or(if page_size >= 99999 {
+                Some(all_entries.len() as u64) // Full fetch: len() is accurate
+            } else {
+                None // Paginated: total count unknown
+            });
+
+    // Map entries to PlaylistEntry structs
+    let playlist_entries: Vec<PlaylistEntry> =
+        all_entries.iter().filter_map(playlist_entry).collect();
+
+    // -I flag handles server-side pagination, no skip/take needed
+    Ok(PlaylistResult {
+        playlist_id,
+        title,
+        url: url.clone(),
+        video_count,
+        channel_name,
+        entries: playlist_entries,
+    })
+}
 
+/// Playlist ID, title and channel name from a `--flat-playlist` entry,
+/// falling back to the ID in the playlist URL.
+pub(super) fn playlist_header(
+    first_entry: &serde_json::Value,
+    url: &str,
+) -> (String, String, Option<String>) {
     let playlist_id = first_entry["playlist_id"]
         .as_str()
         .or_else(|| {
             // Try to extract from URL
-            if let Some(captures) = PLAYLIST_PATTERN.captures(&url) {
+            if let Some(captures) = PLAYLIST_PATTERN.captures(url) {
                 captures.get(1).map(|m| m.as_str())
             } else {
                 None
@@ -269,68 +374,49 @@ pub async fn fetch_playlist_info(
         .or_else(|| first_entry["uploader"].as_str())
         .map(|s| s.to_string());
 
-    // Try to extract total count from yt-dlp's playlist_count field
-    let video_count: Option<u64> =
-        first_entry["playlist_count"]
-            .as_u64()
-            .or(if page_size >= 99999 {
-                Some(all_entries.len() as u64) // Full fetch: len() is accurate
-            } else {
-                None // Paginated: total count unknown
-            });
+    (playlist_id, title, channel_name)
+}
 
-    // Map entries to PlaylistEntry structs
-    let mut playlist_entries: Vec<PlaylistEntry> = Vec::new();
-    for entry in &all_entries {
-        // Extract video_id
-        let video_id = entry["id"]
-            .as_str()
-            .or_else(|| entry["url"].as_str())
-            .unwrap_or("")
-            .to_string();
-
-        if video_id.is_empty() {
-            continue;
-        }
+/// Map one `--flat-playlist` JSON line to a PlaylistEntry. Entries without an ID are skipped.
+pub(super) fn playlist_entry(entry: &serde_json::Value) -> Option<PlaylistEntry> {
+    // Extract video_id
+    let video_id = entry["id"]
+        .as_str()
+        .or_else(|| entry["url"].as_str())
+        .unwrap_or("")
+        .to_string();
 
-        // Construct video URL
-        let video_url = if video_id.starts_with("http") {
-            video_id.clone()
-        } else {
-            format!("https://www.youtube.com/watch?v={}", video_id)
-        };
-
-        let entry_title = entry["title"].as_str().map(|s| s.to_string());
-        let duration = entry["duration"].as_u64();
-
-        // Extract thumbnail
-        let thumbnail = entry["thumbnail"]
-            .as_str()
-            .or_else(|| {
-                entry["thumbnails"]
-                    .as_array()
-                    .and_then(|arr| arr.first())
-                    .and_then(|t| t["url"].as_str())
-            })
-            .map(|s| s.to_string());
-
-        playlist_entries.push(PlaylistEntry {
-            url: video_url,
-            video_id,
-            title: entry_title,
-            duration,
-            thumbnail,
-        });
+    if video_id.is_empty() {
+        return None;
     }
 
-    // -I flag handles server-side pagination, no skip/take needed
-    Ok(PlaylistResult {
-        playlist_id,
+    // Construct video URL
+    let video_url = if video_id.starts_with("http") {
+        video_id.clone()
+    } else {
+        format!("https://www.youtube.com/watch?v={}", video_id)
+    };
+
+    let title = entry["title"].as_str().map(|s| s.to_string());
+    let duration = entry["duration"].as_u64();
+
+    // Extract thumbnail
+    let thumbnail = entry["thumbnail"]
+        .as_str()
+        .or_else(|| {
+            entry["thumbnails"]
+                .as_array()
+                .and_then(|arr| arr.first())
+                .and_then(|t| t["url"].as_str())
+        })
+        .map(|s| s.to_string());
+
+    Some(PlaylistEntry {
+        url: video_url,
+        video_id,
         title,
-        url: url.clone(),
-        video_count,
-        channel_name,
-        entries: playlist_entries,
+        duration,
+        thumbnail,
     })
 }
 
@@ -401,3 +487,68 @@ pub async fn fetch_quick_metadata(url: String) -> Result<QuickMetadata, AppError
         thumbnail,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() {
+        let format = serde_json::json!({
+            "format_id": "337",
+            "ext": "webm",
+            "resolution": "3840x2160",
+            "format_note": "2160p60 HDR",
+            "filesize_approx": 1_500_000_000u64,
+            "vcodec": "vp09.02.51.10.01.09.16.09.00",
+            "acodec": "none",
+            "fps": 60,
+            "tbr": 21000.5,
+            "vbr": 21000.5,
+            "dynamic_range": "HDR10",
+            "protocol": "https",
+            "has_drm": false,
+        });
+        let parsed = parse_format(&format).unwrap();
+        assert!(parsed.has_video && !parsed.has_audio);
+        assert_eq!(parsed.filesize, None);
+        assert_eq!(parsed.filesize_approx, Some(1_500_000_000));
+        assert_eq!(parsed.fps, Some(60.0));
+        assert_eq!(parsed.dynamic_range.as_deref(), Some("HDR10"));
+        assert!(!parsed.has_drm);
+
+        let audio = serde_json::json!({
+            "format_id": "251-drc",
+            "ext": "webm",
+            "vcodec": "none",
+            "acodec": "opus",
+            "abr": 129.5,
+            "language": "en",
+            "audio_channels": 2,
+            "protocol": "m3u8_native",
+            "has_drm": "maybe",
+        });
+        let parsed = parse_format(&audio).unwrap();
+        assert_eq!(parsed.language.as_deref(), Some("en"));
+        assert_eq!(parsed.audio_channels, Some(2));
+        assert!(parsed.has_drm);
+
+        assert!(parse_format(&serde_json::json!({"ext": "mp4"})).is_none());
+    }
+
+    #[test]
+    fn test_parse_chapters() {
+        let json = serde_json::json!({
+            "chapters": [
+                {"title": "Intro", "start_time": 0.0, "end_time": 12.5},
+                {"title": "", "start_time": 12.5, "end_time": 60.0},
+                {"title": "Broken"},
+            ]
+        });
+        let chapters = parse_chapters(&json);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].title, "Chapter 2");
+        assert_eq!(chapters[1].start_time, 12.5);
+        assert!(parse_chapters(&serde_json::json!({"chapters": null})).is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+use super::cancel;
+use super::fetch::{playlist_entry, playlist_header};
+use super::map_stderr_error;
+use crate::modules::logger;
+use crate::modules::types::{AppError, ErrorCode, ErrorDetail};
+use crate::ytdlp::types::*;
+use crate::ytdlp::{binary, security};
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+/// Entries are emitted once this many have arrived, or after FLUSH_INTERVAL
+const BATCH_SIZE: usize = 50;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+/// A fetch fails if yt-dlp prints nothing for this long; large playlists have no overall limit
+const IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn emit_batch(app: &AppHandle, fetch_id: &str, batch: &mut Vec<PlaylistEntry>, received: u64) {
+    if batch.is_empty() {
+        return;
+    }
+    let _ = app.emit(
+        "playlist-entries-event",
+        PlaylistEntriesEvent {
+            fetch_id: fetch_id.to_string(),
+            entries: std::mem::take(batch),
+            received,
+        },
+    );
+}
+
+/// Fetch a whole playlist or channel with `--flat-playlist`, streaming entries to the
+/// frontend as `playlist-entries-event` events while yt-dlp is still running. `fetch_id` is
+/// chosen by the caller so the fetch can be cancelled with `cancel_playlist_fetch`.
+/// Returns a summary once yt-dlp exits or the fetch is cancelled; entries received
+/// before a cancel or a late failure are kept.
+#[tauri::command]
+#[specta::specta]
+pub async fn stream_playlist_info(
+    app: AppHandle,
+    url: String,
+    fetch_id: String,
+) -> Result<PlaylistStreamSummary, AppError> {
+    let url = security::sanitize_url(&url)?;
+    let (mut cancel_rx, _registration) = cancel::register(&fetch_id)?;
+
+    logger::info_cat(
+        "metadata",
+        &format!("Streaming playlist info: {} ({})", url, fetch_id),
+    );
+    let ytdlp_path = binary::resolve_ytdlp_path_with_app(&app).await?;
+    let settings = crate::ytdlp::settings::get_settings(&app).unwrap_or_default();
+
+    let mut cmd = binary::command_with_path_app(&ytdlp_path, &app);
+    cmd.arg("--flat-playlist").arg("--dump-json");
+    cmd.arg("--encoding").arg("UTF-8");
+    if let Some(browser) = &settings.cookie_browser {
+        if security::sanitize_cookie_browser(browser).is_ok() {
+            cmd.arg("--cookies-from-browser").arg(browser);
+        }
+    }
+    cmd.arg(&url);
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd.kill_on_drop(true);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| AppError::MetadataError(format!("Failed to execute yt-dlp: {}", e)))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::MetadataError("yt-dlp stdout unavailable".to_string()))?;
+    let stderr_handle = child.stderr.take().map(|mut stderr| {
+        tokio::spawn(async move {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf).await;
+            String::from_utf8_lossy(&buf).to_string()
+        })
+    });
+
+    let mut summary = PlaylistStreamSummary {
+        fetch_id: fetch_id.clone(),
+        url: url.clone(),
+        ..Default::default()
+    };
+    let mut batch: Vec<PlaylistEntry> = Vec::with_capacity(BATCH_SIZE);
+    let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+    flush.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut last_output = tokio::time::Instant::now();
+    let mut reader = BufReader::new(stdout);
+    // read_until is cancel-safe: a line cut short by another branch stays in `buf`
+    let mut buf = Vec::new();
+
+    loop {
+        let read = tokio::select! {
+            _ = cancel_rx.changed() => {
+                summary.cancelled = true;
+                break;
+            }
+            // Entries can trickle in slowly; don't hold a partial batch until the next one
+            _ = flush.tick() => {
+                emit_batch(&app, &fetch_id, &mut batch, summary.received);
+                continue;
+            }
+            _ = tokio::time::sleep_until(last_output + IDLE_TIMEOUT) => {
+                let _ = child.kill().await;
+                emit_batch(&app, &fetch_id, &mut batch, summary.received);
+                // Stalled: yt-dlp produced no output for IDLE_TIMEOUT
+                return Err(AppError::Coded(
+                    ErrorDetail::new(ErrorCode::Timeout)
+                        .with_param("seconds", IDLE_TIMEOUT.as_secs()),
+                ));
+            }
+            read = reader.read_until(b'\n', &mut buf) => read,
+        };
+        match read {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        last_output = tokio::time::Instant::now();
+
+        let line = String::from_utf8_lossy(&buf).into_owned();
+        buf.clear();
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let json = match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(json) => json,
+            Err(e) => {
+                logger::warn_cat(
+                    "metadata",
+                    &format!("Failed to parse playlist entry JSON: {}", e),
+                );
+                continue;
+            }
+        };
+
+        if summary.received == 0 && summary.playlist_id.is_empty() {
+            let (playlist_id, title, channel_name) = playlist_header(&json, &url);
+            summary.playlist_id = playlist_id;
+            summary.title = title;
+            summary.channel_name = channel_name;
+            summary.video_count = json["playlist_count"].as_u64();
+        }
+        if let Some(entry) = playlist_entry(&json) {
+            batch.push(entry);
+            summary.received += 1;
+        }
+
+        if batch.len() >= BATCH_SIZE {
+            emit_batch(&app, &fetch_id, &mut batch, summary.received);
+            flush.reset();
+        }
+    }
+    emit_batch(&app, &fetch_id, &mut batch, summary.received);
+
+    if summary.cancelled {
+        let _ = child.kill().await;
+        logger::info_cat(
+            "metadata",
+            &format!(
+                "Playlist fetch {} cancelled after {} entries",
+                fetch_id, summary.received
+            ),
+        );
+        return Ok(summary);
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| AppError::MetadataError(format!("Failed to wait for yt-dlp: {}", e)))?;
+    let stderr = match stderr_handle {
+        Some(handle) => handle.await.unwrap_or_default(),
+        None => String::new(),
+    };
+    if !status.success() {
+        if summary.received == 0 {
+            return Err(map_stderr_error(&stderr, status.code()));
+        }
+        // Some entries made it out; report them and keep the error in the log
+        logger::warn_cat(
+            "metadata",
+            &format!(
+                "Playlist fetch {} ended early after {} entries: {}",
+                fetch_id,
+                summary.received,
+                security::sanitize_error_message(&stderr)
+            ),
+        );
+        summary.incomplete = true;
+    } else if summary.received == 0 {
+        return Err(AppError::MetadataError(
+            "No entries found in playlist".to_string(),
+        ));
+    }
+
+    // Only a complete fetch knows the total by itself
+    if !summary.incomplete {
+        summary.video_count = summary.video_count.or(Some(summary.received));
+    }
+    Ok(summary)
+}
+
+/// Stop a streamed playlist fetch. Returns false if no fetch with this ID is running.
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_playlist_fetch(fetch_id: String) -> Result<bool, AppError> {
+    Ok(cancel::cancel(&fetch_id))
+}
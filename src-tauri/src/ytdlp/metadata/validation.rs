@@ -3,26 +3,38 @@ use crate::ytdlp::types::*;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-// Regex patterns for YouTube URL validation
+// Regex patterns for YouTube URL validation.
+// www., m. (mobile) and music. hosts are all the same site; an ID must end at a
+// non-ID character so longer tokens aren't truncated into a valid-looking ID.
 pub(super) static VIDEO_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     vec![
-        Regex::new(r"^https?://(?:www\.)?youtube\.com/watch\?v=([a-zA-Z0-9_-]{11})").unwrap(),
-        Regex::new(r"^https?://(?:www\.)?youtu\.be/([a-zA-Z0-9_-]{11})").unwrap(),
-        Regex::new(r"^https?://(?:www\.)?youtube\.com/shorts/([a-zA-Z0-9_-]{11})").unwrap(),
+        // v= may follow other query parameters (e.g. ?feature=share&v=...)
+        Regex::new(r"^https?://(?:(?:www|m|music)\.)?youtube\.com/watch\?(?:[^#]*&)?v=([a-zA-Z0-9_-]{11})(?:[^a-zA-Z0-9_-]|$)").unwrap(),
+        Regex::new(r"^https?://(?:www\.)?youtu\.be/([a-zA-Z0-9_-]{11})(?:[^a-zA-Z0-9_-]|$)").unwrap(),
+        Regex::new(r"^https?://(?:(?:www|m)\.)?youtube\.com/(?:shorts|live|embed|v)/([a-zA-Z0-9_-]{11})(?:[^a-zA-Z0-9_-]|$)").unwrap(),
+        Regex::new(r"^https?://(?:www\.)?youtube-nocookie\.com/embed/([a-zA-Z0-9_-]{11})(?:[^a-zA-Z0-9_-]|$)").unwrap(),
     ]
 });
 
 pub(super) static PLAYLIST_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^https?://(?:www\.)?youtube\.com/playlist\?list=([a-zA-Z0-9_-]+)").unwrap()
+    Regex::new(
+        r"^https?://(?:(?:www|m|music)\.)?youtube\.com/playlist\?(?:[^#]*&)?list=([a-zA-Z0-9_-]+)",
+    )
+    .unwrap()
 });
 
-static CHANNEL_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+/// Playlist ID carried alongside a video (watch?v=X&list=Y)
+static LIST_PARAM: Lazy<Regex> = Lazy::new(|| Regex::new(r"[?&]list=([a-zA-Z0-9_-]+)").unwrap());
+
+pub(super) static CHANNEL_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     vec![
-        Regex::new(r"^https?://(?:www\.)?youtube\.com/channel/([a-zA-Z0-9_-]+)").unwrap(),
-        Regex::new(r"^https?://(?:www\.)?youtube\.com/@([a-zA-Z0-9_.%\x{0080}-\x{FFFF}-]+)")
+        Regex::new(r"^https?://(?:(?:www|m|music)\.)?youtube\.com/channel/([a-zA-Z0-9_-]+)")
+            .unwrap(),
+        Regex::new(r"^https?://(?:(?:www|m)\.)?youtube\.com/@([a-zA-Z0-9_.%\x{0080}-\x{FFFF}-]+)")
             .unwrap(),
-        Regex::new(r"^https?://(?:www\.)?youtube\.com/c/([a-zA-Z0-9_.%\x{0080}-\x{FFFF}-]+)")
+        Regex::new(r"^https?://(?:(?:www|m)\.)?youtube\.com/c/([a-zA-Z0-9_.%\x{0080}-\x{FFFF}-]+)")
             .unwrap(),
+        Regex::new(r"^https?://(?:(?:www|m)\.)?youtube\.com/user/([a-zA-Z0-9_.-]+)").unwrap(),
     ]
 });
 
@@ -39,12 +51,17 @@ pub fn validate_url(url: String) -> Result<UrlValidation, AppError> {
                 url_type: UrlType::Unknown,
                 normalized_url: None,
                 video_id: None,
+                playlist_id: None,
+                extractor: None,
+                is_collection: false,
+                canonical_id: None,
             });
         }
     };
     let url = url.trim();
 
-    // Check for video URLs
+    // Check for video URLs. A list= parameter is reported too, so the caller can
+    // offer the whole playlist instead of the single video.
     for pattern in VIDEO_PATTERNS.iter() {
         if let Some(captures) = pattern.captures(url) {
             let video_id = captures.get(1).unwrap().as_str();
@@ -54,6 +71,12 @@ pub fn validate_url(url: String) -> Result<UrlValidation, AppError> {
                 url_type: UrlType::Video,
                 normalized_url: Some(normalized),
                 video_id: Some(video_id.to_string()),
+                playlist_id: LIST_PARAM
+                    .captures(url)
+                    .map(|c| c.get(1).unwrap().as_str().to_string()),
+                extractor: Some("youtube".to_string()),
+                is_collection: false,
+                canonical_id: Some(video_id.to_string()),
             });
         }
     }
@@ -67,6 +90,10 @@ pub fn validate_url(url: String) -> Result<UrlValidation, AppError> {
             url_type: UrlType::Playlist,
             normalized_url: Some(normalized),
             video_id: None,
+            playlist_id: Some(playlist_id.to_string()),
+            extractor: Some("youtube".to_string()),
+            is_collection: true,
+            canonical_id: Some(playlist_id.to_string()),
         });
     }
 
@@ -78,6 +105,10 @@ pub fn validate_url(url: String) -> Result<UrlValidation, AppError> {
                 url_type: UrlType::Channel,
                 normalized_url: Some(url.to_string()),
                 video_id: None,
+                playlist_id: None,
+                extractor: Some("youtube".to_string()),
+                is_collection: true,
+                canonical_id: None,
             });
         }
     }
@@ -85,6 +116,7 @@ pub fn validate_url(url: String) -> Result<UrlValidation, AppError> {
     // Not a recognised YouTube URL — fall back to generic HTTP(S) check.
     // sanitize_url already enforces http/https and SSRF protection,
     // so here we just verify the scheme and let yt-dlp decide if it works.
+    // `classify_url` asks yt-dlp whether it is really a single video.
     let lower = url.to_lowercase();
     if lower.starts_with("http://") || lower.starts_with("https://") {
         return Ok(UrlValidation {
@@ -92,6 +124,10 @@ pub fn validate_url(url: String) -> Result<UrlValidation, AppError> {
             url_type: UrlType::Video,
             normalized_url: Some(url.to_string()),
             video_id: None,
+            playlist_id: None,
+            extractor: site_from_url(url),
+            is_collection: false,
+            canonical_id: None,
         });
     }
 
@@ -100,5 +136,173 @@ pub fn validate_url(url: String) -> Result<UrlValidation, AppError> {
         url_type: UrlType::Unknown,
         normalized_url: None,
         video_id: None,
+        playlist_id: None,
+        extractor: None,
+        is_collection: false,
+        canonical_id: None,
     })
 }
+
+/// YouTube video ID in any of the URL shapes that point at a single video
+static YOUTUBE_ID_IN_URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:[?&]v=|youtu\.be/|/shorts/|/live/|/embed/|/v/|/e/)([a-zA-Z0-9_-]{11})(?:[^a-zA-Z0-9_-]|$)")
+        .unwrap()
+});
+
+/// The extractor half of a canonical identity: yt-dlp's `extractor_key`, lowercased
+/// ("Youtube" -> "youtube", "BandcampAlbum" -> "bandcampalbum").
+pub fn canonical_extractor(extractor_key: &str) -> String {
+    extractor_key.trim().to_lowercase()
+}
+
+/// Canonical `(extractor, id)` for a URL when it can be determined without yt-dlp.
+/// Every YouTube form (`youtu.be`, `/shorts/`, `music.`/`m.` hosts, embeds) maps to
+/// `("youtube", id)`; other sites return None until yt-dlp reports `extractor_key`/`id`.
+pub fn canonical_identity(url: &str) -> Option<(String, String)> {
+    let site = site_from_url(url)?;
+    let lower = url.trim().to_lowercase();
+    let is_youtube = site == "youtube" || lower.contains("youtube-nocookie.com/");
+    if !is_youtube {
+        return None;
+    }
+    let id = YOUTUBE_ID_IN_URL.captures(url.trim())?.get(1)?.as_str();
+    Some(("youtube".to_string(), id.to_string()))
+}
+
+/// Second-level labels used under country-code TLDs (co.uk, or.jp, com.br, ...)
+const SECOND_LEVEL_SUFFIXES: &[&str] = &[
+    "ac", "co", "com", "edu", "go", "gov", "ne", "net", "or", "org",
+];
+
+/// Short site name for a URL, used as the history "extractor" until yt-dlp reports one:
+/// "youtube" for youtube.com/youtu.be, otherwise the registrable name ("vimeo" for
+/// player.vimeo.com). Returns None for URLs without a host.
+pub fn site_from_url(url: &str) -> Option<String> {
+    let lower = url.trim().to_lowercase();
+    let after_scheme = lower.split_once("://").map(|(_, rest)| rest)?;
+    // Only the authority part; '@' in paths (e.g. /@channel) is not userinfo
+    let authority = after_scheme.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+
+    if host == "youtu.be" || host == "youtube.com" || host.ends_with(".youtube.com") {
+        return Some("youtube".to_string());
+    }
+
+    let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+    match labels.len() {
+        0 => None,
+        1 => Some(labels[0].to_string()),
+        // Treat "co.uk"-style second levels as part of the suffix
+        n if n >= 3
+            && labels[n - 1].len() == 2
+            && SECOND_LEVEL_SUFFIXES.contains(&labels[n - 2]) =>
+        {
+            Some(labels[n - 3].to_string())
+        }
+        n => Some(labels[n - 2].to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (url, expected type, video ID, playlist ID)
+    const URL_CORPUS: &[(&str, &str, Option<&str>, Option<&str>)] = &[
+        ("https://www.youtube.com/watch?v=dQw4w9WgXcQ", "video", Some("dQw4w9WgXcQ"), None),
+        ("http://youtube.com/watch?v=dQw4w9WgXcQ&t=42s", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://m.youtube.com/watch?v=dQw4w9WgXcQ", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://m.youtube.com/watch?feature=share&v=dQw4w9WgXcQ", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://music.youtube.com/watch?v=dQw4w9WgXcQ&si=abc", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://youtu.be/dQw4w9WgXcQ", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://youtu.be/dQw4w9WgXcQ?si=Xyz&t=10", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://www.youtube.com/shorts/aqz-KE-bpKQ", "video", Some("aqz-KE-bpKQ"), None),
+        ("https://m.youtube.com/shorts/aqz-KE-bpKQ?feature=share", "video", Some("aqz-KE-bpKQ"), None),
+        ("https://www.youtube.com/live/jfKfPfyJRdk", "video", Some("jfKfPfyJRdk"), None),
+        ("https://www.youtube.com/live/jfKfPfyJRdk?si=a", "video", Some("jfKfPfyJRdk"), None),
+        ("https://www.youtube.com/embed/dQw4w9WgXcQ", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ?start=5", "video", Some("dQw4w9WgXcQ"), None),
+        ("https://www.youtube.com/v/dQw4w9WgXcQ", "video", Some("dQw4w9WgXcQ"), None),
+        (
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI&index=3",
+            "video",
+            Some("dQw4w9WgXcQ"),
+            Some("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"),
+        ),
+        (
+            "https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RDAMVMdQw4w9WgXcQ",
+            "video",
+            Some("dQw4w9WgXcQ"),
+            Some("RDAMVMdQw4w9WgXcQ"),
+        ),
+        (
+            "https://youtu.be/dQw4w9WgXcQ?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+            "video",
+            Some("dQw4w9WgXcQ"),
+            Some("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"),
+        ),
+        (
+            "https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+            "playlist",
+            None,
+            Some("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"),
+        ),
+        (
+            "https://music.youtube.com/playlist?list=OLAK5uy_k3zVjyLhQ-8Vq1XQwZ9dZG3p0nYtOJwKc",
+            "playlist",
+            None,
+            Some("OLAK5uy_k3zVjyLhQ-8Vq1XQwZ9dZG3p0nYtOJwKc"),
+        ),
+        (
+            "https://m.youtube.com/playlist?app=desktop&list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+            "playlist",
+            None,
+            Some("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI"),
+        ),
+        ("https://www.youtube.com/@LinusTechTips", "channel", None, None),
+        ("https://m.youtube.com/@LinusTechTips/videos", "channel", None, None),
+        ("https://www.youtube.com/channel/UCXuqSBlHAE6Xw-yeJA0Tunw", "channel", None, None),
+        ("https://music.youtube.com/channel/UCXuqSBlHAE6Xw-yeJA0Tunw", "channel", None, None),
+        ("https://www.youtube.com/c/LinusTechTips", "channel", None, None),
+        ("https://www.youtube.com/user/LinusTechTips", "channel", None, None),
+        // An 11-character prefix of a longer token is not a video ID
+        ("https://www.youtube.com/watch?v=dQw4w9WgXcQextra", "video", None, None),
+        ("https://vimeo.com/76979871", "video", None, None),
+        ("ftp://youtube.com/watch?v=dQw4w9WgXcQ", "unknown", None, None),
+        ("http://127.0.0.1/watch?v=dQw4w9WgXcQ", "unknown", None, None),
+    ];
+
+    #[test]
+    fn test_validate_url_corpus() {
+        for (url, url_type, video_id, playlist_id) in URL_CORPUS {
+            let result = validate_url(url.to_string()).unwrap();
+            let actual_type = serde_json::to_value(&result.url_type).unwrap();
+            assert_eq!(actual_type, *url_type, "type of {}", url);
+            assert_eq!(result.video_id.as_deref(), *video_id, "video ID of {}", url);
+            assert_eq!(
+                result.playlist_id.as_deref(),
+                *playlist_id,
+                "playlist ID of {}",
+                url
+            );
+            assert_eq!(result.valid, *url_type != "unknown", "validity of {}", url);
+        }
+    }
+
+    #[test]
+    fn test_canonical_identity() {
+        let id = Some(("youtube".to_string(), "dQw4w9WgXcQ".to_string()));
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtu.be/dQw4w9WgXcQ?t=42",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            "https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RDAMVM",
+            "https://m.youtube.com/watch?feature=share&v=dQw4w9WgXcQ",
+            "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ",
+        ] {
+            assert_eq!(canonical_identity(url), id, "{}", url);
+        }
+        assert_eq!(canonical_identity("https://www.youtube.com/@channel"), None);
+        assert_eq!(canonical_identity("https://vimeo.com/76979871"), None);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod backup;
 pub mod binary;
 pub mod commands;
 pub mod db;
@@ -5,7 +6,10 @@ pub mod dep_deno;
 pub mod dep_download;
 pub mod dep_ffmpeg;
 pub mod dep_ytdlp;
+pub mod diagnose;
 pub mod download;
+pub mod library;
+pub mod maintenance;
 pub mod metadata;
 pub mod progress;
 pub mod security;
@@ -25,6 +25,119 @@ const DANGEROUS_TEMPLATE_PATTERNS: &[&str] = &[
     "%(#)", // might expand unpredictably
 ];
 
+/// Maximum number of user-supplied extra yt-dlp arguments
+const MAX_EXTRA_ARGS: usize = 32;
+
+/// yt-dlp options accepted in user-supplied extra args, and whether each takes a value.
+/// Anything else is rejected: yt-dlp also accepts unambiguous prefixes of long options
+/// (`--netrc-c` is `--netrc-cmd`) and user-defined `--alias`es, so dangerous options
+/// can't be reliably spotted by name. Options that run commands, touch arbitrary files
+/// or override what the app controls are deliberately absent.
+const ALLOWED_EXTRA_ARGS: &[(&str, bool)] = &[
+    // General and network
+    ("-i", false),
+    ("--ignore-errors", false),
+    ("--abort-on-error", false),
+    ("--no-abort-on-error", false),
+    ("-q", false),
+    ("--quiet", false),
+    ("--no-warnings", false),
+    ("-v", false),
+    ("--verbose", false),
+    ("--socket-timeout", true),
+    ("-4", false),
+    ("--force-ipv4", false),
+    ("-6", false),
+    ("--force-ipv6", false),
+    ("--impersonate", true),
+    ("--geo-bypass", false),
+    ("--no-geo-bypass", false),
+    ("--xff", true),
+    // Video selection
+    ("--no-playlist", false),
+    ("--yes-playlist", false),
+    ("-I", true),
+    ("--playlist-items", true),
+    ("--min-filesize", true),
+    ("--max-filesize", true),
+    ("--date", true),
+    ("--datebefore", true),
+    ("--dateafter", true),
+    ("--match-filters", true),
+    ("--age-limit", true),
+    ("--max-downloads", true),
+    ("--live-from-start", false),
+    ("--no-live-from-start", false),
+    // Download
+    ("-r", true),
+    ("--limit-rate", true),
+    ("--throttled-rate", true),
+    ("-R", true),
+    ("--retries", true),
+    ("--fragment-retries", true),
+    ("--file-access-retries", true),
+    ("--extractor-retries", true),
+    ("--retry-sleep", true),
+    ("-N", true),
+    ("--concurrent-fragments", true),
+    ("--http-chunk-size", true),
+    ("--buffer-size", true),
+    ("--hls-use-mpegts", false),
+    ("--no-part", false),
+    ("--no-mtime", false),
+    ("--no-continue", false),
+    ("--restrict-filenames", false),
+    ("--windows-filenames", false),
+    ("-w", false),
+    ("--no-overwrites", false),
+    ("--force-overwrites", false),
+    // Workarounds
+    ("--add-headers", true),
+    ("--referer", true),
+    ("--sleep-requests", true),
+    ("--sleep-interval", true),
+    ("--min-sleep-interval", true),
+    ("--max-sleep-interval", true),
+    ("--sleep-subtitles", true),
+    ("--extractor-args", true),
+    // Formats, subtitles, thumbnails
+    ("-S", true),
+    ("--format-sort", true),
+    ("--prefer-free-formats", false),
+    ("--check-formats", false),
+    ("--merge-output-format", true),
+    ("--write-subs", false),
+    ("--write-auto-subs", false),
+    ("--sub-langs", true),
+    ("--sub-format", true),
+    ("--write-thumbnail", false),
+    ("--write-description", false),
+    ("--write-info-json", false),
+    // Post-processing
+    ("--audio-quality", true),
+    ("-k", false),
+    ("--keep-video", false),
+    ("--no-keep-video", false),
+    ("--no-post-overwrites", false),
+    ("--embed-subs", false),
+    ("--embed-thumbnail", false),
+    ("--embed-metadata", false),
+    ("--embed-chapters", false),
+    ("--convert-subs", true),
+    ("--convert-thumbnails", true),
+    ("--split-chapters", false),
+    ("--sponsorblock-mark", true),
+    ("--sponsorblock-remove", true),
+];
+
+/// Audio formats accepted for `--audio-format`
+const VALID_AUDIO_FORMATS: &[&str] = &[
+    "best", "aac", "alac", "flac", "m4a", "mp3", "opus", "vorbis", "wav",
+];
+
+/// Containers accepted for `--remux-video`
+const VALID_REMUX_FORMATS: &[&str] = &["mp4", "mkv", "webm", "mov"];
+
 /// Sanitize and validate a URL for safe use with yt-dlp.
 ///
 /// This checks:
@@ -253,6 +366,130 @@ pub fn sanitize_cookie_browser(browser: &str) -> Result<String, AppError> {
     Ok(browser.to_string())
 }
 
+/// Validate user-supplied extra yt-dlp arguments.
+///
+/// Only allowlisted options are accepted, spelled out in full (also in `--opt=value`
+/// form). A bare value must follow an option that takes one, so nothing can be read as
+/// an extra URL. Rejects bundled short options, control characters and oversized lists.
+pub fn sanitize_extra_args(args: &[String]) -> Result<Vec<String>, AppError> {
+    if args.len() > MAX_EXTRA_ARGS {
+        return Err(AppError::Custom(format!(
+            "Too many extra arguments (maximum {})",
+            MAX_EXTRA_ARGS
+        )));
+    }
+
+    let mut sanitized = Vec::with_capacity(args.len());
+    let mut pending_value: Option<&str> = None;
+    for arg in args {
+        let arg = arg.trim();
+        if arg.is_empty() {
+            continue;
+        }
+        if arg.len() > 1024 || arg.chars().any(|c| c.is_control()) {
+            return Err(AppError::Custom(format!(
+                "Invalid extra argument: '{}'",
+                arg.chars().take(64).collect::<String>()
+            )));
+        }
+
+        // yt-dlp takes the next argument as the value whatever it looks like
+        if pending_value.take().is_some() {
+            sanitized.push(arg.to_string());
+            continue;
+        }
+
+        if !arg.starts_with('-') || arg == "-" || arg == "--" {
+            return Err(AppError::Custom(format!(
+                "Unexpected extra argument: '{}'",
+                arg
+            )));
+        }
+
+        // Bundled short options ("-qo file", "-Pdir") can't be checked without
+        // yt-dlp's option table, so short options must be passed one per argument.
+        let bundled = !arg.starts_with("--")
+            && arg.len() > 2
+            && arg.chars().nth(1).is_some_and(|c| c.is_ascii_alphabetic());
+        if bundled {
+            return Err(AppError::Custom(format!(
+                "Short options must be passed separately: '{}'",
+                arg
+            )));
+        }
+
+        let (option, inline_value) = match arg.split_once('=') {
+            Some((option, value)) => (option, Some(value)),
+            None => (arg, None),
+        };
+        let takes_value = ALLOWED_EXTRA_ARGS
+            .iter()
+            .find(|(name, _)| *name == option)
+            .map(|(_, takes_value)| *takes_value)
+            .ok_or_else(|| {
+                AppError::Custom(format!("Extra argument is not allowed: '{}'", option))
+            })?;
+        match (takes_value, inline_value) {
+            (false, Some(_)) => {
+                return Err(AppError::Custom(format!(
+                    "Option '{}' does not take a value",
+                    option
+                )));
+            }
+            (true, None) => pending_value = Some(option),
+            _ => {}
+        }
+
+        sanitized.push(arg.to_string());
+    }
+
+    if let Some(option) = pending_value {
+        return Err(AppError::Custom(format!(
+            "Missing value for extra argument '{}'",
+            option
+        )));
+    }
+
+    Ok(sanitized)
+}
+
+/// Validate post-processing options against the formats yt-dlp accepts.
+pub fn sanitize_post_processing(
+    options: &crate::ytdlp::types::PostProcessingOptions,
+) -> Result<(), AppError> {
+    if let Some(format) = &options.extract_audio_format {
+        if !VALID_AUDIO_FORMATS.contains(&format.as_str()) {
+            return Err(AppError::Custom(format!(
+                "Unsupported audio format: '{}'",
+                format
+            )));
+        }
+    }
+
+    if let Some(format) = &options.remux_format {
+        if !VALID_REMUX_FORMATS.contains(&format.as_str()) {
+            return Err(AppError::Custom(format!(
+                "Unsupported remux format: '{}'",
+                format
+            )));
+        }
+    }
+
+    if let Some(langs) = &options.sub_langs {
+        let valid = langs
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ',' | '-' | '_' | '.' | '*' | '!'));
+        if langs.is_empty() || langs.len() > 200 || !valid {
+            return Err(AppError::Custom(format!(
+                "Invalid subtitle languages: '{}'",
+                langs
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 /// Clamp max_concurrent to a safe range [1, MAX_CONCURRENT_LIMIT].
 pub fn clamp_max_concurrent(n: u32) -> u32 {
     n.clamp(1, MAX_CONCURRENT_LIMIT)
@@ -393,4 +630,81 @@ mod tests {
         assert_eq!(clamp_max_concurrent(100), MAX_CONCURRENT_LIMIT);
         assert_eq!(clamp_max_concurrent(u32::MAX), MAX_CONCURRENT_LIMIT);
     }
+
+    // === Extra args / post-processing tests ===
+
+    #[test]
+    fn test_extra_args_allowed() {
+        let args = vec![
+            "--limit-rate".to_string(),
+            "2M".to_string(),
+            " --sleep-interval=5 ".to_string(),
+            "--no-part".to_string(),
+            "--sponsorblock-remove".to_string(),
+            "-all".to_string(),
+        ];
+        assert_eq!(
+            sanitize_extra_args(&args).unwrap(),
+            vec![
+                "--limit-rate",
+                "2M",
+                "--sleep-interval=5",
+                "--no-part",
+                "--sponsorblock-remove",
+                "-all"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extra_args_blocked() {
+        for arg in [
+            "--exec",
+            "--exec=rm -rf ~",
+            "-o",
+            "-o/tmp/x",
+            "-qo",
+            "--config-location",
+            "--",
+            // Unique prefixes yt-dlp expands to dangerous options
+            "--netrc-c",
+            "--load-info",
+            "--batch",
+            "--print-to-f",
+            "--exe",
+            // Aliases can define options that expand to --exec
+            "--alias",
+            "--alias=x --exec",
+            // A bare argument would be read as another URL
+            "https://example.com/other",
+        ] {
+            assert!(sanitize_extra_args(&[arg.to_string()]).is_err(), "{}", arg);
+        }
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert!(sanitize_extra_args(&args(&["--netrc-c", "touch /tmp/pwned"])).is_err());
+        assert!(sanitize_extra_args(&args(&["--alias", "pwn", "--exec touch x"])).is_err());
+        assert!(sanitize_extra_args(&args(&["--limit-rate"])).is_err());
+        assert!(sanitize_extra_args(&args(&["--no-part=1"])).is_err());
+        assert!(sanitize_extra_args(&args(&["--no-part", "https://example.com"])).is_err());
+        assert!(sanitize_extra_args(&["--limit-rate\n--exec".to_string()]).is_err());
+        assert!(sanitize_extra_args(&vec!["-q".to_string(); MAX_EXTRA_ARGS + 1]).is_err());
+    }
+
+    #[test]
+    fn test_post_processing_validation() {
+        use crate::ytdlp::types::PostProcessingOptions;
+
+        let ok = PostProcessingOptions {
+            extract_audio_format: Some("mp3".to_string()),
+            sub_langs: Some("en,ko.*".to_string()),
+            ..Default::default()
+        };
+        assert!(sanitize_post_processing(&ok).is_ok());
+
+        let bad = PostProcessingOptions {
+            remux_format: Some("exe".to_string()),
+            ..Default::default()
+        };
+        assert!(sanitize_post_processing(&bad).is_err());
+    }
 }
@@ -5,6 +5,15 @@ use tauri_plugin_store::StoreExt;
 
 const STORE_FILE: &str = "settings.json";
 
+/// A retention day count or entry limit. 0 turns the rule off rather than
+/// deleting everything.
+fn retention_limit(value: serde_json::Value) -> Option<u32> {
+    value
+        .as_u64()
+        .filter(|&n| n > 0)
+        .map(|n| n.min(u32::MAX as u64) as u32)
+}
+
 /// Common parsing logic: extract AppSettings from a key-value getter function.
 /// `getter` takes a key name and returns an optional serde_json::Value.
 fn parse_settings(getter: impl Fn(&str) -> Option<serde_json::Value>) -> AppSettings {
@@ -69,6 +78,28 @@ fn parse_settings(getter: impl Fn(&str) -> Option<serde_json::Value>) -> AppSett
         .and_then(|v| v.as_bool())
         .unwrap_or(defaults.setup_completed);
 
+    let library_roots = getter("libraryRoots")
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or(defaults.library_roots);
+
+    let retention_completed_days = getter("retentionCompletedDays").and_then(retention_limit);
+
+    let retention_failed_days = getter("retentionFailedDays").and_then(retention_limit);
+
+    let history_max_entries = getter("historyMaxEntries").and_then(retention_limit);
+
+    let history_prune_missing = getter("historyPruneMissing")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(defaults.history_prune_missing);
+
+    let dedup_policy = getter("dedupPolicy")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or(defaults.dedup_policy);
+
+    let metadata_cache_ttl_hours = getter("metadataCacheTtlHours")
+        .and_then(|v| v.as_u64().map(|n| n.min(u32::MAX as u64) as u32))
+        .unwrap_or(defaults.metadata_cache_ttl_hours);
+
     AppSettings {
         download_path,
         default_quality,
@@ -85,6 +116,13 @@ fn parse_settings(getter: impl Fn(&str) -> Option<serde_json::Value>) -> AppSett
         minimize_to_tray,
         dep_mode,
         setup_completed,
+        library_roots,
+        retention_completed_days,
+        retention_failed_days,
+        history_max_entries,
+        history_prune_missing,
+        dedup_policy,
+        metadata_cache_ttl_hours,
     }
 }
 
@@ -188,6 +226,47 @@ pub fn update_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), Ap
             .map_err(|e| AppError::Custom(e.to_string()))?,
     );
 
+    store.set(
+        "libraryRoots",
+        serde_json::to_value(&settings.library_roots)
+            .map_err(|e| AppError::Custom(e.to_string()))?,
+    );
+
+    store.set(
+        "retentionCompletedDays",
+        serde_json::to_value(settings.retention_completed_days)
+            .map_err(|e| AppError::Custom(e.to_string()))?,
+    );
+
+    store.set(
+        "retentionFailedDays",
+        serde_json::to_value(settings.retention_failed_days)
+            .map_err(|e| AppError::Custom(e.to_string()))?,
+    );
+
+    store.set(
+        "historyMaxEntries",
+        serde_json::to_value(settings.history_max_entries)
+            .map_err(|e| AppError::Custom(e.to_string()))?,
+    );
+
+    store.set(
+        "historyPruneMissing",
+        serde_json::to_value(settings.history_prune_missing)
+            .map_err(|e| AppError::Custom(e.to_string()))?,
+    );
+
+    store.set(
+        "dedupPolicy",
+        serde_json::to_value(settings.dedup_policy).map_err(|e| AppError::Custom(e.to_string()))?,
+    );
+
+    store.set(
+        "metadataCacheTtlHours",
+        serde_json::to_value(settings.metadata_cache_ttl_hours)
+            .map_err(|e| AppError::Custom(e.to_string()))?,
+    );
+
     store.save().map_err(|e| AppError::Custom(e.to_string()))?;
 
     Ok(())
@@ -220,3 +299,21 @@ pub fn get_settings_from_path(app_data_dir: &std::path::Path) -> Result<AppSetti
 
     Ok(parse_settings(|key| value.get(key).cloned()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_zero_is_off() {
+        let settings = parse_settings(|key| match key {
+            "retentionCompletedDays" => Some(serde_json::json!(0)),
+            "retentionFailedDays" => Some(serde_json::json!(7)),
+            "historyMaxEntries" => Some(serde_json::json!(0)),
+            _ => None,
+        });
+        assert_eq!(settings.retention_completed_days, None);
+        assert_eq!(settings.retention_failed_days, Some(7));
+        assert_eq!(settings.history_max_entries, None);
+    }
+}
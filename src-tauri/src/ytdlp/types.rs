@@ -1,3 +1,4 @@
+use crate::modules::types::ErrorDetail;
 use serde::{Deserialize, Serialize};
 
 // === Video Metadata ===
@@ -15,6 +16,28 @@ pub struct VideoInfo {
     pub channel_url: String,
     pub formats: Vec<FormatInfo>,
     pub filesize_approx: Option<u64>,
+    /// yt-dlp extractor key (e.g. "Youtube"); pass it back in DownloadRequest for dedup
+    pub extractor_key: Option<String>,
+    pub description: Option<String>,
+    pub chapters: Vec<VideoChapter>,
+    pub view_count: Option<u64>,
+    pub like_count: Option<u64>,
+    /// Minimum viewer age (0 when unrestricted)
+    pub age_limit: Option<u32>,
+    pub tags: Vec<String>,
+    /// "public", "unlisted", "private", "needs_auth", "subscriber_only" or "premium_only"
+    pub availability: Option<String>,
+    /// "not_live", "is_live", "is_upcoming", "was_live" or "post_live"
+    pub live_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoChapter {
+    pub title: String,
+    /// Seconds from the start of the video
+    pub start_time: f64,
+    pub end_time: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -25,10 +48,26 @@ pub struct FormatInfo {
     pub resolution: Option<String>,
     pub quality_label: Option<String>,
     pub filesize: Option<u64>,
+    /// Estimated size when the exact size is unknown (e.g. from bitrate and duration)
+    pub filesize_approx: Option<u64>,
     pub vcodec: Option<String>,
     pub acodec: Option<String>,
     pub has_video: bool,
     pub has_audio: bool,
+    pub fps: Option<f64>,
+    /// Total, video and audio bitrates in KBit/s
+    pub tbr: Option<f64>,
+    pub vbr: Option<f64>,
+    pub abr: Option<f64>,
+    /// "SDR", "HDR10", "HDR10+", "HDR12", "HLG" or "DV"
+    pub dynamic_range: Option<String>,
+    /// Audio language code (e.g. "en", "ja")
+    pub language: Option<String>,
+    pub audio_channels: Option<u32>,
+    /// Download protocol as reported by yt-dlp ("https", "m3u8_native", "http_dash_segments", ...)
+    pub protocol: Option<String>,
+    /// DRM-protected formats can't be downloaded; yt-dlp's "maybe" counts as protected
+    pub has_drm: bool,
 }
 
 // === Playlist / Channel ===
@@ -54,6 +93,121 @@ pub struct PlaylistEntry {
     pub thumbnail: Option<String>,
 }
 
+/// Result for one URL of a batch metadata fetch
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchMetadataEvent {
+    pub batch_id: String,
+    /// Positions in the submitted list (several when the same video was pasted twice)
+    pub indexes: Vec<u32>,
+    pub url: String,
+    pub info: Option<VideoInfo>,
+    pub error: Option<String>,
+    pub error_detail: Option<ErrorDetail>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchMetadataSummary {
+    pub batch_id: String,
+    pub requested: u32,
+    /// Distinct videos after deduplication
+    pub unique: u32,
+    pub succeeded: u32,
+    pub failed: u32,
+    pub cancelled: bool,
+}
+
+/// A batch of entries from a streamed playlist fetch
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistEntriesEvent {
+    pub fetch_id: String,
+    pub entries: Vec<PlaylistEntry>,
+    /// Entries received so far, including this batch
+    pub received: u64,
+}
+
+/// Result of a streamed playlist fetch; the entries themselves arrive as events
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistStreamSummary {
+    pub fetch_id: String,
+    pub playlist_id: String,
+    pub title: String,
+    pub url: String,
+    /// Total reported by yt-dlp, or the received count once a fetch completes;
+    /// None when unknown (e.g. a cancelled or incomplete fetch of a lazy playlist)
+    pub video_count: Option<u64>,
+    pub channel_name: Option<String>,
+    pub received: u64,
+    pub cancelled: bool,
+    /// yt-dlp failed after some entries were received
+    pub incomplete: bool,
+}
+
+// === Channels ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ChannelTab {
+    Videos,
+    Shorts,
+    /// The "Live" tab (current, past and upcoming streams)
+    Streams,
+    Playlists,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelIdentity {
+    pub channel_id: Option<String>,
+    pub name: Option<String>,
+    /// "@handle", when the channel has one
+    pub handle: Option<String>,
+    pub url: String,
+    pub avatar: Option<String>,
+    pub subscriber_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelTabs {
+    pub channel: ChannelIdentity,
+    pub tabs: Vec<ChannelTab>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelTabRequest {
+    /// Any URL of the channel; its tab part is replaced by `tab`
+    pub url: String,
+    pub tab: ChannelTab,
+    /// Only uploads on or after this date (YYYYMMDD or relative, e.g. "today-1month")
+    #[serde(default)]
+    #[specta(optional)]
+    pub date_after: Option<String>,
+    /// Only uploads on or before this date
+    #[serde(default)]
+    #[specta(optional)]
+    pub date_before: Option<String>,
+    /// At most this many entries, newest first
+    #[serde(default)]
+    #[specta(optional)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelTabResult {
+    pub channel: ChannelIdentity,
+    pub tab: ChannelTab,
+    /// The tab URL that was fetched
+    pub url: String,
+    /// Videos, or playlists for the Playlists tab (`video_id` then holds the playlist ID)
+    pub entries: Vec<PlaylistEntry>,
+}
+
 // === Quick Metadata (oEmbed) ===
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -75,6 +229,16 @@ pub struct UrlValidation {
     pub url_type: UrlType,
     pub normalized_url: Option<String>,
     pub video_id: Option<String>,
+    /// YouTube playlist ID, also set for a video opened from a playlist (watch?v=X&list=Y);
+    /// for other sites the collection ID when yt-dlp classified the URL
+    pub playlist_id: Option<String>,
+    /// Short extractor name ("youtube", "vimeo", ...); from the host unless yt-dlp was
+    /// asked, then its lowercased extractor key ("bandcampalbum"), as in canonical identities
+    pub extractor: Option<String>,
+    /// Playlist, channel, album or other multi-item page
+    pub is_collection: bool,
+    /// The extractor's ID for the item or collection, when known
+    pub canonical_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -101,6 +265,98 @@ pub struct DownloadRequest {
     pub quality_label: String,
     pub output_dir: Option<String>,
     pub cookie_browser: Option<String>,
+    /// Expected duration in seconds (from VideoInfo), used for post-download verification
+    #[serde(default)]
+    #[specta(optional)]
+    pub duration: Option<u64>,
+    /// Uploader/channel name (from VideoInfo), kept for history search
+    #[serde(default)]
+    #[specta(optional)]
+    pub channel: Option<String>,
+    #[serde(default)]
+    #[specta(optional)]
+    pub channel_url: Option<String>,
+    #[serde(default)]
+    #[specta(optional)]
+    pub thumbnail: Option<String>,
+    /// Upload date as reported by yt-dlp (YYYYMMDD)
+    #[serde(default)]
+    #[specta(optional)]
+    pub upload_date: Option<String>,
+    /// Source playlist, when the video was enqueued from one
+    #[serde(default)]
+    #[specta(optional)]
+    pub playlist_id: Option<String>,
+    #[serde(default)]
+    #[specta(optional)]
+    pub playlist_title: Option<String>,
+    #[serde(default)]
+    #[specta(optional)]
+    pub post_processing: Option<PostProcessingOptions>,
+    /// Additional yt-dlp arguments, validated by `security::sanitize_extra_args`
+    #[serde(default)]
+    #[specta(optional)]
+    pub extra_args: Option<Vec<String>>,
+    /// yt-dlp `extractor_key` from VideoInfo; with `video_id` it forms the canonical identity
+    #[serde(default)]
+    #[specta(optional)]
+    pub extractor_key: Option<String>,
+    /// Enqueue even if the dedup policy would skip this video
+    #[serde(default)]
+    #[specta(optional)]
+    pub allow_duplicate: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PostProcessingOptions {
+    pub embed_metadata: bool,
+    pub embed_thumbnail: bool,
+    pub embed_subtitles: bool,
+    /// Subtitle languages for `--sub-langs` (e.g. "en,ko"); only used with embed_subtitles
+    pub sub_langs: Option<String>,
+    /// Extract audio to this format (`-x --audio-format`)
+    pub extract_audio_format: Option<String>,
+    /// Remux the video into this container (`--remux-video`)
+    pub remux_format: Option<String>,
+}
+
+/// Snapshot of everything that determines how a queued download runs.
+/// Stored with each download row when it is enqueued, so later settings changes
+/// don't affect tasks that are already queued, retried or resumed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DownloadOptions {
+    pub format_id: String,
+    /// Full yt-dlp output template (directory joined with the filename template)
+    pub output_template: String,
+    pub cookie_browser: Option<String>,
+    pub post_processing: PostProcessingOptions,
+    pub extra_args: Vec<String>,
+    /// Info JSON saved by the metadata fetch; passed to `--load-info-json` while fresh
+    pub info_json: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchItemStatus {
+    Accepted,
+    /// Accepted, but the video is already queued or in history (dedup policy "warn")
+    AcceptedDuplicate,
+    /// Already queued, repeated earlier in the batch or in history (dedup policy "skip")
+    Duplicate,
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEnqueueResult {
+    /// Position of the request in the submitted batch
+    pub index: u32,
+    pub video_url: String,
+    pub status: BatchItemStatus,
+    pub task_id: Option<u64>,
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -110,6 +366,8 @@ pub enum DownloadStatus {
     Downloading,
     Paused,
     Completed,
+    /// Download finished but post-download verification found problems
+    NeedsAttention,
     Failed,
     Cancelled,
 }
@@ -121,6 +379,7 @@ impl std::fmt::Display for DownloadStatus {
             DownloadStatus::Downloading => write!(f, "downloading"),
             DownloadStatus::Paused => write!(f, "paused"),
             DownloadStatus::Completed => write!(f, "completed"),
+            DownloadStatus::NeedsAttention => write!(f, "needs_attention"),
             DownloadStatus::Failed => write!(f, "failed"),
             DownloadStatus::Cancelled => write!(f, "cancelled"),
         }
@@ -134,6 +393,7 @@ impl DownloadStatus {
             "downloading" => DownloadStatus::Downloading,
             "paused" => DownloadStatus::Paused,
             "completed" => DownloadStatus::Completed,
+            "needs_attention" => DownloadStatus::NeedsAttention,
             "failed" => DownloadStatus::Failed,
             "cancelled" => DownloadStatus::Cancelled,
             unknown => {
@@ -164,6 +424,128 @@ pub struct DownloadTaskInfo {
     pub error_message: Option<String>,
     pub created_at: i64,
     pub completed_at: Option<i64>,
+    pub duration: Option<u64>,
+    pub group_id: Option<u64>,
+    pub channel: Option<String>,
+    pub channel_url: Option<String>,
+    pub thumbnail: Option<String>,
+    pub upload_date: Option<String>,
+    pub playlist_id: Option<String>,
+    pub playlist_title: Option<String>,
+    /// When the current (or last) attempt was claimed by the dispatcher
+    pub started_at: Option<i64>,
+    /// Canonical identity (lowercased yt-dlp extractor key and video ID), if known
+    pub canonical_extractor: Option<String>,
+    pub canonical_id: Option<String>,
+    /// Code and parameters behind `error_message`, for failures yt-dlp gave a known cause
+    pub error_detail: Option<ErrorDetail>,
+    /// Options captured at enqueue time; None for rows created before options were stored
+    pub options: Option<DownloadOptions>,
+}
+
+// === Queue Import / Export ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum QueueFileFormat {
+    Json,
+    Csv,
+    /// Plain text, one URL per line (yt-dlp batch-file style)
+    UrlList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedImportLine {
+    /// Line number in the file (entry position for JSON imports), 1-based
+    pub line: u32,
+    pub content: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueImportReport {
+    pub accepted: u32,
+    pub rejected: Vec<RejectedImportLine>,
+}
+
+// === Download Groups ===
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub enum GroupType {
+    #[serde(rename = "playlist")]
+    Playlist,
+    #[serde(rename = "channel")]
+    Channel,
+}
+
+impl std::fmt::Display for GroupType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupType::Playlist => write!(f, "playlist"),
+            GroupType::Channel => write!(f, "channel"),
+        }
+    }
+}
+
+impl GroupType {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "channel" => GroupType::Channel,
+            _ => GroupType::Playlist,
+        }
+    }
+}
+
+/// Options shared by every item of a group, recorded for display and re-use.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupOptions {
+    pub format_id: String,
+    pub quality_label: String,
+    pub output_dir: Option<String>,
+    pub cookie_browser: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NewDownloadGroup {
+    pub source_url: String,
+    pub group_type: GroupType,
+    pub title: String,
+    pub options: Option<GroupOptions>,
+}
+
+/// A download group with aggregate counts and progress over its items.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadGroup {
+    pub id: u64,
+    pub source_url: String,
+    pub group_type: GroupType,
+    pub title: String,
+    pub options: Option<GroupOptions>,
+    pub created_at: i64,
+    pub total_count: u64,
+    pub pending_count: u64,
+    pub active_count: u64,
+    pub paused_count: u64,
+    /// Includes items that completed with verification warnings
+    pub completed_count: u64,
+    pub failed_count: u64,
+    pub cancelled_count: u64,
+    /// Average progress across all items (finished items count as 100)
+    pub progress: f32,
+}
+
+/// Latest progress of a running download, held in memory by the DownloadManager
+/// and flushed to the database in batches.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LiveProgress {
+    pub progress: f32,
+    pub speed: Option<String>,
+    pub eta: Option<String>,
 }
 
 // Global download event for app-wide event emission
@@ -178,6 +560,8 @@ pub struct GlobalDownloadEvent {
     pub file_path: Option<String>,
     pub file_size: Option<u64>,
     pub message: Option<String>,
+    /// Set on "error" events that have a localizable cause
+    pub error: Option<ErrorDetail>,
 }
 
 // === Install ===
@@ -207,6 +591,69 @@ pub struct HistoryItem {
     pub file_path: String,
     pub file_size: Option<u64>,
     pub downloaded_at: i64,
+    pub duration: Option<u64>,
+    pub verify_status: VerifyStatus,
+    pub verify_issues: Option<String>,
+    pub verified_at: Option<i64>,
+    /// SHA-256 of the file contents (hex), if computed
+    pub content_hash: Option<String>,
+    pub channel: Option<String>,
+    /// Site the download came from (e.g. "youtube", "vimeo")
+    pub extractor: Option<String>,
+    pub channel_url: Option<String>,
+    pub thumbnail: Option<String>,
+    /// Upload date as reported by yt-dlp (YYYYMMDD)
+    pub upload_date: Option<String>,
+    pub playlist_id: Option<String>,
+    pub playlist_title: Option<String>,
+    /// Wall-clock seconds the download took, for throughput statistics
+    pub download_secs: Option<u64>,
+    /// Canonical identity (lowercased yt-dlp extractor key and video ID), if known
+    pub canonical_extractor: Option<String>,
+    pub canonical_id: Option<String>,
+    /// Streams the downloaded format carries, for re-verification (None for old rows)
+    pub expected_streams: Option<ExpectedStreams>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum HistorySort {
+    Date,
+    Size,
+    Title,
+}
+
+/// File presence as last recorded by verification (`verify_status`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryFileFilter {
+    Present,
+    Missing,
+}
+
+/// Filters for the history query. All fields are optional and combined with AND.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryFilter {
+    /// Full-text search over title, channel, video ID, URL and file path
+    pub search: Option<String>,
+    /// Unix timestamps (seconds), inclusive
+    pub downloaded_after: Option<i64>,
+    pub downloaded_before: Option<i64>,
+    /// File size range in bytes, inclusive
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub format: Option<String>,
+    pub quality_label: Option<String>,
+    pub extractor: Option<String>,
+    /// Exact channel name
+    pub channel: Option<String>,
+    pub playlist_id: Option<String>,
+    pub file_status: Option<HistoryFileFilter>,
+    /// Defaults to date
+    pub sort_by: Option<HistorySort>,
+    /// Defaults to descending
+    pub sort_ascending: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
@@ -230,6 +677,7 @@ pub struct QueueResult {
     pub active_count: u64,
     pub pending_count: u64,
     pub completed_count: u64,
+    pub needs_attention_count: u64,
     pub failed_count: u64,
     pub cancelled_count: u64,
 }
@@ -242,11 +690,275 @@ pub struct QueueSummary {
     pub active_count: u64,
     pub pending_count: u64,
     pub completed_count: u64,
+    pub needs_attention_count: u64,
     pub total_count: u64,
+    /// Groups with their aggregate state; only filled when the summary is requested by group
+    pub groups: Vec<DownloadGroup>,
+}
+
+// === Verification ===
+
+/// Stream types a downloaded file should contain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedStreams {
+    pub video: bool,
+    pub audio: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum VerifyStatus {
+    /// Not probed yet, or ffprobe was unavailable
+    Unverified,
+    Ok,
+    NeedsAttention,
+    /// File no longer exists at the recorded path
+    Missing,
+}
+
+impl std::fmt::Display for VerifyStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyStatus::Unverified => write!(f, "unverified"),
+            VerifyStatus::Ok => write!(f, "ok"),
+            VerifyStatus::NeedsAttention => write!(f, "needs_attention"),
+            VerifyStatus::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+impl VerifyStatus {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "ok" => VerifyStatus::Ok,
+            "needs_attention" => VerifyStatus::NeedsAttention,
+            "missing" => VerifyStatus::Missing,
+            _ => VerifyStatus::Unverified,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationResult {
+    pub status: VerifyStatus,
+    /// Human-readable descriptions of every problem found (empty when OK)
+    pub issues: Vec<String>,
+    pub probed_duration: Option<f64>,
+    pub has_video: bool,
+    pub has_audio: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryVerifySummary {
+    pub checked: u64,
+    pub ok: u64,
+    pub needs_attention: u64,
+    pub missing: u64,
+    pub unverified: u64,
+}
+
+/// How a missing history file was found again
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RelinkMatch {
+    /// The file name contains the video ID (e.g. yt-dlp's `[%(id)s]`)
+    VideoId,
+    /// Same size and SHA-256 as the recorded content hash
+    ContentHash,
+    /// The source URL is embedded in the container tags (`--embed-metadata`)
+    EmbeddedMetadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkedFile {
+    pub history_id: u64,
+    pub old_path: String,
+    pub new_path: String,
+    pub matched_by: RelinkMatch,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryReconcileSummary {
+    pub checked: u64,
+    /// Rows whose file was not at the recorded path
+    pub missing: u64,
+    pub relinked: Vec<RelinkedFile>,
+    /// Missing rows left marked missing: not relinked, or already missing and not
+    /// searched for again by a background run
+    pub still_missing: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveLibraryResult {
+    pub moved_files: u64,
+    pub updated_entries: u64,
+    /// History rows under the source folder whose file was already gone
+    pub skipped: u64,
+    pub errors: Vec<String>,
+}
+
+/// Where a scanned file's video ID came from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportIdSource {
+    /// `<name>.info.json` written by `--write-info-json`
+    InfoJson,
+    /// A `[id]` suffix in the file name
+    FileName,
+    /// Source URL in the container tags (`--embed-metadata`)
+    EmbeddedTags,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportEntryStatus {
+    /// Will be (or was) added to history
+    New,
+    /// The file or its video ID is already in history
+    AlreadyInHistory,
+    /// Another scanned file has the same video ID
+    DuplicateInScan,
+    /// The ID was found but not the site it came from, so there is no URL to record
+    NoSourceUrl,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImportEntry {
+    pub file_path: String,
+    pub file_size: u64,
+    pub video_id: String,
+    pub video_url: String,
+    pub title: String,
+    pub channel: Option<String>,
+    pub extractor: Option<String>,
+    pub detected_by: ImportIdSource,
+    pub status: ImportEntryStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImportReport {
+    pub dry_run: bool,
+    pub scanned_files: u64,
+    pub entries: Vec<LibraryImportEntry>,
+    /// Media files whose video ID could not be determined
+    pub unidentified: Vec<String>,
+    /// Rows added to history (always 0 for a dry run)
+    pub imported: u64,
+}
+
+// === Statistics ===
+
+/// Bytes and file count for one calendar period (`2024-05-31`, `2024-05` or, for weeks,
+/// the Monday they start on)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsPeriod {
+    pub period: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCount {
+    pub status: DownloadStatus,
+    pub count: u64,
+}
+
+/// Outcome counts for one site. Based on the queue, so rows removed with
+/// "clear completed" are not included.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractorStats {
+    pub extractor: String,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub cancelled: u64,
+    /// succeeded / (succeeded + failed), or None when neither happened yet
+    pub success_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelStats {
+    pub channel: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadStatistics {
+    /// Last 30 days, oldest first; days without downloads are omitted
+    pub bytes_per_day: Vec<StatsPeriod>,
+    /// Last 12 weeks (Monday-based), oldest first
+    pub bytes_per_week: Vec<StatsPeriod>,
+    /// Last 12 months, oldest first
+    pub bytes_per_month: Vec<StatsPeriod>,
+    pub status_counts: Vec<StatusCount>,
+    pub extractors: Vec<ExtractorStats>,
+    pub top_channels: Vec<ChannelStats>,
+    /// Bytes per second over downloads with recorded timing
+    pub average_throughput: Option<f64>,
+    /// Total size of history files not marked missing
+    pub library_bytes: u64,
+    pub library_files: u64,
+}
+
+// === Backup ===
+
+/// `manifest.json` stored at the root of a backup archive
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    /// Archive layout version, bumped if entries change incompatibly
+    pub format_version: u32,
+    pub app_version: String,
+    /// `ytdlp.db` schema version of the snapshot
+    pub schema_version: u32,
+    pub created_at: i64,
+    /// Archive entries besides the manifest
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub manifest: BackupManifest,
+    /// One line per restored part, e.g. "ytdlp.db: restored"
+    pub results: Vec<String>,
 }
 
 // === Duplicate Check ===
 
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateFileGroup {
+    pub content_hash: String,
+    pub file_size: Option<u64>,
+    /// Oldest first; the first item is the suggested one to keep
+    pub items: Vec<HistoryItem>,
+    /// Bytes that would be freed by removing every file except the first
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveDuplicatesResult {
+    pub removed_entries: u32,
+    pub deleted_files: u32,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct DuplicateCheckResult {
@@ -278,6 +990,87 @@ pub struct AppSettings {
     pub dep_mode: String,
     /// Whether the initial setup wizard has been completed
     pub setup_completed: bool,
+    /// Extra folders searched when relinking moved files (the download path is always searched)
+    #[serde(default)]
+    pub library_roots: Vec<String>,
+    /// Remove completed queue rows this many days after they finish (None keeps them)
+    #[serde(default)]
+    pub retention_completed_days: Option<u32>,
+    /// Remove failed and cancelled queue rows after this many days (None keeps them)
+    #[serde(default)]
+    pub retention_failed_days: Option<u32>,
+    /// Keep at most this many history entries, newest first (None is unlimited)
+    #[serde(default)]
+    pub history_max_entries: Option<u32>,
+    /// Remove history entries whose files were found missing by reconciliation
+    #[serde(default)]
+    pub history_prune_missing: bool,
+    /// What enqueueing does with a video that is already downloaded or queued
+    #[serde(default)]
+    pub dedup_policy: DedupPolicy,
+    /// How long fetched video metadata is reused before yt-dlp is asked again (0 disables the cache)
+    #[serde(default = "default_metadata_cache_ttl_hours")]
+    pub metadata_cache_ttl_hours: u32,
+}
+
+fn default_metadata_cache_ttl_hours() -> u32 {
+    24
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum DedupPolicy {
+    /// Enqueue anyway
+    Allow,
+    /// Enqueue, but report the duplicate
+    #[default]
+    Warn,
+    /// Refuse to enqueue unless the request sets `allowDuplicate`
+    Skip,
+}
+
+impl DownloadRequest {
+    /// Canonical `(extractor, id)`: the yt-dlp extractor key and video ID when the caller
+    /// supplied both, otherwise whatever the URL alone identifies.
+    pub fn canonical_identity(&self) -> Option<(String, String)> {
+        match &self.extractor_key {
+            Some(key) if !key.is_empty() && !self.video_id.is_empty() => Some((
+                crate::ytdlp::metadata::canonical_extractor(key),
+                self.video_id.clone(),
+            )),
+            _ => crate::ytdlp::metadata::canonical_identity(&self.video_url),
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            completed_days: self.retention_completed_days,
+            failed_days: self.retention_failed_days,
+            history_max_entries: self.history_max_entries,
+            prune_missing_history: self.history_prune_missing,
+        }
+    }
+}
+
+/// Retention rules for finished queue rows and history, taken from settings.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    pub completed_days: Option<u32>,
+    pub failed_days: Option<u32>,
+    pub history_max_entries: Option<u32>,
+    pub prune_missing_history: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionResult {
+    pub queue_rows_removed: u64,
+    pub history_removed: u64,
+    pub logs_removed: u64,
+    /// Expired metadata cache entries
+    pub metadata_removed: u64,
 }
 
 impl Default for AppSettings {
@@ -298,6 +1091,13 @@ impl Default for AppSettings {
             minimize_to_tray: None,
             dep_mode: "external".to_string(),
             setup_completed: false,
+            library_roots: Vec::new(),
+            retention_completed_days: None,
+            retention_failed_days: None,
+            history_max_entries: None,
+            history_prune_missing: false,
+            dedup_policy: DedupPolicy::Warn,
+            metadata_cache_ttl_hours: default_metadata_cache_ttl_hours(),
         }
     }
 }
@@ -72,17 +72,40 @@ async selectDownloadDirectory() : Promise<Result<string | null, AppError>> {
 async getAvailableBrowsers() : Promise<string[]> {
     return await TAURI_INVOKE("get_available_browsers");
 },
-async getDownloadHistory(page: number, pageSize: number, search: string | null) : Promise<Result<HistoryResult, AppError>> {
+async getDownloadHistory(page: number, pageSize: number, search: string | null, filter: HistoryFilter | null) : Promise<Result<HistoryResult, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_download_history", { page, pageSize, search }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_download_history", { page, pageSize, search, filter }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async checkDuplicate(videoId: string) : Promise<Result<DuplicateCheckResult, AppError>> {
+/**
+ * Sites present in history, for populating the site filter.
+ */
+async getHistoryExtractors() : Promise<Result<string[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_extractors") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Download and library statistics: bytes per period, outcomes per site, top channels,
+ * throughput and library size. `top_channels` defaults to 10.
+ */
+async getDownloadStatistics(topChannels: number | null) : Promise<Result<DownloadStatistics, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_download_statistics", { topChannels }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async checkDuplicate(videoId: string, videoUrl: string | null) : Promise<Result<DuplicateCheckResult, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("check_duplicate", { videoId }) };
+    return { status: "ok", data: await TAURI_INVOKE("check_duplicate", { videoId, videoUrl }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -112,9 +135,77 @@ async getDownloadQueuePaginated(page: number, pageSize: number, statusFilter: st
     else return { status: "error", error: e  as any };
 }
 },
-async getQueueSummary() : Promise<Result<QueueSummary, AppError>> {
+async getQueueSummary(byGroup: boolean | null) : Promise<Result<QueueSummary, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_queue_summary") };
+    return { status: "ok", data: await TAURI_INVOKE("get_queue_summary", { byGroup }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Re-run ffprobe verification for every history item and store the results.
+ */
+async reverifyLibrary() : Promise<Result<LibraryVerifySummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reverify_library") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Group history items whose files have identical contents (hashing any files not yet hashed).
+ */
+async findDuplicateFiles() : Promise<Result<DuplicateFileGroup[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_duplicate_files") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove duplicate history entries, optionally deleting their files from disk.
+ */
+async removeDuplicateFiles(historyIds: number[], deleteFiles: boolean) : Promise<Result<RemoveDuplicatesResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_duplicate_files", { historyIds, deleteFiles }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check every history file on disk, relinking moved files found in the library roots
+ * and marking the rest missing.
+ */
+async reconcileLibrary() : Promise<Result<LibraryReconcileSummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reconcile_library") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Move all history files under `from_root` to `to_root`, keeping their relative layout.
+ */
+async moveLibrary(fromRoot: string, toRoot: string) : Promise<Result<MoveLibraryResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("move_library", { fromRoot, toRoot }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Scan a folder of earlier yt-dlp downloads and add the identified files to history.
+ * With `dry_run` nothing is written and only the report is returned.
+ */
+async importLibraryFolder(folder: string, dryRun: boolean) : Promise<Result<LibraryImportReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_library_folder", { folder, dryRun }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -132,11 +223,53 @@ async validateUrl(url: string) : Promise<Result<UrlValidation, AppError>> {
 }
 },
 /**
- * Fetch video metadata using yt-dlp --dump-json
+ * Classify a URL more precisely than `validate_url` by asking yt-dlp which extractor
+ * handles it and whether it is a single item or a collection (Vimeo showcase,
+ * SoundCloud set, Bandcamp album, ...). Only one entry is extracted. Recognised
+ * YouTube URLs are answered without running yt-dlp. Results are cached for an hour.
+ */
+async classifyUrl(url: string) : Promise<Result<UrlValidation, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("classify_url", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetch video metadata, served from the metadata cache when a fresh entry exists.
+ * `force_refresh` always runs yt-dlp (and updates the cache).
+ */
+async fetchVideoInfo(url: string, forceRefresh: boolean | null) : Promise<Result<VideoInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_video_info", { url, forceRefresh }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetch metadata for many URLs at once (e.g. a pasted list). URLs that point at the
+ * same video are fetched once. At most `concurrency` yt-dlp processes run at a time,
+ * cached results are used where fresh, and each result or per-URL error is emitted as
+ * a `batch-metadata` event as soon as it is ready. `batch_id` is chosen by the caller
+ * so the batch can be stopped with `cancel_metadata_batch`, which kills its running
+ * yt-dlp processes.
  */
-async fetchVideoInfo(url: string) : Promise<Result<VideoInfo, AppError>> {
+async fetchVideoInfoBatch(urls: string[], batchId: string, concurrency: number | null) : Promise<Result<BatchMetadataSummary, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("fetch_video_info", { url }) };
+    return { status: "ok", data: await TAURI_INVOKE("fetch_video_info_batch", { urls, batchId, concurrency }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop a batch metadata fetch. Returns false if no batch with this ID is running.
+ */
+async cancelMetadataBatch(batchId: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_metadata_batch", { batchId }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -153,6 +286,57 @@ async fetchPlaylistInfo(url: string, page: number, pageSize: number) : Promise<R
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Fetch a whole playlist or channel with `--flat-playlist`, streaming entries to the
+ * frontend as `playlist-entries` events while yt-dlp is still running. `fetch_id` is
+ * chosen by the caller so the fetch can be cancelled with `cancel_playlist_fetch`.
+ * Returns a summary once yt-dlp exits or the fetch is cancelled; entries received
+ * before a cancel or a late failure are kept.
+ */
+async streamPlaylistInfo(url: string, fetchId: string) : Promise<Result<PlaylistStreamSummary, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stream_playlist_info", { url, fetchId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop a streamed playlist fetch. Returns false if no fetch with this ID is running.
+ */
+async cancelPlaylistFetch(fetchId: string) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_playlist_fetch", { fetchId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the tabs a YouTube channel offers, with the channel's identity.
+ * The channel page itself lists its Videos, Shorts and Live tabs; Playlists is
+ * always offered since yt-dlp doesn't report it there.
+ */
+async listChannelTabs(url: string) : Promise<Result<ChannelTabs, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_channel_tabs", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fetch the entries of one channel tab, optionally limited to an upload date range
+ * and a maximum number of entries (newest first).
+ */
+async fetchChannelTab(request: ChannelTabRequest) : Promise<Result<ChannelTabResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fetch_channel_tab", { request }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Fetch quick metadata via YouTube oEmbed API (~200ms vs ~12s for yt-dlp)
  */
@@ -164,6 +348,18 @@ async fetchQuickMetadata(url: string) : Promise<Result<QuickMetadata, AppError>>
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Delete cached video metadata and saved info JSON. With `expired_only`, only entries
+ * older than the configured TTL are removed. Returns the number of cache entries removed.
+ */
+async clearMetadataCache(expiredOnly: boolean | null) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_metadata_cache", { expiredOnly }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async startDownload(request: DownloadRequest) : Promise<Result<number, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("start_download", { request }) };
@@ -180,6 +376,22 @@ async addToQueue(request: DownloadRequest) : Promise<Result<number, AppError>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Enqueue many downloads at once (e.g. a whole playlist).
+ * Every request is validated individually; valid, non-duplicate ones are inserted in a
+ * single transaction and the dispatcher is triggered once for the whole batch.
+ * When `group` is given, the accepted items are recorded under a new download group.
+ * Items already queued or repeated in the batch are always rejected; items already in
+ * history follow the dedup policy. `allowDuplicate` on a request bypasses both checks.
+ */
+async addToQueueBatch(requests: DownloadRequest[], group: NewDownloadGroup | null) : Promise<Result<BatchEnqueueResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_to_queue_batch", { requests, group }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async cancelDownload(taskId: number) : Promise<Result<null, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("cancel_download", { taskId }) };
@@ -212,6 +424,101 @@ async resumeDownload(taskId: number) : Promise<Result<null, AppError>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getDownloadGroups() : Promise<Result<DownloadGroup[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_download_groups") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDownloadGroup(groupId: number) : Promise<Result<DownloadGroup, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_download_group", { groupId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancel every unfinished item in the group. Returns the number of items cancelled.
+ */
+async cancelGroup(groupId: number) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_group", { groupId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pause the group: pending items are held back and running downloads are stopped.
+ * Partially downloaded files are kept so yt-dlp can continue them on resume.
+ */
+async pauseGroup(groupId: number) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pause_group", { groupId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resumeGroup(groupId: number) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_group", { groupId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Requeue every failed item in the group. Returns the number of items requeued.
+ */
+async retryFailedInGroup(groupId: number) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("retry_failed_in_group", { groupId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete the group and all of its queue items, stopping any that are still running.
+ * Download history and files on disk are not affected.
+ */
+async deleteGroup(groupId: number) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_group", { groupId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export the whole queue (all statuses) to a JSON, CSV or plain URL list file.
+ * Returns the number of exported items.
+ */
+async exportQueue(path: string, format: QueueFileFormat) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_queue", { path, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import downloads from a JSON, CSV or plain URL list file. The format is detected from
+ * the file extension (or content) unless given. Valid entries are enqueued through the
+ * batch path; everything else is reported back with its line number and reason.
+ */
+async importQueue(path: string, format: QueueFileFormat | null) : Promise<Result<QueueImportReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_queue", { path, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setMinimizeToTray(minimize: boolean, remember: boolean) : Promise<Result<null, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_minimize_to_tray", { minimize, remember }) };
@@ -296,6 +603,40 @@ async resetAllData() : Promise<Result<string[], AppError>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Write a backup archive of the app state (database, settings, dependency cache and
+ * optionally logs) to `path`.
+ */
+async createBackup(path: string, includeLogs: boolean) : Promise<Result<BackupManifest, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_backup", { path, includeLogs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restore app state from a backup archive, pausing the queue while the databases are swapped.
+ */
+async restoreBackup(path: string) : Promise<Result<RestoreReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_backup", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Apply the retention settings now instead of waiting for the next periodic run.
+ */
+async runMaintenance() : Promise<Result<RetentionResult, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_maintenance") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getLogs(page: number, pageSize: number, level: string | null, category: string | null, search: string | null, since: number | null) : Promise<Result<LogQueryResult, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_logs", { page, pageSize, level, category, search, since }) };
@@ -326,13 +667,19 @@ async clearLogs(beforeTimestamp: number | null) : Promise<Result<number, AppErro
 
 
 export const events = __makeEvents__<{
+batchMetadataEvent: BatchMetadataEvent,
 depInstallEvent: DepInstallEvent,
 globalDownloadEvent: GlobalDownloadEvent,
-newLogEvent: NewLogEvent
+libraryReconcileSummary: LibraryReconcileSummary,
+newLogEvent: NewLogEvent,
+playlistEntriesEvent: PlaylistEntriesEvent
 }>({
+batchMetadataEvent: "batch-metadata-event",
 depInstallEvent: "dep-install-event",
 globalDownloadEvent: "global-download-event",
-newLogEvent: "new-log-event"
+libraryReconcileSummary: "library-reconcile-summary",
+newLogEvent: "new-log-event",
+playlistEntriesEvent: "playlist-entries-event"
 })
 
 /** user-defined constants **/
@@ -341,7 +688,11 @@ newLogEvent: "new-log-event"
 
 /** user-defined types **/
 
-export type AppError = { FileError: string } | { Custom: string } | { BinaryNotFound: string } | { DownloadError: string } | { MetadataError: string } | { DatabaseError: string } | { NetworkError: string } | { InvalidUrl: string } | { DependencyInstallError: string } | { ChecksumError: string } | { NotImplemented: string }
+export type AppError = { FileError: string } | { Custom: string } | { BinaryNotFound: string } | { DownloadError: string } | { MetadataError: string } | { DatabaseError: string } | { NetworkError: string } | { InvalidUrl: string } | { DependencyInstallError: string } | { ChecksumError: string } | { NotImplemented: string } | { Duplicate: string } | 
+/**
+ * A failure the frontend can localize from its code and parameters
+ */
+{ Coded: ErrorDetail }
 export type AppSettings = { downloadPath: string; defaultQuality: string; maxConcurrent: number; filenameTemplate: string; cookieBrowser: string | null; autoUpdateYtdlp: boolean; useAdvancedTemplate: boolean; templateUploaderFolder: boolean; templateUploadDate: boolean; templateVideoId: boolean; language: string | null; theme: string | null; minimizeToTray: boolean | null; 
 /**
  * Dependency resolution mode: "external" (app-managed) or "system" (system PATH only)
@@ -350,7 +701,129 @@ depMode: string;
 /**
  * Whether the initial setup wizard has been completed
  */
-setupCompleted: boolean }
+setupCompleted: boolean; 
+/**
+ * Extra folders searched when relinking moved files (the download path is always searched)
+ */
+libraryRoots?: string[]; 
+/**
+ * Remove completed queue rows this many days after they finish (None keeps them)
+ */
+retentionCompletedDays?: number | null; 
+/**
+ * Remove failed and cancelled queue rows after this many days (None keeps them)
+ */
+retentionFailedDays?: number | null; 
+/**
+ * Keep at most this many history entries, newest first (None is unlimited)
+ */
+historyMaxEntries?: number | null; 
+/**
+ * Remove history entries whose files were found missing by reconciliation
+ */
+historyPruneMissing?: boolean; 
+/**
+ * What enqueueing does with a video that is already downloaded or queued
+ */
+dedupPolicy?: DedupPolicy; 
+/**
+ * How long fetched video metadata is reused before yt-dlp is asked again (0 disables the cache)
+ */
+metadataCacheTtlHours?: number }
+/**
+ * `manifest.json` stored at the root of a backup archive
+ */
+export type BackupManifest = { 
+/**
+ * Archive layout version, bumped if entries change incompatibly
+ */
+formatVersion: number; appVersion: string; 
+/**
+ * `ytdlp.db` schema version of the snapshot
+ */
+schemaVersion: number; createdAt: number; 
+/**
+ * Archive entries besides the manifest
+ */
+files: string[] }
+export type BatchEnqueueResult = { 
+/**
+ * Position of the request in the submitted batch
+ */
+index: number; videoUrl: string; status: BatchItemStatus; taskId: number | null; message: string | null }
+export type BatchItemStatus = "accepted" | 
+/**
+ * Accepted, but the video is already queued or in history (dedup policy "warn")
+ */
+"acceptedDuplicate" | 
+/**
+ * Already queued, repeated earlier in the batch or in history (dedup policy "skip")
+ */
+"duplicate" | "invalid"
+/**
+ * Result for one URL of a batch metadata fetch
+ */
+export type BatchMetadataEvent = { batchId: string; 
+/**
+ * Positions in the submitted list (several when the same video was pasted twice)
+ */
+indexes: number[]; url: string; info: VideoInfo | null; error: string | null; errorDetail: ErrorDetail | null }
+export type BatchMetadataSummary = { batchId: string; requested: number; 
+/**
+ * Distinct videos after deduplication
+ */
+unique: number; succeeded: number; failed: number; cancelled: boolean }
+export type ChannelIdentity = { channelId: string | null; name: string | null; 
+/**
+ * "@handle", when the channel has one
+ */
+handle: string | null; url: string; avatar: string | null; subscriberCount: number | null }
+export type ChannelStats = { channel: string; files: number; bytes: number }
+export type ChannelTab = "videos" | "shorts" | 
+/**
+ * The "Live" tab (current, past and upcoming streams)
+ */
+"streams" | "playlists"
+export type ChannelTabRequest = { 
+/**
+ * Any URL of the channel; its tab part is replaced by `tab`
+ */
+url: string; tab: ChannelTab; 
+/**
+ * Only uploads on or after this date (YYYYMMDD or relative, e.g. "today-1month")
+ */
+dateAfter?: string | null; 
+/**
+ * Only uploads on or before this date
+ */
+dateBefore?: string | null; 
+/**
+ * At most this many entries, newest first
+ */
+limit?: number | null }
+export type ChannelTabResult = { channel: ChannelIdentity; tab: ChannelTab; 
+/**
+ * The tab URL that was fetched
+ */
+url: string; 
+/**
+ * Videos, or playlists for the Playlists tab (`video_id` then holds the playlist ID)
+ */
+entries: PlaylistEntry[] }
+export type ChannelTabs = { channel: ChannelIdentity; tabs: ChannelTab[] }
+export type DedupPolicy = 
+/**
+ * Enqueue anyway
+ */
+"allow" | 
+/**
+ * Enqueue, but report the duplicate
+ */
+"warn" | 
+/**
+ * Refuse to enqueue unless the request sets `allowDuplicate`
+ */
+"skip"
 export type DepInfo = { installed: boolean; version: string | null; source: DepSource; path: string | null }
 export type DepInstallEvent = { depName: string; stage: DepInstallStage; percent: number; bytesDownloaded: number; bytesTotal: number | null; message: string | null }
 export type DepInstallStage = "Downloading" | "Verifying" | "Extracting" | "Completing" | "Failed"
@@ -361,27 +834,459 @@ export type DependencyStatus = { ytdlpInstalled: boolean; ytdlpVersion: string |
  * Diagnostic info when ytdlp check fails (path tried, error reason)
  */
 ytdlpDebug: string | null }
-export type DownloadRequest = { videoUrl: string; videoId: string; title: string; formatId: string; qualityLabel: string; outputDir: string | null; cookieBrowser: string | null }
-export type DownloadStatus = "pending" | "downloading" | "paused" | "completed" | "failed" | "cancelled"
-export type DownloadTaskInfo = { id: number; videoUrl: string; videoId: string; title: string; formatId: string; qualityLabel: string; outputPath: string; status: DownloadStatus; progress: number; speed: string | null; eta: string | null; errorMessage: string | null; createdAt: number; completedAt: number | null }
+/**
+ * A download group with aggregate counts and progress over its items.
+ */
+export type DownloadGroup = { id: number; sourceUrl: string; groupType: GroupType; title: string; options: GroupOptions | null; createdAt: number; totalCount: number; pendingCount: number; activeCount: number; pausedCount: number; 
+/**
+ * Includes items that completed with verification warnings
+ */
+completedCount: number; failedCount: number; cancelledCount: number; 
+/**
+ * Average progress across all items (finished items count as 100)
+ */
+progress: number }
+/**
+ * Snapshot of everything that determines how a queued download runs.
+ * Stored with each download row when it is enqueued, so later settings changes
+ * don't affect tasks that are already queued, retried or resumed.
+ */
+export type DownloadOptions = { formatId: string; 
+/**
+ * Full yt-dlp output template (directory joined with the filename template)
+ */
+outputTemplate: string; cookieBrowser: string | null; postProcessing: PostProcessingOptions; extraArgs: string[]; 
+/**
+ * Info JSON saved by the metadata fetch; passed to `--load-info-json` while fresh
+ */
+infoJson: string | null }
+export type DownloadRequest = { videoUrl: string; videoId: string; title: string; formatId: string; qualityLabel: string; outputDir: string | null; cookieBrowser: string | null; 
+/**
+ * Expected duration in seconds (from VideoInfo), used for post-download verification
+ */
+duration?: number | null; 
+/**
+ * Uploader/channel name (from VideoInfo), kept for history search
+ */
+channel?: string | null; channelUrl?: string | null; thumbnail?: string | null; 
+/**
+ * Upload date as reported by yt-dlp (YYYYMMDD)
+ */
+uploadDate?: string | null; 
+/**
+ * Source playlist, when the video was enqueued from one
+ */
+playlistId?: string | null; playlistTitle?: string | null; postProcessing?: PostProcessingOptions | null; 
+/**
+ * Additional yt-dlp arguments, validated by `security::sanitize_extra_args`
+ */
+extraArgs?: string[] | null; 
+/**
+ * yt-dlp `extractor_key` from VideoInfo; with `video_id` it forms the canonical identity
+ */
+extractorKey?: string | null; 
+/**
+ * Enqueue even if the dedup policy would skip this video
+ */
+allowDuplicate?: boolean | null }
+export type DownloadStatistics = { 
+/**
+ * Last 30 days, oldest first; days without downloads are omitted
+ */
+bytesPerDay: StatsPeriod[]; 
+/**
+ * Last 12 weeks (Monday-based), oldest first
+ */
+bytesPerWeek: StatsPeriod[]; 
+/**
+ * Last 12 months, oldest first
+ */
+bytesPerMonth: StatsPeriod[]; statusCounts: StatusCount[]; extractors: ExtractorStats[]; topChannels: ChannelStats[]; 
+/**
+ * Bytes per second over downloads with recorded timing
+ */
+averageThroughput: number | null; 
+/**
+ * Total size of history files not marked missing
+ */
+libraryBytes: number; libraryFiles: number }
+export type DownloadStatus = "pending" | "downloading" | "paused" | "completed" | 
+/**
+ * Download finished but post-download verification found problems
+ */
+"needsAttention" | "failed" | "cancelled"
+export type DownloadTaskInfo = { id: number; videoUrl: string; videoId: string; title: string; formatId: string; qualityLabel: string; outputPath: string; status: DownloadStatus; progress: number; speed: string | null; eta: string | null; errorMessage: string | null; createdAt: number; completedAt: number | null; duration: number | null; groupId: number | null; channel: string | null; channelUrl: string | null; thumbnail: string | null; uploadDate: string | null; playlistId: string | null; playlistTitle: string | null; 
+/**
+ * When the current (or last) attempt was claimed by the dispatcher
+ */
+startedAt: number | null; 
+/**
+ * Canonical identity (lowercased yt-dlp extractor key and video ID), if known
+ */
+canonicalExtractor: string | null; canonicalId: string | null; 
+/**
+ * Code and parameters behind `error_message`, for failures yt-dlp gave a known cause
+ */
+errorDetail: ErrorDetail | null; 
+/**
+ * Options captured at enqueue time; None for rows created before options were stored
+ */
+options: DownloadOptions | null }
 export type DuplicateCheckResult = { inHistory: boolean; inQueue: boolean; historyItem: HistoryItem | null; fileExists: boolean }
-export type FormatInfo = { formatId: string; ext: string; resolution: string | null; qualityLabel: string | null; filesize: number | null; vcodec: string | null; acodec: string | null; hasVideo: boolean; hasAudio: boolean }
+export type DuplicateFileGroup = { contentHash: string; fileSize: number | null; 
+/**
+ * Oldest first; the first item is the suggested one to keep
+ */
+items: HistoryItem[]; 
+/**
+ * Bytes that would be freed by removing every file except the first
+ */
+reclaimableBytes: number }
+/**
+ * Stable identifier for a user-facing failure. The frontend translates it as
+ * `errors.<code>`, substituting the detail's parameters.
+ */
+export type ErrorCode = "privateVideo" | 
+/**
+ * Needs an account: members-only, sign-in walls
+ */
+"loginRequired" | "videoUnavailable" | "liveNotStarted" | "unsupportedUrl" | "rateLimited" | "noFormats" | "formatUnavailable" | "ageRestricted" | "geoBlocked" | "cookieCopyFailed" | "networkError" | 
+/**
+ * Params: `status`
+ */
+"httpError" | "ffmpegMissing" | "postprocessFailed" | "diskFull" | "encodingError" | 
+/**
+ * Params: `seconds`
+ */
+"timeout" | "cancelled" | "ytdlpNotFound" | 
+/**
+ * The process died without an exit code (killed or crashed)
+ */
+"processCrashed" | 
+/**
+ * Params: `code`
+ */
+"exitCode" | 
+/**
+ * Unrecognized yt-dlp failure. Params: `detail` (last stderr line)
+ */
+"ytdlp"
+/**
+ * An error code with its message parameters and, separately, the raw stderr that
+ * led to it (already sanitized of local paths).
+ */
+export type ErrorDetail = { code: ErrorCode; params: Partial<{ [key in string]: string }>; stderr: string | null }
+/**
+ * Outcome counts for one site. Based on the queue, so rows removed with
+ * "clear completed" are not included.
+ */
+/**
+ * Stream types a downloaded file should contain
+ */
+export type ExpectedStreams = { video: boolean; audio: boolean }
+export type ExtractorStats = { extractor: string; succeeded: number; failed: number; cancelled: number; 
+/**
+ * succeeded / (succeeded + failed), or None when neither happened yet
+ */
+successRate: number | null }
+export type FormatInfo = { formatId: string; ext: string; resolution: string | null; qualityLabel: string | null; filesize: number | null; 
+/**
+ * Estimated size when the exact size is unknown (e.g. from bitrate and duration)
+ */
+filesizeApprox: number | null; vcodec: string | null; acodec: string | null; hasVideo: boolean; hasAudio: boolean; fps: number | null; 
+/**
+ * Total, video and audio bitrates in KBit/s
+ */
+tbr: number | null; vbr: number | null; abr: number | null; 
+/**
+ * "SDR", "HDR10", "HDR10+", "HDR12", "HLG" or "DV"
+ */
+dynamicRange: string | null; 
+/**
+ * Audio language code (e.g. "en", "ja")
+ */
+language: string | null; audioChannels: number | null; 
+/**
+ * Download protocol as reported by yt-dlp ("https", "m3u8_native", "http_dash_segments", ...)
+ */
+protocol: string | null; 
+/**
+ * DRM-protected formats can't be downloaded; yt-dlp's "maybe" counts as protected
+ */
+hasDrm: boolean }
 export type FullDependencyStatus = { ytdlp: DepInfo; ffmpeg: DepInfo; deno: DepInfo }
-export type GlobalDownloadEvent = { taskId: number; eventType: string; percent: number | null; speed: string | null; eta: string | null; filePath: string | null; fileSize: number | null; message: string | null }
-export type HistoryItem = { id: number; videoUrl: string; videoId: string; title: string; qualityLabel: string; format: string; filePath: string; fileSize: number | null; downloadedAt: number }
+export type GlobalDownloadEvent = { taskId: number; eventType: string; percent: number | null; speed: string | null; eta: string | null; filePath: string | null; fileSize: number | null; message: string | null; 
+/**
+ * Set on "error" events that have a localizable cause
+ */
+error: ErrorDetail | null }
+/**
+ * Options shared by every item of a group, recorded for display and re-use.
+ */
+export type GroupOptions = { formatId: string; qualityLabel: string; outputDir: string | null; cookieBrowser: string | null }
+export type GroupType = "playlist" | "channel"
+/**
+ * File presence as last recorded by verification (`verify_status`)
+ */
+export type HistoryFileFilter = "present" | "missing"
+/**
+ * Filters for the history query. All fields are optional and combined with AND.
+ */
+export type HistoryFilter = { 
+/**
+ * Full-text search over title, channel, video ID, URL and file path
+ */
+search: string | null; 
+/**
+ * Unix timestamps (seconds), inclusive
+ */
+downloadedAfter: number | null; downloadedBefore: number | null; 
+/**
+ * File size range in bytes, inclusive
+ */
+minSize: number | null; maxSize: number | null; format: string | null; qualityLabel: string | null; extractor: string | null; 
+/**
+ * Exact channel name
+ */
+channel: string | null; playlistId: string | null; fileStatus: HistoryFileFilter | null; 
+/**
+ * Defaults to date
+ */
+sortBy: HistorySort | null; 
+/**
+ * Defaults to descending
+ */
+sortAscending: boolean }
+export type HistoryItem = { id: number; videoUrl: string; videoId: string; title: string; qualityLabel: string; format: string; filePath: string; fileSize: number | null; downloadedAt: number; duration: number | null; verifyStatus: VerifyStatus; verifyIssues: string | null; verifiedAt: number | null; 
+/**
+ * SHA-256 of the file contents (hex), if computed
+ */
+contentHash: string | null; channel: string | null; 
+/**
+ * Site the download came from (e.g. "youtube", "vimeo")
+ */
+extractor: string | null; channelUrl: string | null; thumbnail: string | null; 
+/**
+ * Upload date as reported by yt-dlp (YYYYMMDD)
+ */
+uploadDate: string | null; playlistId: string | null; playlistTitle: string | null; 
+/**
+ * Wall-clock seconds the download took, for throughput statistics
+ */
+downloadSecs: number | null; 
+/**
+ * Canonical identity (lowercased yt-dlp extractor key and video ID), if known
+ */
+canonicalExtractor: string | null; canonicalId: string | null; 
+/**
+ * Streams the downloaded format carries, for re-verification (None for old rows)
+ */
+expectedStreams: ExpectedStreams | null }
 export type HistoryResult = { items: HistoryItem[]; totalCount: number; page: number; pageSize: number }
+export type HistorySort = "date" | "size" | "title"
+export type ImportEntryStatus = 
+/**
+ * Will be (or was) added to history
+ */
+"new" | 
+/**
+ * The file or its video ID is already in history
+ */
+"alreadyInHistory" | 
+/**
+ * Another scanned file has the same video ID
+ */
+"duplicateInScan" | 
+/**
+ * The ID was found but not the site it came from, so there is no URL to record
+ */
+"noSourceUrl"
+/**
+ * Where a scanned file's video ID came from
+ */
+export type ImportIdSource = 
+/**
+ * `<name>.info.json` written by `--write-info-json`
+ */
+"infoJson" | 
+/**
+ * A `[id]` suffix in the file name
+ */
+"fileName" | 
+/**
+ * Source URL in the container tags (`--embed-metadata`)
+ */
+"embeddedTags"
+export type LibraryImportEntry = { filePath: string; fileSize: number; videoId: string; videoUrl: string; title: string; channel: string | null; extractor: string | null; detectedBy: ImportIdSource; status: ImportEntryStatus }
+export type LibraryImportReport = { dryRun: boolean; scannedFiles: number; entries: LibraryImportEntry[]; 
+/**
+ * Media files whose video ID could not be determined
+ */
+unidentified: string[]; 
+/**
+ * Rows added to history (always 0 for a dry run)
+ */
+imported: number }
+export type LibraryReconcileSummary = { checked: number; 
+/**
+ * Rows whose file was not at the recorded path
+ */
+missing: number; relinked: RelinkedFile[]; 
+/**
+ * Missing rows left marked missing: not relinked, or already missing and not
+ * searched for again by a background run
+ */
+stillMissing: number }
+export type LibraryVerifySummary = { checked: number; ok: number; needsAttention: number; missing: number; unverified: number }
 export type LogEntry = { id: number; timestamp: number; level: string; category: string; message: string; details: string | null }
 export type LogQueryResult = { items: LogEntry[]; totalCount: number; page: number; pageSize: number }
 export type LogStats = { totalCount: number; errorCount: number; warnCount: number; infoCount: number }
+export type MoveLibraryResult = { movedFiles: number; updatedEntries: number; 
+/**
+ * History rows under the source folder whose file was already gone
+ */
+skipped: number; errors: string[] }
+export type NewDownloadGroup = { sourceUrl: string; groupType: GroupType; title: string; options: GroupOptions | null }
 export type NewLogEvent = { entry: LogEntry }
+/**
+ * A batch of entries from a streamed playlist fetch
+ */
+export type PlaylistEntriesEvent = { fetchId: string; entries: PlaylistEntry[]; 
+/**
+ * Entries received so far, including this batch
+ */
+received: number }
 export type PlaylistEntry = { url: string; videoId: string; title: string | null; duration: number | null; thumbnail: string | null }
 export type PlaylistResult = { playlistId: string; title: string; url: string; videoCount: number | null; channelName: string | null; entries: PlaylistEntry[] }
-export type QueueResult = { items: DownloadTaskInfo[]; totalCount: number; page: number; pageSize: number; activeCount: number; pendingCount: number; completedCount: number; failedCount: number; cancelledCount: number }
-export type QueueSummary = { activeItems: DownloadTaskInfo[]; recentCompleted: DownloadTaskInfo[]; activeCount: number; pendingCount: number; completedCount: number; totalCount: number }
+/**
+ * Result of a streamed playlist fetch; the entries themselves arrive as events
+ */
+export type PlaylistStreamSummary = { fetchId: string; playlistId: string; title: string; url: string; 
+/**
+ * Total reported by yt-dlp, or the received count once a fetch completes;
+ * None when unknown (e.g. a cancelled or incomplete fetch of a lazy playlist)
+ */
+videoCount: number | null; channelName: string | null; received: number; cancelled: boolean; 
+/**
+ * yt-dlp failed after some entries were received
+ */
+incomplete: boolean }
+export type PostProcessingOptions = { embedMetadata: boolean; embedThumbnail: boolean; embedSubtitles: boolean; 
+/**
+ * Subtitle languages for `--sub-langs` (e.g. "en,ko"); only used with embed_subtitles
+ */
+subLangs: string | null; 
+/**
+ * Extract audio to this format (`-x --audio-format`)
+ */
+extractAudioFormat: string | null; 
+/**
+ * Remux the video into this container (`--remux-video`)
+ */
+remuxFormat: string | null }
+export type QueueFileFormat = "json" | "csv" | 
+/**
+ * Plain text, one URL per line (yt-dlp batch-file style)
+ */
+"urlList"
+export type QueueImportReport = { accepted: number; rejected: RejectedImportLine[] }
+export type QueueResult = { items: DownloadTaskInfo[]; totalCount: number; page: number; pageSize: number; activeCount: number; pendingCount: number; completedCount: number; needsAttentionCount: number; failedCount: number; cancelledCount: number }
+export type QueueSummary = { activeItems: DownloadTaskInfo[]; recentCompleted: DownloadTaskInfo[]; activeCount: number; pendingCount: number; completedCount: number; needsAttentionCount: number; totalCount: number; 
+/**
+ * Groups with their aggregate state; only filled when the summary is requested by group
+ */
+groups: DownloadGroup[] }
 export type QuickMetadata = { videoId: string; title: string; channel: string; channelUrl: string; thumbnail: string }
+export type RejectedImportLine = { 
+/**
+ * Line number in the file (entry position for JSON imports), 1-based
+ */
+line: number; content: string; reason: string }
+/**
+ * How a missing history file was found again
+ */
+export type RelinkMatch = 
+/**
+ * The file name contains the video ID (e.g. yt-dlp's `[%(id)s]`)
+ */
+"videoId" | 
+/**
+ * Same size and SHA-256 as the recorded content hash
+ */
+"contentHash" | 
+/**
+ * The source URL is embedded in the container tags (`--embed-metadata`)
+ */
+"embeddedMetadata"
+export type RelinkedFile = { historyId: number; oldPath: string; newPath: string; matchedBy: RelinkMatch }
+export type RemoveDuplicatesResult = { removedEntries: number; deletedFiles: number; freedBytes: number; errors: string[] }
+export type RestoreReport = { manifest: BackupManifest; 
+/**
+ * One line per restored part, e.g. "ytdlp.db: restored"
+ */
+results: string[] }
+export type RetentionResult = { queueRowsRemoved: number; historyRemoved: number; logsRemoved: number; 
+/**
+ * Expired metadata cache entries
+ */
+metadataRemoved: number }
+/**
+ * Bytes and file count for one calendar period (`2024-05-31`, `2024-05` or, for weeks,
+ * the Monday they start on)
+ */
+export type StatsPeriod = { period: string; bytes: number; files: number }
+export type StatusCount = { status: DownloadStatus; count: number }
 export type UrlType = "video" | "channel" | "playlist" | "unknown"
-export type UrlValidation = { valid: boolean; urlType: UrlType; normalizedUrl: string | null; videoId: string | null }
-export type VideoInfo = { url: string; videoId: string; title: string; thumbnail: string; duration: number; uploadDate: string; channel: string; channelUrl: string; formats: FormatInfo[]; filesizeApprox: number | null }
+export type UrlValidation = { valid: boolean; urlType: UrlType; normalizedUrl: string | null; videoId: string | null; 
+/**
+ * YouTube playlist ID, also set for a video opened from a playlist (watch?v=X&list=Y);
+ * for other sites the collection ID when yt-dlp classified the URL
+ */
+playlistId: string | null; 
+/**
+ * Short extractor name ("youtube", "vimeo", ...); from the host unless yt-dlp was
+ * asked, then its lowercased extractor key ("bandcampalbum"), as in canonical identities
+ */
+extractor: string | null; 
+/**
+ * Playlist, channel, album or other multi-item page
+ */
+isCollection: boolean; 
+/**
+ * The extractor's ID for the item or collection, when known
+ */
+canonicalId: string | null }
+export type VerifyStatus = 
+/**
+ * Not probed yet, or ffprobe was unavailable
+ */
+"unverified" | "ok" | "needsAttention" | 
+/**
+ * File no longer exists at the recorded path
+ */
+"missing"
+export type VideoChapter = { title: string; 
+/**
+ * Seconds from the start of the video
+ */
+startTime: number; endTime: number }
+export type VideoInfo = { url: string; videoId: string; title: string; thumbnail: string; duration: number; uploadDate: string; channel: string; channelUrl: string; formats: FormatInfo[]; filesizeApprox: number | null; 
+/**
+ * yt-dlp extractor key (e.g. "Youtube"); pass it back in DownloadRequest for dedup
+ */
+extractorKey: string | null; description: string | null; chapters: VideoChapter[]; viewCount: number | null; likeCount: number | null; 
+/**
+ * Minimum viewer age (0 when unrestricted)
+ */
+ageLimit: number | null; tags: string[]; 
+/**
+ * "public", "unlisted", "private", "needs_auth", "subscriber_only" or "premium_only"
+ */
+availability: string | null; 
+/**
+ * "not_live", "is_live", "is_upcoming", "was_live" or "post_live"
+ */
+liveStatus: string | null }
 
 /** tauri-specta globals **/
 
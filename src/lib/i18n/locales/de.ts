@@ -99,8 +99,12 @@ const de: Record<string, string> = {
   "download.downloading": "Herunterladen... {percent}%",
   "download.downloadComplete": "Download abgeschlossen!",
   "download.downloadFailed": "Download fehlgeschlagen",
-  "download.skippedQueue": "{count} Video(s) übersprungen (bereits in der Warteschlange).",
-  "download.skippedExists": "{count} Video(s) übersprungen (bereits heruntergeladen).",
+  "download.batchQueued": "{count} Video(s) zur Warteschlange hinzugefügt.",
+  "download.batchDuplicates": "{count} Video(s) als Duplikat übersprungen.",
+  "download.batchInvalid": "{count} Eintrag/Einträge konnte(n) nicht hinzugefügt werden.",
+  "download.batchStatusAcceptedDuplicate": "Hinzugefügt (Duplikat)",
+  "download.batchStatusDuplicate": "Übersprungen (Duplikat)",
+  "download.batchStatusInvalid": "Ungültig",
   "download.redownload": "Erneut herunterladen",
   "download.cancel": "Abbrechen",
   "download.alreadyDownloaded": "\u201E{title}\u201C wurde bereits heruntergeladen.",
@@ -203,6 +207,31 @@ const de: Record<string, string> = {
   "logs.cleared": "Protokolle gelöscht",
   "logs.allLevels": "Alle",
   "logs.allCategories": "Alle",
+
+  // Errors (codes from the backend)
+  "errors.privateVideo": "Dieses Video ist privat.",
+  "errors.loginRequired": "Für dieses Video ist eine Anmeldung nötig. Wähle in den Einstellungen einen Cookie-Browser.",
+  "errors.videoUnavailable": "Dieses Video ist nicht verfügbar.",
+  "errors.liveNotStarted": "Dieser Livestream hat noch nicht begonnen.",
+  "errors.unsupportedUrl": "Diese URL wird nicht unterstützt.",
+  "errors.rateLimited": "Zu viele Anfragen. Versuche es später erneut.",
+  "errors.noFormats": "Keine Videoformate gefunden. Möglicherweise ist es ein Livestream.",
+  "errors.formatUnavailable": "Das angeforderte Format ist für dieses Video nicht verfügbar.",
+  "errors.ageRestricted": "Dieses Video ist altersbeschränkt. Wähle in den Einstellungen einen Cookie-Browser.",
+  "errors.geoBlocked": "Dieses Video ist in deinem Land nicht verfügbar.",
+  "errors.cookieCopyFailed": "Browser-Cookies konnten nicht gelesen werden. Schließe den Browser vollständig oder verwende Firefox-Cookies.",
+  "errors.networkError": "Netzwerkproblem. Überprüfe deine Internetverbindung.",
+  "errors.httpError": "Der Server antwortete mit HTTP-Fehler {status}.",
+  "errors.ffmpegMissing": "Für diesen Download wird ffmpeg benötigt, wurde aber nicht gefunden.",
+  "errors.postprocessFailed": "Die Nachbearbeitung mit ffmpeg ist fehlgeschlagen.",
+  "errors.diskFull": "Nicht genügend Speicherplatz.",
+  "errors.encodingError": "Download wegen eines Zeichenkodierungsfehlers fehlgeschlagen. Aktiviere „Beta: Unicode UTF-8 für die weltweite Sprachunterstützung verwenden“ in den Windows-Regionseinstellungen und starte neu.",
+  "errors.timeout": "Zeitüberschreitung nach {seconds} Sekunden.",
+  "errors.cancelled": "Abgebrochen.",
+  "errors.ytdlpNotFound": "yt-dlp nicht gefunden. Installiere es über Homebrew oder klicke auf Installieren.",
+  "errors.processCrashed": "Der yt-dlp-Prozess wurde unerwartet beendet.",
+  "errors.exitCode": "yt-dlp wurde mit Code {code} beendet.",
+  "errors.ytdlp": "yt-dlp-Fehler: {detail}",
 }
 
 export default de
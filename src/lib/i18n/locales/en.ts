@@ -102,8 +102,12 @@ const en: Record<string, string> = {
   "download.downloading": "Downloading... {percent}%",
   "download.downloadComplete": "Download complete!",
   "download.downloadFailed": "Download failed",
-  "download.skippedQueue": "{count} video(s) skipped (already in queue).",
-  "download.skippedExists": "{count} video(s) skipped (already downloaded).",
+  "download.batchQueued": "{count} video(s) queued.",
+  "download.batchDuplicates": "{count} video(s) skipped as duplicates.",
+  "download.batchInvalid": "{count} item(s) could not be queued.",
+  "download.batchStatusAcceptedDuplicate": "Queued (duplicate)",
+  "download.batchStatusDuplicate": "Skipped (duplicate)",
+  "download.batchStatusInvalid": "Invalid",
   "download.redownload": "Re-download",
   "download.cancel": "Cancel",
   "download.alreadyDownloaded": "\"{title}\" has already been downloaded.",
@@ -211,6 +215,31 @@ const en: Record<string, string> = {
   "logs.cleared": "Logs cleared",
   "logs.allLevels": "All",
   "logs.allCategories": "All",
+
+  // Errors (codes from the backend)
+  "errors.privateVideo": "This video is private.",
+  "errors.loginRequired": "This video requires signing in. Set a cookie browser in Settings.",
+  "errors.videoUnavailable": "This video is unavailable.",
+  "errors.liveNotStarted": "This live stream has not started yet.",
+  "errors.unsupportedUrl": "This URL is not supported.",
+  "errors.rateLimited": "Too many requests. Try again later.",
+  "errors.noFormats": "No video formats found. It may be a live stream.",
+  "errors.formatUnavailable": "The requested format is not available for this video.",
+  "errors.ageRestricted": "This video is age-restricted. Set a cookie browser in Settings.",
+  "errors.geoBlocked": "This video is not available in your country.",
+  "errors.cookieCopyFailed": "Could not read browser cookies. Close the browser completely or use Firefox cookies.",
+  "errors.networkError": "Network problem. Check your internet connection.",
+  "errors.httpError": "The server responded with HTTP error {status}.",
+  "errors.ffmpegMissing": "ffmpeg is required for this download but was not found.",
+  "errors.postprocessFailed": "Post-processing with ffmpeg failed.",
+  "errors.diskFull": "Not enough disk space.",
+  "errors.encodingError": "Download failed due to a text encoding error. Enable \"Beta: Use Unicode UTF-8 for worldwide language support\" in the Windows region settings and restart.",
+  "errors.timeout": "Timed out after {seconds} seconds.",
+  "errors.cancelled": "Cancelled.",
+  "errors.ytdlpNotFound": "yt-dlp not found. Please install via Homebrew or click Install.",
+  "errors.processCrashed": "The yt-dlp process exited unexpectedly.",
+  "errors.exitCode": "yt-dlp exited with code {code}.",
+  "errors.ytdlp": "yt-dlp error: {detail}",
 }
 
 export default en
@@ -99,8 +99,12 @@ const fr: Record<string, string> = {
   "download.downloading": "Téléchargement... {percent}%",
   "download.downloadComplete": "Téléchargement terminé !",
   "download.downloadFailed": "Échec du téléchargement",
-  "download.skippedQueue": "{count} vidéo(s) ignorée(s) (déjà dans la file d'attente).",
-  "download.skippedExists": "{count} vidéo(s) ignorée(s) (déjà téléchargée(s)).",
+  "download.batchQueued": "{count} vidéo(s) ajoutée(s) à la file d'attente.",
+  "download.batchDuplicates": "{count} vidéo(s) ignorée(s) (doublons).",
+  "download.batchInvalid": "{count} élément(s) n'ont pas pu être ajouté(s).",
+  "download.batchStatusAcceptedDuplicate": "Ajoutée (doublon)",
+  "download.batchStatusDuplicate": "Ignorée (doublon)",
+  "download.batchStatusInvalid": "Invalide",
   "download.redownload": "Retélécharger",
   "download.cancel": "Annuler",
   "download.alreadyDownloaded": "« {title} » a déjà été téléchargée.",
@@ -203,6 +207,31 @@ const fr: Record<string, string> = {
   "logs.cleared": "Journaux effacés",
   "logs.allLevels": "Tous",
   "logs.allCategories": "Toutes",
+
+  // Errors (codes from the backend)
+  "errors.privateVideo": "Cette vidéo est privée.",
+  "errors.loginRequired": "Cette vidéo nécessite une connexion. Choisissez un navigateur pour les cookies dans les paramètres.",
+  "errors.videoUnavailable": "Cette vidéo n'est pas disponible.",
+  "errors.liveNotStarted": "Ce direct n'a pas encore commencé.",
+  "errors.unsupportedUrl": "Cette URL n'est pas prise en charge.",
+  "errors.rateLimited": "Trop de requêtes. Réessayez plus tard.",
+  "errors.noFormats": "Aucun format vidéo trouvé. Il s'agit peut-être d'un direct.",
+  "errors.formatUnavailable": "Le format demandé n'est pas disponible pour cette vidéo.",
+  "errors.ageRestricted": "Cette vidéo est soumise à une limite d'âge. Choisissez un navigateur pour les cookies dans les paramètres.",
+  "errors.geoBlocked": "Cette vidéo n'est pas disponible dans votre pays.",
+  "errors.cookieCopyFailed": "Impossible de lire les cookies du navigateur. Fermez complètement le navigateur ou utilisez les cookies de Firefox.",
+  "errors.networkError": "Problème réseau. Vérifiez votre connexion Internet.",
+  "errors.httpError": "Le serveur a répondu avec l'erreur HTTP {status}.",
+  "errors.ffmpegMissing": "ffmpeg est requis pour ce téléchargement mais est introuvable.",
+  "errors.postprocessFailed": "Le post-traitement avec ffmpeg a échoué.",
+  "errors.diskFull": "Espace disque insuffisant.",
+  "errors.encodingError": "Échec du téléchargement dû à une erreur d'encodage. Activez « Bêta : Utiliser Unicode UTF-8 pour la prise en charge des langues du monde entier » dans les paramètres régionaux de Windows et redémarrez.",
+  "errors.timeout": "Délai dépassé après {seconds} secondes.",
+  "errors.cancelled": "Annulé.",
+  "errors.ytdlpNotFound": "yt-dlp introuvable. Installez-le via Homebrew ou cliquez sur Installer.",
+  "errors.processCrashed": "Le processus yt-dlp s'est arrêté de manière inattendue.",
+  "errors.exitCode": "yt-dlp s'est terminé avec le code {code}.",
+  "errors.ytdlp": "Erreur yt-dlp : {detail}",
 }
 
 export default fr
@@ -99,8 +99,12 @@ const ja: Record<string, string> = {
   "download.downloading": "ダウンロード中... {percent}%",
   "download.downloadComplete": "ダウンロード完了！",
   "download.downloadFailed": "ダウンロード失敗",
-  "download.skippedQueue": "{count}件の動画がスキップされました（すでにキューにあります）。",
-  "download.skippedExists": "{count}件の動画がスキップされました（すでにダウンロード済み）。",
+  "download.batchQueued": "{count}件の動画をキューに追加しました。",
+  "download.batchDuplicates": "{count}件の動画が重複のためスキップされました。",
+  "download.batchInvalid": "{count}件をキューに追加できませんでした。",
+  "download.batchStatusAcceptedDuplicate": "追加済み（重複）",
+  "download.batchStatusDuplicate": "スキップ（重複）",
+  "download.batchStatusInvalid": "無効",
   "download.redownload": "再ダウンロード",
   "download.cancel": "キャンセル",
   "download.alreadyDownloaded": "「{title}」はすでにダウンロード済みです。",
@@ -203,6 +207,31 @@ const ja: Record<string, string> = {
   "logs.cleared": "ログが削除されました",
   "logs.allLevels": "すべて",
   "logs.allCategories": "すべて",
+
+  // Errors (codes from the backend)
+  "errors.privateVideo": "この動画は非公開です。",
+  "errors.loginRequired": "この動画はログインが必要です。設定でCookieブラウザを設定してください。",
+  "errors.videoUnavailable": "この動画は利用できません。",
+  "errors.liveNotStarted": "このライブ配信はまだ開始されていません。",
+  "errors.unsupportedUrl": "このURLはサポートされていません。",
+  "errors.rateLimited": "リクエストが多すぎます。しばらくしてから再試行してください。",
+  "errors.noFormats": "動画フォーマットが見つかりません。ライブ配信の可能性があります。",
+  "errors.formatUnavailable": "要求されたフォーマットはこの動画では利用できません。",
+  "errors.ageRestricted": "年齢制限のある動画です。設定でCookieブラウザを設定してください。",
+  "errors.geoBlocked": "この動画はお住まいの国では利用できません。",
+  "errors.cookieCopyFailed": "ブラウザのCookieを読み取れません。ブラウザを完全に終了するか、FirefoxのCookieを使用してください。",
+  "errors.networkError": "ネットワークの問題です。インターネット接続を確認してください。",
+  "errors.httpError": "サーバーがHTTPエラー {status} を返しました。",
+  "errors.ffmpegMissing": "このダウンロードにはffmpegが必要ですが、見つかりません。",
+  "errors.postprocessFailed": "ffmpegによる後処理に失敗しました。",
+  "errors.diskFull": "ディスクの空き容量が不足しています。",
+  "errors.encodingError": "文字エンコードのエラーでダウンロードに失敗しました。Windowsの地域設定で「ベータ: ワールドワイド言語サポートで Unicode UTF-8 を使用」を有効にして再起動してください。",
+  "errors.timeout": "{seconds}秒後にタイムアウトしました。",
+  "errors.cancelled": "キャンセルされました。",
+  "errors.ytdlpNotFound": "yt-dlpが見つかりません。Homebrewでインストールするか、インストールをクリックしてください。",
+  "errors.processCrashed": "yt-dlpプロセスが予期せず終了しました。",
+  "errors.exitCode": "yt-dlpが終了コード {code} で終了しました。",
+  "errors.ytdlp": "yt-dlpエラー: {detail}",
 }
 
 export default ja
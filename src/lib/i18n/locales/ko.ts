@@ -99,8 +99,12 @@ const ko: Record<string, string> = {
   "download.downloading": "다운로드 중... {percent}%",
   "download.downloadComplete": "다운로드 완료!",
   "download.downloadFailed": "다운로드 실패",
-  "download.skippedQueue": "{count}개 영상이 이미 큐에 있어 건너뛰었습니다.",
-  "download.skippedExists": "{count}개 영상이 이미 다운로드되어 건너뛰었습니다.",
+  "download.batchQueued": "{count}개 영상을 대기열에 추가했습니다.",
+  "download.batchDuplicates": "{count}개 영상이 중복되어 건너뛰었습니다.",
+  "download.batchInvalid": "{count}개 항목을 대기열에 추가하지 못했습니다.",
+  "download.batchStatusAcceptedDuplicate": "추가됨 (중복)",
+  "download.batchStatusDuplicate": "건너뜀 (중복)",
+  "download.batchStatusInvalid": "잘못된 항목",
   "download.redownload": "다시 다운로드",
   "download.cancel": "취소",
   "download.alreadyDownloaded": "\"{title}\"은(는) 이미 다운로드한 적이 있습니다.",
@@ -203,6 +207,31 @@ const ko: Record<string, string> = {
   "logs.cleared": "로그가 삭제되었습니다",
   "logs.allLevels": "전체",
   "logs.allCategories": "전체",
+
+  // Errors (codes from the backend)
+  "errors.privateVideo": "비공개 비디오입니다. 접근할 수 없습니다.",
+  "errors.loginRequired": "로그인이 필요한 비디오입니다. 설정에서 쿠키 브라우저를 설정하세요.",
+  "errors.videoUnavailable": "이 비디오는 사용할 수 없습니다.",
+  "errors.liveNotStarted": "아직 시작되지 않은 라이브 스트림입니다.",
+  "errors.unsupportedUrl": "지원하지 않는 URL 형식입니다.",
+  "errors.rateLimited": "요청이 너무 많습니다. 잠시 후 다시 시도하세요.",
+  "errors.noFormats": "비디오 형식을 찾을 수 없습니다. 라이브 스트림일 수 있습니다.",
+  "errors.formatUnavailable": "요청한 형식을 이 비디오에서 사용할 수 없습니다.",
+  "errors.ageRestricted": "연령 제한 콘텐츠입니다. 설정에서 쿠키 브라우저를 설정하세요.",
+  "errors.geoBlocked": "이 국가에서는 볼 수 없는 비디오입니다.",
+  "errors.cookieCopyFailed": "브라우저 쿠키에 접근할 수 없습니다. 브라우저를 완전히 종료하거나, Firefox 쿠키를 사용하세요.",
+  "errors.networkError": "네트워크 연결 문제입니다. 인터넷 연결을 확인하세요.",
+  "errors.httpError": "서버가 HTTP 오류 {status}을(를) 반환했습니다.",
+  "errors.ffmpegMissing": "이 다운로드에는 ffmpeg가 필요하지만 찾을 수 없습니다.",
+  "errors.postprocessFailed": "ffmpeg 후처리에 실패했습니다.",
+  "errors.diskFull": "디스크 공간이 부족합니다.",
+  "errors.encodingError": "인코딩 오류로 다운로드에 실패했습니다. Windows 설정 → 시간 및 언어 → 관리 언어 설정 → 시스템 로캘 변경 → 'Beta: 세계 언어 지원을 위해 Unicode UTF-8 사용'을 활성화한 후 재시작하세요.",
+  "errors.timeout": "{seconds}초 후 시간이 초과되었습니다.",
+  "errors.cancelled": "취소되었습니다.",
+  "errors.ytdlpNotFound": "yt-dlp를 찾을 수 없습니다. Homebrew로 설치하거나 설치 버튼을 누르세요.",
+  "errors.processCrashed": "yt-dlp 프로세스가 예기치 않게 종료되었습니다.",
+  "errors.exitCode": "yt-dlp가 종료 코드 {code}(으)로 종료되었습니다.",
+  "errors.ytdlp": "yt-dlp 오류: {detail}",
 }
 
 export default ko
@@ -99,8 +99,12 @@ const zhCN: Record<string, string> = {
   "download.downloading": "下载中... {percent}%",
   "download.downloadComplete": "下载完成！",
   "download.downloadFailed": "下载失败",
-  "download.skippedQueue": "{count}个视频已跳过（已在队列中）。",
-  "download.skippedExists": "{count}个视频已跳过（已下载）。",
+  "download.batchQueued": "已将{count}个视频加入队列。",
+  "download.batchDuplicates": "{count}个视频因重复已跳过。",
+  "download.batchInvalid": "{count}个项目无法加入队列。",
+  "download.batchStatusAcceptedDuplicate": "已加入（重复）",
+  "download.batchStatusDuplicate": "已跳过（重复）",
+  "download.batchStatusInvalid": "无效",
   "download.redownload": "重新下载",
   "download.cancel": "取消",
   "download.alreadyDownloaded": "「{title}」已经下载过。",
@@ -203,6 +207,31 @@ const zhCN: Record<string, string> = {
   "logs.cleared": "日志已清除",
   "logs.allLevels": "全部",
   "logs.allCategories": "全部",
+
+  // Errors (codes from the backend)
+  "errors.privateVideo": "此视频为私享视频。",
+  "errors.loginRequired": "此视频需要登录。请在设置中选择 Cookie 浏览器。",
+  "errors.videoUnavailable": "此视频不可用。",
+  "errors.liveNotStarted": "此直播尚未开始。",
+  "errors.unsupportedUrl": "不支持此 URL。",
+  "errors.rateLimited": "请求过多，请稍后再试。",
+  "errors.noFormats": "未找到视频格式，可能是直播。",
+  "errors.formatUnavailable": "此视频不提供所请求的格式。",
+  "errors.ageRestricted": "此视频有年龄限制。请在设置中选择 Cookie 浏览器。",
+  "errors.geoBlocked": "此视频在您所在的国家/地区不可用。",
+  "errors.cookieCopyFailed": "无法读取浏览器 Cookie。请完全关闭浏览器或使用 Firefox 的 Cookie。",
+  "errors.networkError": "网络问题，请检查网络连接。",
+  "errors.httpError": "服务器返回 HTTP 错误 {status}。",
+  "errors.ffmpegMissing": "此下载需要 ffmpeg，但未找到。",
+  "errors.postprocessFailed": "ffmpeg 后处理失败。",
+  "errors.diskFull": "磁盘空间不足。",
+  "errors.encodingError": "由于文本编码错误，下载失败。请在 Windows 区域设置中启用“Beta 版: 使用 Unicode UTF-8 提供全球语言支持”并重启。",
+  "errors.timeout": "{seconds} 秒后超时。",
+  "errors.cancelled": "已取消。",
+  "errors.ytdlpNotFound": "未找到 yt-dlp。请通过 Homebrew 安装或点击安装。",
+  "errors.processCrashed": "yt-dlp 进程意外退出。",
+  "errors.exitCode": "yt-dlp 退出，代码 {code}。",
+  "errors.ytdlp": "yt-dlp 错误：{detail}",
 }
 
 export default zhCN
@@ -99,8 +99,12 @@ const zhTW: Record<string, string> = {
   "download.downloading": "下載中... {percent}%",
   "download.downloadComplete": "下載完成！",
   "download.downloadFailed": "下載失敗",
-  "download.skippedQueue": "{count}部影片已略過（已在佇列中）。",
-  "download.skippedExists": "{count}部影片已略過（已下載）。",
+  "download.batchQueued": "已將{count}部影片加入佇列。",
+  "download.batchDuplicates": "{count}部影片因重複已略過。",
+  "download.batchInvalid": "{count}個項目無法加入佇列。",
+  "download.batchStatusAcceptedDuplicate": "已加入（重複）",
+  "download.batchStatusDuplicate": "已略過（重複）",
+  "download.batchStatusInvalid": "無效",
   "download.redownload": "重新下載",
   "download.cancel": "取消",
   "download.alreadyDownloaded": "「{title}」已經下載過。",
@@ -203,6 +207,31 @@ const zhTW: Record<string, string> = {
   "logs.cleared": "日誌已清除",
   "logs.allLevels": "全部",
   "logs.allCategories": "全部",
+
+  // Errors (codes from the backend)
+  "errors.privateVideo": "此影片為私人影片。",
+  "errors.loginRequired": "此影片需要登入。請在設定中選擇 Cookie 瀏覽器。",
+  "errors.videoUnavailable": "此影片無法使用。",
+  "errors.liveNotStarted": "此直播尚未開始。",
+  "errors.unsupportedUrl": "不支援此 URL。",
+  "errors.rateLimited": "請求過多，請稍後再試。",
+  "errors.noFormats": "找不到影片格式，可能是直播。",
+  "errors.formatUnavailable": "此影片不提供所要求的格式。",
+  "errors.ageRestricted": "此影片有年齡限制。請在設定中選擇 Cookie 瀏覽器。",
+  "errors.geoBlocked": "此影片在您所在的國家/地區無法觀看。",
+  "errors.cookieCopyFailed": "無法讀取瀏覽器 Cookie。請完全關閉瀏覽器或使用 Firefox 的 Cookie。",
+  "errors.networkError": "網路問題，請檢查網路連線。",
+  "errors.httpError": "伺服器回傳 HTTP 錯誤 {status}。",
+  "errors.ffmpegMissing": "此下載需要 ffmpeg，但找不到。",
+  "errors.postprocessFailed": "ffmpeg 後製處理失敗。",
+  "errors.diskFull": "磁碟空間不足。",
+  "errors.encodingError": "由於文字編碼錯誤，下載失敗。請在 Windows 地區設定中啟用「Beta: 使用 Unicode UTF-8 提供全球語言支援」並重新啟動。",
+  "errors.timeout": "{seconds} 秒後逾時。",
+  "errors.cancelled": "已取消。",
+  "errors.ytdlpNotFound": "找不到 yt-dlp。請透過 Homebrew 安裝或點擊安裝。",
+  "errors.processCrashed": "yt-dlp 程序意外結束。",
+  "errors.exitCode": "yt-dlp 結束，代碼 {code}。",
+  "errors.ytdlp": "yt-dlp 錯誤：{detail}",
 }
 
 export default zhTW
@@ -1,6 +1,15 @@
+import type { ErrorDetail } from "$lib/bindings"
+import { t } from "$lib/i18n/index.svelte"
+
+/** Localized message for a coded backend error (`errors.<code>` with its params) */
+export function localizeError(detail: ErrorDetail): string {
+  return t(`errors.${detail.code}`, detail.params as Record<string, string>)
+}
+
 export function extractError(err: unknown): string {
   if (typeof err === "string") return err
   if (err && typeof err === "object") {
+    if ("Coded" in err) return localizeError((err as { Coded: ErrorDetail }).Coded)
     const values = Object.values(err)
     if (values.length > 0 && typeof values[0] === "string") return values[0]
   }